@@ -0,0 +1,65 @@
+//! Compares range and closest queries with spatial index against linear scan.
+//!
+//! Run with `cargo bench --bench spatial_index`.
+
+use rand::prelude::*;
+use rust_sc2::{distance::Distance, geometry::Point2, units::spatial::SpatialIndex};
+use std::{
+	hint::black_box,
+	time::{Duration, Instant},
+};
+
+const ITERATIONS: u32 = 200;
+
+fn measure<F: FnMut() -> usize>(mut f: F) -> Duration {
+	let start = Instant::now();
+	for _ in 0..ITERATIONS {
+		black_box(f());
+	}
+	start.elapsed() / ITERATIONS
+}
+
+fn main() {
+	let mut rng = StdRng::seed_from_u64(0);
+	for n in [50, 200, 400, 800] {
+		// Two armies fighting in the middle of the map
+		let points = (0..n)
+			.map(|i| {
+				let pos = Point2::new(rng.gen_range(60.0..100.0), rng.gen_range(60.0..100.0));
+				(i as u64, pos, 0.5)
+			})
+			.collect::<Vec<_>>();
+
+		// Every unit looks for units in range and for closest unit
+		let linear = measure(|| {
+			let mut found = 0;
+			for &(_, center, _) in &points {
+				found += points.iter().filter(|p| p.1.is_closer(6.0, center)).count();
+				found += points
+					.iter()
+					.map(|p| p.1.distance_squared(center.offset(3.0, 3.0)))
+					.min_by(|a, b| a.partial_cmp(b).unwrap())
+					.is_some() as usize;
+			}
+			found
+		});
+		let indexed = measure(|| {
+			let index = SpatialIndex::from_points(points.iter().copied());
+			let mut found = 0;
+			for &(_, center, _) in &points {
+				found += index.within(center, 6.0).count();
+				found += index
+					.nearest(center.offset(3.0, 3.0), |tag| Some(tag as usize))
+					.is_some() as usize;
+			}
+			found
+		});
+		println!(
+			"{:>4} units: linear {:>10.1?}, indexed {:>10.1?} (including build), speedup {:.1}x",
+			n,
+			linear,
+			indexed,
+			linear.as_secs_f64() / indexed.as_secs_f64()
+		);
+	}
+}
@@ -80,11 +80,15 @@ impl Player for ResearchBot {
     }
 }
 
+// Plays on ladder when started with ladder arguments and vs computer otherwise
 fn main() -> SC2Result<()> {
-    run_vs_computer(
+    run_from_args(
         &mut ResearchBot::default(),
         Computer::new(Race::Random, Difficulty::VeryEasy, None),
         "Simple64",
-        LaunchOptions::default(),
+        LaunchOptions {
+            log_dir: Some("logs"),
+            ..Default::default()
+        },
     )
 }
\ No newline at end of file
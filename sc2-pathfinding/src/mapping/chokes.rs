@@ -94,6 +94,7 @@ pub fn solve_chokes(points: &mut Vec<Vec<map_point::MapPoint>>,
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Choke {
     pub main_line: ((f32, f32), (f32, f32)),
     pub lines: Vec<((f32, f32), (f32, f32))>,
@@ -12,6 +12,8 @@ use sc2_proto::{
 	raw::{ActionRawUnitCommand_oneof_target as ProtoTarget, ActionRaw_oneof_action as ProtoRawAction},
 	sc2api::{Action as ProtoAction, ActionChat_Channel, ActionError as ProtoActionError},
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 // pub(crate) type Command = (u64, (AbilityId, Target, bool));
 
@@ -23,6 +25,7 @@ pub(crate) struct Commander {
 
 /// Target of ability used by unit.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Target {
 	/// Ability target is position (move, build, ...).
 	Pos(Point2),
@@ -34,6 +37,7 @@ pub enum Target {
 
 #[doc(hidden)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Action {
 	UnitCommand(AbilityId, Target, Vec<u64>, bool),
 	CameraMove(Point3),
@@ -130,6 +134,7 @@ impl FromProto<&ProtoAction> for Option<Action> {
 /// Structure used to analyze actions failed on previous game step.
 /// Stored in [`state.action_errors`](crate::game_state::GameState::action_errors).
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ActionError {
 	/// Tag of unit that was executing action.
 	pub unit: u64,
@@ -154,6 +159,7 @@ impl FromProto<&ProtoActionError> for ActionError {
 /// Result of executed action.
 #[allow(missing_docs)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionResult {
 	Success,
 	NotSupported,
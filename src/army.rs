@@ -0,0 +1,327 @@
+//! Grouping of army units into clusters and detection of fronts between them.
+//!
+//! [`Units::clusters`] groups any collection of units with DBSCAN and computes
+//! properties of every group. When [`track_army`](crate::bot::Bot::track_army) is enabled,
+//! clusters of bot's and opponent's army are also built every step into [`army`](crate::bot::Bot::army)
+//! field of bot, where they keep stable ids between steps and get velocities from movement of their units.
+//!
+//! ```
+//! let mut bot = rust_sc2::bot::Bot::default();
+//! bot.track_army = true;
+//!
+//! // Front closest to the enemy army, i.e. where fight happens or is about to happen
+//! if let Some(front) = bot.army.fronts().iter().min_by(|a, b| a.gap.total_cmp(&b.gap)) {
+//!     let (left, right) = front.line();
+//!     println!("Hold the line from {:?} to {:?}", left, right);
+//! }
+//! ```
+//!
+//! Clustering makes a range query for every unit, so it's better to enable
+//! [`use_spatial_index`](crate::bot::Bot::use_spatial_index) for big armies.
+
+use crate::{bot::Bot, distance::Distance, geometry::Point2, unit::Unit, units::Units, utils::dbscan};
+use rustc_hash::{FxHashMap, FxHashSet};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Group of units standing close to each other.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArmyCluster {
+	/// Id of the cluster, ids start from `0`.
+	/// Clusters tracked by [`ArmyTracker`] keep their ids between steps.
+	pub id: u32,
+	/// Tags of units in the cluster.
+	pub tags: Vec<u64>,
+	/// Average position of units.
+	pub center: Point2,
+	/// Distance from center to the furthest edge of unit in the cluster.
+	pub radius: f32,
+	/// Total minerals and vespene spent on units.
+	pub value: u32,
+	/// Total supply of units.
+	pub supply: f32,
+	/// Total dps of units against ground targets (without upgrades).
+	pub ground_dps: f32,
+	/// Total dps of units against air targets (without upgrades).
+	pub air_dps: f32,
+	/// Average velocity of units in game units per second.
+	/// Always zero for clusters not tracked by [`ArmyTracker`].
+	pub velocity: Point2,
+}
+impl ArmyCluster {
+	fn new(id: u32, tags: Vec<u64>, units: &Units) -> Self {
+		let members = tags.iter().filter_map(|tag| units.get(*tag)).collect::<Vec<_>>();
+		let center = members.iter().map(|u| u.position()).sum::<Point2>() / members.len().max(1) as f32;
+		let mut cluster = Self {
+			id,
+			center,
+			..Default::default()
+		};
+		for u in members {
+			let cost = u.cost();
+			cluster.radius = cluster.radius.max(u.distance(center) + u.radius());
+			cluster.value += cost.minerals + cost.vespene;
+			cluster.supply += u.supply_cost();
+			cluster.ground_dps += u.ground_dps();
+			cluster.air_dps += u.air_dps();
+		}
+		cluster.tags = tags;
+		cluster
+	}
+
+	/// Returns number of units in the cluster.
+	pub fn len(&self) -> usize {
+		self.tags.len()
+	}
+	/// Checks if the cluster has no units.
+	pub fn is_empty(&self) -> bool {
+		self.tags.is_empty()
+	}
+	/// Checks if unit with given tag is in the cluster.
+	pub fn contains(&self, tag: u64) -> bool {
+		self.tags.contains(&tag)
+	}
+	/// Returns speed of the cluster in game units per second.
+	pub fn speed(&self) -> f32 {
+		self.velocity.len()
+	}
+	/// Returns normalized direction of movement or `None` if cluster stands still.
+	pub fn direction(&self) -> Option<Point2> {
+		(self.speed() > f32::EPSILON).then(|| self.velocity.normalize())
+	}
+	/// Returns predicted position of the cluster's center after given amount of seconds.
+	pub fn predicted_center(&self, seconds: f32) -> Point2 {
+		self.center + self.velocity * seconds
+	}
+}
+
+/// Groups units into clusters. See [`Units::clusters`].
+pub(crate) fn clusters(units: &Units, eps: f32, min_points: usize) -> Vec<ArmyCluster> {
+	let tags = units.iter().map(|u| u.tag()).collect::<Vec<_>>();
+	let (groups, _) = dbscan(
+		&tags,
+		|tag| {
+			units
+				.closer(eps, units[*tag].position())
+				.iter()
+				.map(|u| u.tag())
+				.collect()
+		},
+		min_points,
+	);
+	groups
+		.into_iter()
+		.enumerate()
+		.map(|(id, tags)| ArmyCluster::new(id as u32, tags, units))
+		.collect()
+}
+
+/// Place between bot's cluster and the closest enemy cluster, where they're going to fight.
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Front {
+	/// Id of bot's cluster.
+	pub my_cluster: u32,
+	/// Id of enemy cluster.
+	pub enemy_cluster: u32,
+	/// Middle point between edges of clusters.
+	pub position: Point2,
+	/// Normalized direction from bot's cluster to enemy cluster.
+	pub normal: Point2,
+	/// Distance between edges of clusters (negative when clusters overlap).
+	pub gap: f32,
+	/// Length of the front line, i.e. diameter of the bigger cluster.
+	pub width: f32,
+}
+impl Front {
+	/// Returns ends of the front line, which goes through [`position`](Self::position)
+	/// perpendicular to [`normal`](Self::normal).
+	pub fn line(&self) -> (Point2, Point2) {
+		let half = self.normal.rotate90(false) * (self.width / 2.0);
+		(self.position - half, self.position + half)
+	}
+	/// Returns point on bot's side of the front at given distance from the front line,
+	/// e.g. to keep units in range of their weapons.
+	pub fn behind(&self, distance: f32) -> Point2 {
+		self.position - self.normal * distance
+	}
+}
+
+/// Finds fronts between every bot's cluster and the closest enemy cluster,
+/// which edge is not further than `max_gap` from bot's cluster edge.
+pub fn find_fronts(my: &[ArmyCluster], enemy: &[ArmyCluster], max_gap: f32) -> Vec<Front> {
+	my.iter()
+		.filter_map(|m| {
+			let gap = |e: &ArmyCluster| m.center.distance(e.center) - m.radius - e.radius;
+			let e = enemy
+				.iter()
+				.min_by(|a, b| gap(a).partial_cmp(&gap(b)).unwrap_or(Ordering::Equal))?;
+			let gap = gap(e);
+			if gap > max_gap {
+				return None;
+			}
+			let offset = e.center - m.center;
+			let distance = offset.len();
+			let normal = if distance > f32::EPSILON {
+				offset / distance
+			} else {
+				// Clusters are in the same place, face enemy's movement
+				e.direction().map_or(Point2::new(1.0, 0.0), |d| -d)
+			};
+			Some(Front {
+				my_cluster: m.id,
+				enemy_cluster: e.id,
+				position: m.center + normal * (m.radius + gap / 2.0),
+				normal,
+				gap,
+				width: m.radius.max(e.radius) * 2.0,
+			})
+		})
+		.collect()
+}
+
+/// Clusters of bot's and opponent's army, tracked between steps
+/// when [`track_army`](crate::bot::Bot::track_army) is enabled.
+///
+/// Units are matched with clusters of previous step, cluster keeps its id if it
+/// shares the most units with some previous cluster. When cluster splits, its id goes
+/// to the part with more units of the old cluster, other parts get new ids.
+#[derive(Debug, Clone)]
+pub struct ArmyTracker {
+	/// Maximum distance between neighboring units in a cluster. [Default: `6.0`]
+	pub eps: f32,
+	/// Minimum number of units in neighborhood of unit to start a cluster,
+	/// other units are ignored. [Default: `1`]
+	pub min_points: usize,
+	/// Maximum distance between edges of clusters to make a front. [Default: `20.0`]
+	pub front_distance: f32,
+	my: Vec<ArmyCluster>,
+	enemy: Vec<ArmyCluster>,
+	fronts: Vec<Front>,
+	positions: FxHashMap<u64, Point2>,
+	next_id: u32,
+	time: f32,
+}
+impl Default for ArmyTracker {
+	fn default() -> Self {
+		Self {
+			eps: 6.0,
+			min_points: 1,
+			front_distance: 20.0,
+			my: Default::default(),
+			enemy: Default::default(),
+			fronts: Default::default(),
+			positions: Default::default(),
+			next_id: 0,
+			time: 0.0,
+		}
+	}
+}
+impl ArmyTracker {
+	pub(crate) fn update(bot: &mut Bot) {
+		let my_units = bot.units.my.units.filter(|u| is_army(u));
+		let enemy_units = bot.units.enemy.units.filter(|u| is_army(u));
+
+		let tracker = &mut bot.army;
+		let dt = bot.time - tracker.time;
+		let my = my_units.clusters(tracker.eps, tracker.min_points);
+		let enemy = enemy_units.clusters(tracker.eps, tracker.min_points);
+		let previous = std::mem::take(&mut tracker.my);
+		tracker.my = tracker.track(my, &previous, &my_units, dt);
+		let previous = std::mem::take(&mut tracker.enemy);
+		tracker.enemy = tracker.track(enemy, &previous, &enemy_units, dt);
+		tracker.fronts = find_fronts(&tracker.my, &tracker.enemy, tracker.front_distance);
+
+		tracker.positions = my_units
+			.iter()
+			.chain(enemy_units.iter())
+			.map(|u| (u.tag(), u.position()))
+			.collect();
+		tracker.time = bot.time;
+	}
+
+	fn track(
+		&mut self,
+		mut clusters: Vec<ArmyCluster>,
+		previous: &[ArmyCluster],
+		units: &Units,
+		dt: f32,
+	) -> Vec<ArmyCluster> {
+		let owners = previous
+			.iter()
+			.flat_map(|c| c.tags.iter().map(move |tag| (*tag, c.id)))
+			.collect::<FxHashMap<u64, u32>>();
+
+		// Number of shared units for every pair of new and old clusters
+		let mut pairs = Vec::new();
+		for (i, c) in clusters.iter().enumerate() {
+			let mut shared = FxHashMap::<u32, usize>::default();
+			for tag in &c.tags {
+				if let Some(id) = owners.get(tag) {
+					*shared.entry(*id).or_default() += 1;
+				}
+			}
+			pairs.extend(shared.into_iter().map(|(id, count)| (count, i, id)));
+		}
+		pairs.sort_unstable_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+		let mut ids = vec![None; clusters.len()];
+		let mut taken = FxHashSet::default();
+		for (_, i, id) in pairs {
+			if ids[i].is_none() && taken.insert(id) {
+				ids[i] = Some(id);
+			}
+		}
+
+		for (c, id) in clusters.iter_mut().zip(ids) {
+			c.id = id.unwrap_or_else(|| {
+				let id = self.next_id;
+				self.next_id += 1;
+				id
+			});
+			if dt > 0.0 {
+				let moved = c
+					.tags
+					.iter()
+					.filter_map(|tag| Some(units.get(*tag)?.position() - *self.positions.get(tag)?))
+					.collect::<Vec<_>>();
+				if !moved.is_empty() {
+					c.velocity = moved.iter().copied().sum::<Point2>() / (moved.len() as f32 * dt);
+				}
+			}
+		}
+		clusters
+	}
+
+	/// Returns clusters of bot's army.
+	pub fn my(&self) -> &[ArmyCluster] {
+		&self.my
+	}
+	/// Returns clusters of opponent's army.
+	pub fn enemy(&self) -> &[ArmyCluster] {
+		&self.enemy
+	}
+	/// Returns fronts between bot's clusters and the closest enemy clusters.
+	pub fn fronts(&self) -> &[Front] {
+		&self.fronts
+	}
+	/// Returns bot's or opponent's cluster with given id.
+	pub fn get(&self, id: u32) -> Option<&ArmyCluster> {
+		self.my.iter().chain(&self.enemy).find(|c| c.id == id)
+	}
+	/// Returns cluster which contains unit with given tag.
+	pub fn cluster_of(&self, tag: u64) -> Option<&ArmyCluster> {
+		self.my.iter().chain(&self.enemy).find(|c| c.contains(tag))
+	}
+	/// Returns front of bot's cluster with given id.
+	pub fn front_of(&self, id: u32) -> Option<&Front> {
+		self.fronts.iter().find(|f| f.my_cluster == id)
+	}
+}
+
+// Units which take part in fights
+fn is_army(u: &Unit) -> bool {
+	!u.is_worker() && !u.is_structure() && (u.can_attack() || u.supply_cost() > 0.0)
+}
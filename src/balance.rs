@@ -0,0 +1,438 @@
+//! Balance values which aren't provided by the game, selected for the running version of SC2.
+//!
+//! [`BalanceTable`] contains damage bonuses of upgrades, speed modifiers, hits of units,
+//! radiuses of inhibitor zones and optionally costs and speeds of units.
+//! Built-in table is made of values from [`consts`](crate::consts), tables for other versions
+//! can be loaded from a file to [`balance_tables`](crate::bot::Bot::balance_tables) field of bot.
+//! At the start of the game table for the running base version is selected, and checked against
+//! data received from the game; found discrepancies are logged as warnings.
+//!
+//! Tables are stored in a text file, where every line changes one value:
+//! ```text
+//! # Lines before the first header change the built-in table.
+//! # Every header starts a table for given base version, which inherits values of the table
+//! # for the closest lower version. Tables can be listed in any order.
+//! [81009]
+//! # damage_bonus <unit> <target> <base bonus or -> [<attribute>=<bonus>...]
+//! damage_bonus Stalker Any - Armored=1
+//! # speed_upgrade, on_creep_speed_upgrade, off_creep_speed_upgrade <unit> <upgrade> <multiplier>
+//! speed_upgrade Zealot Charge 1.5
+//! # speed_on_creep <unit> <multiplier>
+//! speed_on_creep Queen 2.67
+//! # speed_buff <buff> <multiplier>
+//! speed_buff Stimpack 1.5
+//! # hits <unit> <health> <shield>
+//! hits Cyclone 120 0
+//! # inhibitor_zone <unit> <radius>
+//! inhibitor_zone InhibitorZoneSmall 4
+//! # cost <unit> <minerals> <vespene> <supply> <time in game loops>
+//! cost Marine 50 0 1 400
+//! # speed <unit> <movement speed without upgrades>
+//! speed Marine 3.15
+//! ```
+//! Names are the same as names of variants of ids, case insensitive.
+
+use crate::{
+	consts::{
+		DAMAGE_BONUS_PER_UPGRADE, INHIBITOR_ZONE_RADIUS, OFF_CREEP_SPEED_UPGRADES, ON_CREEP_SPEED_UPGRADES,
+		SPEED_BUFFS, SPEED_ON_CREEP, SPEED_UPGRADES, UNIT_HITS,
+	},
+	game_data::{Attribute, Cost, GameData, TargetType},
+	ids::{BuffId, UnitTypeId, UpgradeId},
+	utils::ids_by_name,
+};
+use rustc_hash::FxHashMap;
+use std::{
+	collections::{BTreeMap, HashMap},
+	error::Error,
+	fmt, fs, io,
+	path::Path,
+	str::FromStr,
+};
+
+/// Additional damage given by every level of weapon upgrade: bonus to base damage
+/// (`None` means `1`) and bonuses to damage against attributes.
+pub type DamageBonuses = HashMap<TargetType, (Option<u32>, HashMap<Attribute, u32>)>;
+
+/// Maximum difference of build time in game loops, which isn't reported by validation.
+const TIME_TOLERANCE: f32 = 0.5;
+// Maximum difference of speeds which isn't reported
+const SPEED_TOLERANCE: f32 = 0.01;
+
+/// Error of parsing balance tables.
+#[derive(Debug)]
+pub enum BalanceError {
+	/// Line of the file can't be parsed.
+	InvalidLine {
+		/// Number of the line (starting from `1`).
+		line: usize,
+		/// Content of the line.
+		content: String,
+	},
+	/// Failed to read the file.
+	Io(io::Error),
+}
+impl fmt::Display for BalanceError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidLine { line, content } => {
+				write!(f, "Invalid line {} in balance tables: {:?}", line, content)
+			}
+			Self::Io(e) => write!(f, "Failed to read balance tables: {}", e),
+		}
+	}
+}
+impl Error for BalanceError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Io(e) => Some(e),
+			_ => None,
+		}
+	}
+}
+impl From<io::Error> for BalanceError {
+	fn from(e: io::Error) -> Self {
+		Self::Io(e)
+	}
+}
+
+/// Balance values for some version of SC2.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceTable {
+	/// Minimal base version of SC2 the table is used for.
+	pub base_version: u32,
+	/// Damage bonuses given by weapon upgrades.
+	pub damage_bonus_per_upgrade: HashMap<UnitTypeId, DamageBonuses>,
+	/// Upgrades which increase speed of units and their multipliers.
+	pub speed_upgrades: HashMap<UnitTypeId, (UpgradeId, f32)>,
+	/// Upgrades which increase speed of units only on creep.
+	pub on_creep_speed_upgrades: HashMap<UnitTypeId, (UpgradeId, f32)>,
+	/// Upgrades which increase speed of units only off creep.
+	pub off_creep_speed_upgrades: HashMap<UnitTypeId, (UpgradeId, f32)>,
+	/// Multipliers of speed of units on creep.
+	pub speed_on_creep: HashMap<UnitTypeId, f32>,
+	/// Multipliers of speed given by buffs.
+	pub speed_buffs: HashMap<BuffId, f32>,
+	/// Maximum health and shield of units without upgrades.
+	pub hits: HashMap<UnitTypeId, (u32, u32)>,
+	/// Radiuses of Inhibitor Zones.
+	pub inhibitor_zone_radius: HashMap<UnitTypeId, f32>,
+	/// Expected costs of units, used only for validation. Empty in built-in table.
+	pub costs: HashMap<UnitTypeId, Cost>,
+	/// Expected movement speeds of units without upgrades, used only for validation. Empty in built-in table.
+	pub speeds: HashMap<UnitTypeId, f32>,
+}
+impl Default for BalanceTable {
+	fn default() -> Self {
+		Self {
+			base_version: 0,
+			damage_bonus_per_upgrade: DAMAGE_BONUS_PER_UPGRADE.clone(),
+			speed_upgrades: SPEED_UPGRADES.clone(),
+			on_creep_speed_upgrades: ON_CREEP_SPEED_UPGRADES.clone(),
+			off_creep_speed_upgrades: OFF_CREEP_SPEED_UPGRADES.clone(),
+			speed_on_creep: SPEED_ON_CREEP.clone(),
+			speed_buffs: SPEED_BUFFS.clone(),
+			hits: UNIT_HITS.clone(),
+			inhibitor_zone_radius: INHIBITOR_ZONE_RADIUS.clone(),
+			costs: HashMap::new(),
+			speeds: HashMap::new(),
+		}
+	}
+}
+impl BalanceTable {
+	/// Cross-checks the table with data received from the game.
+	/// Returns descriptions of found discrepancies: costs and speeds which don't match and unknown ids.
+	pub fn validate(&self, data: &GameData) -> Vec<String> {
+		let mut issues = vec![];
+		let check_unit = |unit: &UnitTypeId, issues: &mut Vec<String>| {
+			if !data.units.contains_key(unit) {
+				issues.push(format!("Unknown unit type {:?}", unit));
+			}
+		};
+
+		let mut costs = self.costs.iter().collect::<Vec<_>>();
+		costs.sort_unstable_by_key(|(unit, _)| **unit as u32);
+		for (unit, cost) in costs {
+			match data.units.get(unit) {
+				Some(unit_data) => {
+					let actual = unit_data.cost();
+					if actual.minerals != cost.minerals
+						|| actual.vespene != cost.vespene
+						|| actual.supply != cost.supply
+						|| (actual.time - cost.time).abs() > TIME_TOLERANCE
+					{
+						issues.push(format!(
+							"Cost of {:?} is {}/{}/{}/{} in table, but {}/{}/{}/{} in game",
+							unit,
+							cost.minerals,
+							cost.vespene,
+							cost.supply,
+							cost.time,
+							actual.minerals,
+							actual.vespene,
+							actual.supply,
+							actual.time
+						));
+					}
+				}
+				None => check_unit(unit, &mut issues),
+			}
+		}
+
+		let mut speeds = self.speeds.iter().collect::<Vec<_>>();
+		speeds.sort_unstable_by_key(|(unit, _)| **unit as u32);
+		for (unit, speed) in speeds {
+			match data.units.get(unit) {
+				Some(unit_data) => {
+					if (unit_data.movement_speed - speed).abs() > SPEED_TOLERANCE {
+						issues.push(format!(
+							"Speed of {:?} is {} in table, but {} in game",
+							unit, speed, unit_data.movement_speed
+						));
+					}
+				}
+				None => check_unit(unit, &mut issues),
+			}
+		}
+
+		let mut units = (self.damage_bonus_per_upgrade.keys())
+			.chain(self.speed_on_creep.keys())
+			.chain(self.hits.keys())
+			.chain(self.inhibitor_zone_radius.keys())
+			.collect::<Vec<_>>();
+		let mut upgrades = vec![];
+		for map in [
+			&self.speed_upgrades,
+			&self.on_creep_speed_upgrades,
+			&self.off_creep_speed_upgrades,
+		] {
+			for (unit, (upgrade, _)) in map {
+				units.push(unit);
+				upgrades.push(upgrade);
+			}
+		}
+		units.sort_unstable_by_key(|unit| **unit as u32);
+		units.dedup();
+		for unit in units {
+			check_unit(unit, &mut issues);
+		}
+		upgrades.sort_unstable_by_key(|upgrade| **upgrade as u32);
+		upgrades.dedup();
+		for upgrade in upgrades {
+			if !data.upgrades.contains_key(upgrade) {
+				issues.push(format!("Unknown upgrade {:?}", upgrade));
+			}
+		}
+		let mut buffs = self.speed_buffs.keys().collect::<Vec<_>>();
+		buffs.sort_unstable_by_key(|buff| **buff as u32);
+		for buff in buffs {
+			if !data.buffs.contains_key(buff) {
+				issues.push(format!("Unknown buff {:?}", buff));
+			}
+		}
+		issues
+	}
+}
+
+/// Balance tables for different versions of SC2, sorted by base version.
+/// Always contains built-in table for base version `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceTables(Vec<BalanceTable>);
+impl Default for BalanceTables {
+	fn default() -> Self {
+		Self(vec![BalanceTable::default()])
+	}
+}
+impl BalanceTables {
+	/// Adds table, replacing existing one with the same base version.
+	pub fn insert(&mut self, table: BalanceTable) {
+		match self
+			.0
+			.binary_search_by_key(&table.base_version, |t| t.base_version)
+		{
+			Ok(i) => self.0[i] = table,
+			Err(i) => self.0.insert(i, table),
+		}
+	}
+	/// Returns table with given base version.
+	pub fn get(&self, base_version: u32) -> Option<&BalanceTable> {
+		self.0.iter().find(|t| t.base_version == base_version)
+	}
+	/// Selects table for given base version of SC2: the one with the highest base version not above given.
+	pub fn select(&self, base_version: u32) -> &BalanceTable {
+		self.0
+			.iter()
+			.rev()
+			.find(|t| t.base_version <= base_version)
+			.unwrap_or(&self.0[0])
+	}
+	/// Returns all tables sorted by base version.
+	pub fn tables(&self) -> &[BalanceTable] {
+		&self.0
+	}
+
+	/// Parses tables in format described in [module documentation](self).
+	pub fn parse(text: &str) -> Result<Self, BalanceError> {
+		// Lines are grouped by versions first, so tables can be listed in any order
+		let mut versions = BTreeMap::<u32, Vec<(usize, &str)>>::new();
+		let mut version = 0;
+		for (i, line) in text.lines().enumerate() {
+			let line = line.split('#').next().unwrap_or_default().trim();
+			if line.is_empty() {
+				continue;
+			}
+			match line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+				Some(header) => {
+					version = header.trim().parse().map_err(|_| invalid_line(i, line))?;
+					versions.entry(version).or_default();
+				}
+				None => versions.entry(version).or_default().push((i, line)),
+			}
+		}
+
+		let names = Names::new();
+		let mut tables = Self::default();
+		for (base_version, lines) in versions {
+			let mut table = tables.select(base_version).clone();
+			table.base_version = base_version;
+			for (i, line) in lines {
+				names
+					.apply(&mut table, line)
+					.ok_or_else(|| invalid_line(i, line))?;
+			}
+			tables.insert(table);
+		}
+		Ok(tables)
+	}
+	/// Loads tables from file.
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, BalanceError> {
+		Self::parse(&fs::read_to_string(path)?)
+	}
+}
+
+// Ids mapped to their names in lower case
+struct Names {
+	units: FxHashMap<String, UnitTypeId>,
+	upgrades: FxHashMap<String, UpgradeId>,
+	buffs: FxHashMap<String, BuffId>,
+}
+impl Names {
+	fn new() -> Self {
+		Self {
+			units: ids_by_name(),
+			upgrades: ids_by_name(),
+			buffs: ids_by_name(),
+		}
+	}
+	fn unit(&self, name: &str) -> Option<UnitTypeId> {
+		self.units.get(&name.to_lowercase()).copied()
+	}
+	fn upgrade(&self, name: &str) -> Option<UpgradeId> {
+		self.upgrades.get(&name.to_lowercase()).copied()
+	}
+	fn buff(&self, name: &str) -> Option<BuffId> {
+		self.buffs.get(&name.to_lowercase()).copied()
+	}
+
+	// Changes value of the table described by line
+	fn apply(&self, table: &mut BalanceTable, line: &str) -> Option<()> {
+		let words = line.split_whitespace().collect::<Vec<_>>();
+		match words.as_slice() {
+			["damage_bonus", unit, target, base, attributes @ ..] => {
+				let base = match *base {
+					"-" => None,
+					base => Some(base.parse().ok()?),
+				};
+				let attributes = attributes
+					.iter()
+					.map(|a| {
+						let (attribute, bonus) = a.split_once('=')?;
+						Some((parse_attribute(attribute)?, bonus.parse().ok()?))
+					})
+					.collect::<Option<HashMap<_, _>>>()?;
+				table
+					.damage_bonus_per_upgrade
+					.entry(self.unit(unit)?)
+					.or_default()
+					.insert(parse_target(target)?, (base, attributes));
+			}
+			[kind @ ("speed_upgrade" | "on_creep_speed_upgrade" | "off_creep_speed_upgrade"), unit, upgrade, multiplier] =>
+			{
+				let map = match *kind {
+					"speed_upgrade" => &mut table.speed_upgrades,
+					"on_creep_speed_upgrade" => &mut table.on_creep_speed_upgrades,
+					_ => &mut table.off_creep_speed_upgrades,
+				};
+				map.insert(self.unit(unit)?, (self.upgrade(upgrade)?, parse(multiplier)?));
+			}
+			["speed_on_creep", unit, multiplier] => {
+				table.speed_on_creep.insert(self.unit(unit)?, parse(multiplier)?);
+			}
+			["speed_buff", buff, multiplier] => {
+				table.speed_buffs.insert(self.buff(buff)?, parse(multiplier)?);
+			}
+			["hits", unit, health, shield] => {
+				table
+					.hits
+					.insert(self.unit(unit)?, (parse(health)?, parse(shield)?));
+			}
+			["inhibitor_zone", unit, radius] => {
+				table
+					.inhibitor_zone_radius
+					.insert(self.unit(unit)?, parse(radius)?);
+			}
+			["cost", unit, minerals, vespene, supply, time] => {
+				let cost = Cost {
+					minerals: parse(minerals)?,
+					vespene: parse(vespene)?,
+					supply: parse(supply)?,
+					time: parse(time)?,
+				};
+				table.costs.insert(self.unit(unit)?, cost);
+			}
+			["speed", unit, speed] => {
+				table.speeds.insert(self.unit(unit)?, parse(speed)?);
+			}
+			_ => return None,
+		}
+		Some(())
+	}
+}
+
+fn invalid_line(index: usize, content: &str) -> BalanceError {
+	BalanceError::InvalidLine {
+		line: index + 1,
+		content: content.to_string(),
+	}
+}
+
+fn parse<T: FromStr>(s: &str) -> Option<T> {
+	s.parse().ok()
+}
+
+fn parse_target(s: &str) -> Option<TargetType> {
+	match s.to_lowercase().as_str() {
+		"ground" => Some(TargetType::Ground),
+		"air" => Some(TargetType::Air),
+		"any" => Some(TargetType::Any),
+		_ => None,
+	}
+}
+
+fn parse_attribute(s: &str) -> Option<Attribute> {
+	let attribute = match s.to_lowercase().as_str() {
+		"light" => Attribute::Light,
+		"armored" => Attribute::Armored,
+		"biological" => Attribute::Biological,
+		"mechanical" => Attribute::Mechanical,
+		"robotic" => Attribute::Robotic,
+		"psionic" => Attribute::Psionic,
+		"massive" => Attribute::Massive,
+		"structure" => Attribute::Structure,
+		"hover" => Attribute::Hover,
+		"heroic" => Attribute::Heroic,
+		"summoned" => Attribute::Summoned,
+		_ => return None,
+	};
+	Some(attribute)
+}
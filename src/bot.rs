@@ -3,7 +3,10 @@
 use crate::{
 	action::{Action, ActionResult, Commander, Target},
 	api::API,
+	army::ArmyTracker,
+	balance::{BalanceTable, BalanceTables},
 	client::SC2Result,
+	commands::ChatCommands,
 	consts::{RaceValues, FRAMES_PER_SECOND, INHIBITOR_IDS, RACE_VALUES, TECH_ALIAS, UNIT_ALIAS},
 	debug::{DebugCommand, Debugger},
 	dicts::get_upgrade_for_ability,
@@ -11,25 +14,39 @@ use crate::{
 	game_data::{Cost, GameData},
 	game_info::GameInfo,
 	game_state::Effect,
-	game_state::{Alliance, GameState},
+	game_state::{apply_observation, Alliance, GameState},
 	geometry::{Point2, Point3},
 	ids::{AbilityId, BuffId, EffectId, UnitTypeId, UpgradeId},
+	layout::{self, BaseLayout, LayoutOptions},
+	placement::{self, PlacementGrid},
 	player::Race,
+	production::{self, Production, ProductionReport},
 	ramp::{Ramp, Ramps},
+	scouting::{self, ScoutTarget, Scouting},
+	snapshot::Snapshot,
+	spells::{self, AreaSpell, SpellOptions, SpellTarget},
+	stats::{UnitStats, UpgradeLevels},
+	strategy::{StrategyEstimate, StrategyInference},
+	supply::{self, SupplyForecast, SupplyTimeline},
+	tech_tree::{TechItem, TechPlan},
+	triggers::{self, Triggers},
 	unit::{DataForUnit, SharedUnitData, Unit},
 	units::{AllUnits, Units},
 	utils::{dbscan, range_query},
-	FromProto, IntoProto,
+	wall::{self, Wall},
+	Event, FromProto, IntoProto,
 };
 use indexmap::IndexSet;
 use num_traits::ToPrimitive;
 use rand::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
 use sc2_proto::{
-	query::{RequestQueryBuildingPlacement, RequestQueryPathing},
-	sc2api::Request,
+	query::{RequestQueryBuildingPlacement, RequestQueryPathing, ResponseQueryAvailableAbilities},
+	sc2api::{Request, ResponseObservation},
 };
 use std::{fmt, hash::BuildHasherDefault, process::Child};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
@@ -78,6 +95,19 @@ pub(crate) type Writer<'a, T> = RwLockWriteGuard<'a, T>;
 #[cfg(not(feature = "rayon"))]
 pub(crate) type Writer<'a, T> = RefMut<'a, T>;
 
+/// Bound of closures stored in bot (triggers, chat commands),
+/// which requires them to be `Send + Sync` when `rayon` feature is enabled.
+#[cfg(feature = "rayon")]
+pub trait MaybeSendSync: Send + Sync {}
+#[cfg(feature = "rayon")]
+impl<T: Send + Sync> MaybeSendSync for T {}
+/// Bound of closures stored in bot (triggers, chat commands),
+/// which requires them to be `Send + Sync` when `rayon` feature is enabled.
+#[cfg(not(feature = "rayon"))]
+pub trait MaybeSendSync {}
+#[cfg(not(feature = "rayon"))]
+impl<T> MaybeSendSync for T {}
+
 pub(crate) trait Locked<T> {
 	fn read_lock(&self) -> Reader<'_, T>;
 	fn write_lock(&self) -> Writer<'_, T>;
@@ -174,6 +204,7 @@ impl LockOwned<u32> for LockU32 {
 
 /// Information about an expansion location.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Expansion {
 	/// Placement position for townhall.
 	pub loc: Point2,
@@ -202,6 +233,11 @@ pub struct PlacementOptions {
 	pub random: bool,
 	/// Filter positions where addon can fit. [Default: `false`]
 	pub addon: bool,
+	/// Confirm positions which passed local [`placement`](Bot::placement) check
+	/// with query to the server.
+	/// If `false`, positions are checked only on local grid,
+	/// which is faster but may be less accurate. [Default: `true`]
+	pub confirm: bool,
 }
 impl Default for PlacementOptions {
 	fn default() -> Self {
@@ -210,6 +246,7 @@ impl Default for PlacementOptions {
 			step: 2,
 			random: false,
 			addon: false,
+			confirm: true,
 		}
 	}
 }
@@ -395,6 +432,13 @@ pub struct Bot {
 	pub(crate) game_left: bool,
 	#[doc(hidden)]
 	pub disable_fog: bool,
+	/// Build spatial index over units every step, which speeds up range and closest queries
+	/// of [`Units`] on big amounts of units. See [`spatial`](crate::units::spatial) module for details.
+	/// [Default: `false`]
+	pub use_spatial_index: bool,
+	/// Track clusters of bot's and opponent's army every step in [`army`](Self::army) field.
+	/// See [`army`](crate::army) module for details. [Default: `false`]
+	pub track_army: bool,
 	/// Actual race of your bot.
 	pub race: Race,
 	/// Requested race of your opponent.
@@ -413,6 +457,12 @@ pub struct Bot {
 	pub game_info: GameInfo,
 	/// Constant information about abilities, unit types, upgrades, buffs and effects.
 	pub game_data: Rs<GameData>,
+	/// Balance values for different versions of SC2, the table for running version
+	/// is selected at the start of the game. See [`balance`](crate::balance) module for details.
+	pub balance_tables: BalanceTables,
+	pub(crate) balance: Rs<BalanceTable>,
+	/// Base build of running SC2 client.
+	pub base_build: u32,
 	/// Information about current state, updated each step.
 	pub state: GameState,
 	/// Values, which depend on bot's race
@@ -461,15 +511,35 @@ pub struct Bot {
 	max_cooldowns: Rw<FxHashMap<UnitTypeId, f32>>,
 	pub(crate) last_units_hits: Rw<FxHashMap<u64, u32>>,
 	pub(crate) last_units_seen: Rw<FxHashMap<u64, u32>>,
+	pub(crate) last_units_positions: Rw<FxHashMap<u64, Point2>>,
+	pub(crate) last_positions_loop: Rs<LockU32>,
 	/// Obstacles on map which block vision of ground units, but still pathable.
 	pub vision_blockers: Vec<Point2>,
 	/// Ramps on map.
 	pub ramps: Ramps,
 	/// Pathfinding map for advanced pathfinding operations.
 	pub pathfinding_map: Option<sc2pathfinding::Map>,
+	/// Triggers called by the runner on events, alerts and timers.
+	pub triggers: Triggers,
+	/// Chat commands parsed from messages of other players, used for interactive debugging.
+	pub commands: ChatCommands,
+	/// Settings of production manager, which is run with [`run_production`](Self::run_production).
+	pub production: Production,
+	/// Information about when different parts of the map were seen last time.
+	pub scouting: Scouting,
+	/// Observations of opponent's units used to estimate its strategy.
+	pub strategy: StrategyInference,
+	/// Local grid used to check placement of buildings without queries to the server.
+	pub placement: PlacementGrid,
+	/// Clusters of bot's and opponent's army and fronts between them.
+	/// Updated only when [`track_army`](Self::track_army) is enabled.
+	pub army: ArmyTracker,
 	enemy_upgrades: Rw<FxHashSet<UpgradeId>>,
 	pub(crate) owned_tags: FxHashSet<u64>,
 	pub(crate) under_construction: FxHashSet<u64>,
+	pub(crate) seen_enemy_tags: FxHashSet<u64>,
+	// Locations of enemy expansions and tags of their townhalls
+	pub(crate) enemy_expansions: FxHashMap<Point2, u64>,
 	pub(crate) available_frames: Rw<FxHashMap<u64, u32>>,
 }
 
@@ -491,6 +561,91 @@ impl Bot {
 	pub fn game_step(&self) -> u32 {
 		self.game_step.get_locked()
 	}
+	/// Balance table selected for running version of SC2.
+	pub fn balance(&self) -> &BalanceTable {
+		&self.balance
+	}
+	/// Checks if given position is inside of any visible Inhibitor Zone.
+	/// Radiuses of zones are taken from [`balance`](Self::balance) table.
+	pub fn in_inhibitor_zone(&self, pos: Point2) -> bool {
+		self.units.inhibitor_zones.iter().any(|zone| {
+			self.balance
+				.inhibitor_zone_radius
+				.get(&zone.type_id())
+				.is_some_and(|radius| zone.is_closer(*radius, pos))
+		})
+	}
+	/// Returns effective stats of unit of given type with upgrades of bot or known upgrades of opponent.
+	/// Useful to estimate units which weren't seen yet. Buffs aren't taken into account.
+	pub fn unit_stats(&self, unit_type: UnitTypeId, enemy: bool) -> Option<UnitStats> {
+		let upgrades = if enemy {
+			self.enemy_upgrades.read_lock()
+		} else {
+			self.state.observation.raw.upgrades.read_lock()
+		};
+		let data = self.game_data.units.get(&unit_type)?;
+		let levels = UpgradeLevels::of(unit_type, data.race, &data.attributes, &upgrades);
+		UnitStats::new(
+			unit_type,
+			&self.game_data,
+			&self.balance,
+			&upgrades,
+			levels,
+			&Default::default(),
+		)
+	}
+	/// Constructs hypothetical unit of given type, owned by given player and placed at given position.
+	/// Unit has full health and known upgrades of its owner (i.e. [`enemy_upgrades`] for opponent),
+	/// so weapon and range methods like [`dps_vs`] and [`in_real_range`] work on it without observation.
+	///
+	/// Returns `None` if there's no data for given type, or its hits aren't in [`balance`](Self::balance) table.
+	///
+	/// [`enemy_upgrades`]: Self::enemy_upgrades
+	/// [`dps_vs`]: Unit::dps_vs
+	/// [`in_real_range`]: Unit::in_real_range
+	pub fn synthetic_unit(&self, unit_type: UnitTypeId, owner: u32, position: Point2) -> Option<Unit> {
+		let alliance = self.synthetic_alliance(owner);
+		Unit::synthetic(self.synthetic_data(alliance, None), alliance, owner, unit_type, position)
+	}
+	/// Same as [`synthetic_unit`](Self::synthetic_unit), but with given set of upgrades.
+	pub fn synthetic_unit_with_upgrades(
+		&self,
+		unit_type: UnitTypeId,
+		owner: u32,
+		position: Point2,
+		upgrades: &FxHashSet<UpgradeId>,
+	) -> Option<Unit> {
+		let alliance = self.synthetic_alliance(owner);
+		Unit::synthetic(
+			self.synthetic_data(alliance, Some(upgrades)),
+			alliance,
+			owner,
+			unit_type,
+			position,
+		)
+	}
+	fn synthetic_alliance(&self, owner: u32) -> Alliance {
+		if owner == self.player_id {
+			Alliance::Own
+		} else {
+			Alliance::Enemy
+		}
+	}
+	fn synthetic_data(&self, alliance: Alliance, upgrades: Option<&FxHashSet<UpgradeId>>) -> SharedUnitData {
+		// Static data is taken from bot itself, so units can be constructed before the game started
+		let mut data = DataForUnit::clone(&self.data_for_unit);
+		data.game_data = Rs::clone(&self.game_data);
+		data.balance = Rs::clone(&self.balance);
+		if let Some(upgrades) = upgrades {
+			let upgrades = Rs::new(Rl::new(upgrades.clone()));
+			if alliance.is_mine() {
+				data.upgrades = upgrades;
+			} else {
+				data.enemy_upgrades = upgrades;
+			}
+		}
+		Rs::new(data)
+	}
 
 	/// Converts rust-sc2 PixelMap to Vec<Vec<usize>> format for sc2-pathfinding
 	fn pixel_map_to_vec(pixel_map: &crate::pixel_map::PixelMap) -> Vec<Vec<usize>> {
@@ -813,6 +968,49 @@ impl Bot {
 			})
 			.unwrap_or(0.0)
 	}
+	/// Returns ordered chain of missing structures, addons and researches required to get given
+	/// unit or upgrade, with their costs and estimated timings based on current income.
+	///
+	/// Examples:
+	/// ```
+	/// # use rust_sc2::prelude::*;
+	/// # let bot = rust_sc2::bot::Bot::default();
+	/// let plan = bot.tech_plan(UnitTypeId::Carrier);
+	/// for step in &plan.steps {
+	///     println!("{:?} {:?} starts in {}s", step.kind, step.item, step.start);
+	/// }
+	/// if let Some(eta) = bot.tech_plan(UpgradeId::Charge).eta() {
+	///     println!("Charge will be done in {}s", eta);
+	/// }
+	/// ```
+	pub fn tech_plan<T: Into<TechItem>>(&self, goal: T) -> TechPlan {
+		TechPlan::new(self, goal.into())
+	}
+	/// Orders units according to ratios set in [`production`](Self::production),
+	/// and injects larva or uses chrono boost if enabled.
+	/// Resources and supply of ordered units are subtracted.
+	///
+	/// See [`production`](crate::production) module for details.
+	pub fn run_production(&mut self) -> ProductionReport {
+		let settings = std::mem::take(&mut self.production);
+		let report = production::run(self, &settings);
+		self.production = settings;
+		report
+	}
+	/// Returns up to `count` free positions in psionic matrix to warp units in, closest to given point.
+	pub fn warp_in_positions(&self, near: Point2, count: usize) -> Vec<Point2> {
+		production::warp_in_positions(self, near, count)
+	}
+	/// Returns supply providers in progress and supply expected to be requested by production
+	/// in the next `horizon` seconds. See [`supply`](crate::supply) module for details.
+	pub fn supply_timeline(&self, horizon: f32) -> SupplyTimeline {
+		supply::supply_timeline(self, horizon)
+	}
+	/// Forecasts supply for the next `horizon` seconds and finds when to start new supply providers
+	/// (depots, pylons or overlords), so they finish right before supply is needed.
+	pub fn supply_forecast(&self, horizon: f32) -> SupplyForecast {
+		supply::supply_forecast(self, horizon)
+	}
 	/// Move player camera to specified position.
 	pub fn move_camera(&mut self, pos: Point3) {
 		self.actions.push(Action::CameraMove(pos));
@@ -955,16 +1153,21 @@ impl Bot {
 			self.enemy_player_id = enemy_player_id;
 		}
 		self.race_values = Rs::new(RACE_VALUES[&self.race].clone());
-
+		self.link_data_for_unit();
+	}
+	fn link_data_for_unit(&mut self) {
 		self.data_for_unit = Rs::new(DataForUnit {
 			commander: Rs::clone(&self.commander),
 			game_data: Rs::clone(&self.game_data),
+			balance: Rs::clone(&self.balance),
 			techlab_tags: Rs::clone(&self.techlab_tags),
 			reactor_tags: Rs::clone(&self.reactor_tags),
 			race_values: Rs::clone(&self.race_values),
 			max_cooldowns: Rs::clone(&self.max_cooldowns),
 			last_units_hits: Rs::clone(&self.last_units_hits),
 			last_units_seen: Rs::clone(&self.last_units_seen),
+			last_units_positions: Rs::clone(&self.last_units_positions),
+			last_positions_loop: Rs::clone(&self.last_positions_loop),
 			abilities_units: Rs::clone(&self.abilities_units),
 			enemy_upgrades: Rs::clone(&self.enemy_upgrades),
 			upgrades: Rs::clone(&self.state.observation.raw.upgrades),
@@ -973,8 +1176,34 @@ impl Bot {
 			game_loop: Rs::clone(&self.state.observation.game_loop),
 			available_frames: Rs::clone(&self.available_frames),
 		});
+		self.debug.game_loop = Rs::clone(&self.state.observation.game_loop);
+	}
+	/// Updates state of bot from given observation without requesting anything from the game,
+	/// then returns events which happened since the previous observation.
+	///
+	/// Can be used to replay recorded observations or to test bot's logic offline.
+	/// Abilities available to own units are taken from `abilities`,
+	/// which is normally the response to `RequestQueryAvailableAbilities` for these units.
+	pub fn observe(
+		&mut self,
+		observation: &ResponseObservation,
+		abilities: &[ResponseQueryAvailableAbilities],
+	) -> Vec<Event> {
+		// Game data could be replaced since the last step, so units should get the current one
+		self.link_data_for_unit();
+		let events = apply_observation(self, observation, abilities);
+		self.prepare_step();
+		events
+	}
+	/// Calls registered [`triggers`](Self::triggers) for given events and current observation,
+	/// like the runner does on each step.
+	///
+	/// Can be used together with [`observe`](Self::observe) to test bot's logic offline.
+	pub fn dispatch_triggers(&mut self, events: &[Event]) -> SC2Result<()> {
+		triggers::dispatch_triggers(self, events)
 	}
 	pub(crate) fn prepare_start(&mut self) {
+		self.debug.terrain_height = Rs::clone(&self.game_info.terrain_height);
 		if let Some(townhall) = self.units.my.townhalls.first() {
 			self.start_location = townhall.position();
 		}
@@ -1280,6 +1509,18 @@ impl Bot {
 		}
 		self.current_units = current_units;
 		self.orders = orders;
+
+		self.scouting.update(
+			&self.state.observation.raw.visibility,
+			&self.expansions,
+			self.state.observation.game_loop(),
+		);
+
+		StrategyInference::update(self);
+		PlacementGrid::update(self);
+		if self.track_army {
+			ArmyTracker::update(self);
+		}
 	}
 	pub(crate) fn update_units(&mut self, all_units: Units) {
 		*self.last_units_hits.write_lock() = self
@@ -1305,6 +1546,15 @@ impl Bot {
 			})
 			.collect();
 
+		*self.last_units_positions.write_lock() = self
+			.units
+			.all
+			.iter()
+			.map(|u| (u.tag(), u.position()))
+			.collect();
+		self.last_positions_loop
+			.set_locked((self.time * FRAMES_PER_SECOND).round() as u32);
+
 		self.units.clear();
 
 		let mut techlab_tags = self.techlab_tags.write_lock();
@@ -1477,6 +1727,9 @@ impl Bot {
 			}
 		}
 		units.all = all_units;
+		if self.use_spatial_index {
+			units.build_index();
+		}
 
 		let enemies = &mut self.units.enemy;
 		for &u in &self.saved_hallucinations {
@@ -1703,121 +1956,127 @@ impl Bot {
 
 	/// Simple wrapper around [`query_placement`](Self::query_placement).
 	/// Checks if it's possible to build given building on given position.
+	/// Returns `false` if `building` can't be built by a worker.
+	///
+	/// Position is checked on local [`placement`](Self::placement) grid first,
+	/// so the server is queried only if it's free there.
 	pub fn can_place(&self, building: UnitTypeId, pos: Point2) -> bool {
-		self.query_placement(
-			vec![(self.game_data.units[&building].ability.unwrap(), pos, None)],
-			false,
-		)
-		.unwrap_or_default()[0]
-			== ActionResult::Success
+		self.game_data
+			.units
+			.get(&building)
+			.and_then(|data| data.ability)
+			.is_some_and(|ability| {
+				self.can_place_local(building, pos)
+					&& self
+						.query_placement(vec![(ability, pos, None)], false)
+						.unwrap_or_default()
+						.first() == Some(&ActionResult::Success)
+			})
 	}
 	/// Simple wrapper around [`query_placement`](Self::query_placement).
 	/// Multi-version of [`can_place`](Self::can_place).
 	pub fn can_place_some(&self, places: Vec<(UnitTypeId, Point2)>) -> Vec<bool> {
-		self.query_placement(
-			places
-				.into_iter()
-				.map(|(building, pos)| (self.game_data.units[&building].ability.unwrap(), pos, None))
-				.collect(),
-			false,
-		)
-		.unwrap_or_default()
-		.into_iter()
-		.map(|r| r == ActionResult::Success)
-		.collect()
+		let abilities = places
+			.iter()
+			.map(|(building, _)| self.game_data.units.get(building).and_then(|data| data.ability))
+			.collect::<Vec<_>>();
+		let queried = places
+			.iter()
+			.zip(&abilities)
+			.filter_map(|((_, pos), ability)| Some(((*ability)?, *pos, None)))
+			.collect::<Vec<_>>();
+		let mut results = if queried.is_empty() {
+			vec![]
+		} else {
+			self.query_placement(queried, false).unwrap_or_default()
+		}
+		.into_iter();
+
+		abilities
+			.iter()
+			.map(|ability| ability.is_some() && results.next() == Some(ActionResult::Success))
+			.collect()
+	}
+	/// Checks if it's possible to build given building on given position using only
+	/// local [`placement`](Self::placement) grid, so no requests are sent to the server.
+	///
+	/// Structures, resources, destructables, creep and psionic matrix are taken into account,
+	/// but units standing on the position aren't.
+	pub fn can_place_local(&self, building: UnitTypeId, pos: Point2) -> bool {
+		placement::can_place(self, building, pos, false)
 	}
 
 	/// Nice wrapper around [`query_placement`](Self::query_placement).
 	/// Returns correct position where it is possible to build given `building`,
 	/// or `None` if position is not found or `building` can't be built by a worker.
+	///
+	/// Positions are checked on local [`placement`](Self::placement) grid first,
+	/// then the ones left are confirmed with a single query to the server.
+	/// If [`confirm`](PlacementOptions::confirm) option is unset, the query is skipped.
 	pub fn find_placement(
 		&self,
 		building: UnitTypeId,
 		near: Point2,
 		options: PlacementOptions,
 	) -> Option<Point2> {
-		if let Some(data) = self.game_data.units.get(&building) {
-			if let Some(ability) = data.ability {
-				let addon = options.addon;
-				if self
-					.query_placement(
-						if addon {
-							vec![
-								(ability, near, None),
-								(AbilityId::TerranBuildSupplyDepot, near.offset(2.5, -0.5), None),
-							]
-						} else {
-							vec![(ability, near, None)]
-						},
-						false,
-					)
-					.unwrap_or_default()
-					.iter()
-					.all(|r| matches!(r, ActionResult::Success))
-				{
-					return Some(near);
-				}
-
-				let placement_step = options.step;
-				for distance in (placement_step..options.max_distance).step_by(placement_step as usize) {
-					let positions = (-distance..=distance)
-						.step_by(placement_step as usize)
-						.flat_map(|offset| {
-							vec![
-								near.offset(offset as f32, (-distance) as f32),
-								near.offset(offset as f32, distance as f32),
-								near.offset((-distance) as f32, offset as f32),
-								near.offset(distance as f32, offset as f32),
-							]
-						})
-						.collect::<Vec<Point2>>();
-					let results = self
-						.query_placement(positions.iter().map(|pos| (ability, *pos, None)).collect(), false)
-						.unwrap_or_default();
-
-					let mut valid_positions = positions
-						.iter()
-						.zip(results.iter())
-						.filter_map(|(pos, res)| {
-							if matches!(res, ActionResult::Success) {
-								Some(*pos)
-							} else {
-								None
-							}
-						})
-						.collect::<Vec<Point2>>();
-
-					if addon {
-						let results = self
-							.query_placement(
-								valid_positions
-									.iter()
-									.map(|pos| {
-										(AbilityId::TerranBuildSupplyDepot, pos.offset(2.5, -0.5), None)
-									})
-									.collect(),
-								false,
-							)
-							.unwrap_or_default();
-						valid_positions = valid_positions
-							.into_iter()
-							.zip(results.into_iter())
-							.filter(|(_, res)| *res == ActionResult::Success)
-							.map(|(pos, _)| pos)
-							.collect::<Vec<Point2>>();
-					}
+		let ability = self.game_data.units.get(&building)?.ability?;
+		let addon = options.addon;
+
+		// Given position itself is checked first, then rings of positions around it
+		let placement_step = options.step;
+		let mut rings = vec![vec![near]];
+		for distance in (placement_step..options.max_distance).step_by(placement_step as usize) {
+			rings.push(
+				(-distance..=distance)
+					.step_by(placement_step as usize)
+					.flat_map(|offset| {
+						vec![
+							near.offset(offset as f32, (-distance) as f32),
+							near.offset(offset as f32, distance as f32),
+							near.offset((-distance) as f32, offset as f32),
+							near.offset(distance as f32, offset as f32),
+						]
+					})
+					.collect::<Vec<Point2>>(),
+			);
+		}
+		for ring in &mut rings {
+			ring.retain(|pos| placement::can_place(self, building, *pos, addon));
+		}
 
-					if !valid_positions.is_empty() {
-						return if options.random {
-							valid_positions.choose(&mut thread_rng()).copied()
-						} else {
-							valid_positions.iter().closest(near).copied()
-						};
-					}
-				}
+		if options.confirm {
+			let positions = rings.iter().flatten().copied().collect::<Vec<_>>();
+			if positions.is_empty() {
+				return None;
+			}
+			let mut places = positions
+				.iter()
+				.map(|pos| (ability, *pos, None))
+				.collect::<Vec<_>>();
+			if addon {
+				places.extend(positions.iter().map(|pos| {
+					(
+						AbilityId::TerranBuildSupplyDepot,
+						placement::addon_position(*pos),
+						None,
+					)
+				}));
 			}
+			let results = self.query_placement(places, false).unwrap_or_default();
+			let success = |i: usize| results.get(i) == Some(&ActionResult::Success);
+			let len = positions.len();
+			let mut confirmed = (0..len).map(|i| success(i) && (!addon || success(len + i)));
+			for ring in &mut rings {
+				ring.retain(|_| confirmed.next() == Some(true));
+			}
+		}
+
+		let valid_positions = rings.into_iter().find(|ring| !ring.is_empty())?;
+		if options.random {
+			valid_positions.choose(&mut thread_rng()).copied()
+		} else {
+			valid_positions.iter().closest(near).copied()
 		}
-		None
 	}
 	/// Another wrapper around [`query_placement`](Self::query_placement),
 	/// used to find free geyser near given base.
@@ -2523,6 +2782,92 @@ impl Bot {
 		})
 	}
 
+	/// Returns places suggested for scouting, sorted by value in descending order.
+	///
+	/// Value depends on how long place wasn't seen and what's expected to be found there:
+	/// opponent's start location and bases are more valuable than free expansions and regions of the map.
+	/// Currently visible places are never suggested.
+	pub fn scout_targets(&self, count: usize) -> Vec<ScoutTarget> {
+		scouting::scout_targets(self, count)
+	}
+	/// Returns locations of known opponent's bases (including start location)
+	/// with amount of seconds passed since they were seen last time.
+	pub fn enemy_bases_staleness(&self) -> Vec<(Point2, f32)> {
+		scouting::enemy_bases_staleness(self)
+	}
+	/// Plans layout of buildings around expansion with given townhall position for given race.
+	/// Initializes pathfinding if it wasn't initialized before.
+	///
+	/// Returns `None` if there's no expansion at given position.
+	/// See [`layout`](crate::layout) module for details.
+	pub fn plan_layout(&mut self, base: Point2, race: Race, options: LayoutOptions) -> Option<BaseLayout> {
+		if !self.expansions.iter().any(|exp| exp.loc.distance_squared(base) < 1.0) {
+			return None;
+		}
+		self.ensure_pathfinding_initialized();
+		layout::plan_layout(self, base, race, options)
+	}
+	/// Finds placement of given buildings, which closes given choke from the side of `inside` point.
+	///
+	/// If `gap` is `0` wall is fully closed, otherwise it leaves passage of `gap` tiles,
+	/// so only units of that size can walk through it (e.g. `1` for zerglings and zealots).
+	/// Returns `None` if such wall can't be built.
+	///
+	/// See [`wall`](crate::wall) module for details.
+	pub fn find_wall(
+		&self,
+		choke: &sc2pathfinding::Choke,
+		inside: Point2,
+		buildings: &[UnitTypeId],
+		gap: usize,
+	) -> Option<Wall> {
+		wall::find_wall(self, choke, inside, buildings, gap)
+	}
+	/// Returns the most probable opponent's strategy, estimated from observed units and structures.
+	///
+	/// See [`strategy`](crate::strategy) module for details.
+	pub fn enemy_strategy(&self) -> Option<&StrategyEstimate> {
+		self.strategy.best()
+	}
+	/// Finds the most valuable target of area-of-effect spell for given caster
+	/// among all visible enemies, taking into account own units and effects on the map.
+	///
+	/// See [`spells`](crate::spells) module for details.
+	pub fn find_spell_target(
+		&self,
+		caster: &Unit,
+		spell: AreaSpell,
+		options: &SpellOptions,
+	) -> Option<SpellTarget> {
+		spells::find_spell_target(
+			caster,
+			spell,
+			&self.units.enemy.all,
+			&self.units.my.all,
+			&self.state.observation.raw.effects,
+			options,
+		)
+	}
+	/// Makes owned copy of the game state on current step,
+	/// which can be serialized with `serde` feature enabled.
+	///
+	/// See [`snapshot`](crate::snapshot) module for details.
+	pub fn snapshot(&self) -> Snapshot {
+		Snapshot::new(self)
+	}
+	/// Builds route which visits all given targets, starting from closest ones.
+	/// Paths are found over pathfinding map avoiding known enemy units, which can attack given type of unit.
+	///
+	/// Returns `None` if pathfinding isn't initialized. Unreachable targets are skipped.
+	pub fn scouting_route(
+		&mut self,
+		start: Point2,
+		targets: &[Point2],
+		unit_type: PathfindingUnitType,
+	) -> Option<Vec<Point2>> {
+		scouting::scouting_route(self, start, targets, unit_type)
+	}
+
 	/// Leaves current game, which is counted as Defeat for bot.
 	///
 	/// Note: [`on_end`] will not be called, if needed use [`debug.end_game`] instead.
@@ -2563,6 +2908,8 @@ impl Default for Bot {
 			game_step: Rs::new(LockU32::new(1)),
 			game_left: false,
 			disable_fog: false,
+			use_spatial_index: false,
+			track_army: false,
 			race: Race::Random,
 			enemy_race: Race::Random,
 			process: None,
@@ -2575,6 +2922,9 @@ impl Default for Bot {
 			debug: Default::default(),
 			game_info: Default::default(),
 			game_data: Default::default(),
+			balance_tables: Default::default(),
+			balance: Default::default(),
+			base_build: 0,
 			state: Default::default(),
 			race_values: Default::default(),
 			data_for_unit: Default::default(),
@@ -2600,12 +2950,23 @@ impl Default for Bot {
 			max_cooldowns: Default::default(),
 			last_units_hits: Default::default(),
 			last_units_seen: Default::default(),
+			last_units_positions: Default::default(),
+			last_positions_loop: Default::default(),
 			vision_blockers: Default::default(),
 			ramps: Default::default(),
 			pathfinding_map: None,
 			enemy_upgrades: Default::default(),
 			owned_tags: Default::default(),
 			under_construction: Default::default(),
+			seen_enemy_tags: Default::default(),
+			enemy_expansions: Default::default(),
+			triggers: Default::default(),
+			commands: Default::default(),
+			production: Default::default(),
+			scouting: Default::default(),
+			strategy: Default::default(),
+			placement: Default::default(),
+			army: Default::default(),
 			enemies_ordered: Default::default(),
 			enemies_current: Default::default(),
 			saved_hallucinations: Default::default(),
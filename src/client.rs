@@ -6,11 +6,15 @@
 use crate::{
 	api::API,
 	bot::{Bot, LockOwned, Rs},
+	commands::dispatch_commands,
 	game_state::update_state,
-	paths::*,
+	launch::{LaunchConfig, LaunchError},
+	logging::{finish_game_log, start_game_log},
 	player::Computer,
+	triggers::dispatch_triggers,
 	IntoProto, IntoSC2, Player, PlayerSettings,
 };
+use log::LevelFilter;
 use sc2_proto::sc2api::{PlayerSetup, PlayerType, PortSet, Request, RequestCreateGame, Status};
 use std::{
 	error::Error,
@@ -79,6 +83,7 @@ where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	bot: &'a mut B,
+	config: LaunchConfig,
 	sc2_path: String,
 	sc2_version: Option<&'a str>,
 	/// Computer opponent configuration.
@@ -95,27 +100,48 @@ where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	/// Constructs new single player runner.
+	///
+	/// # Panics
+	/// Panics if SC2 installation or the map can't be found,
+	/// use [`with_config`](Self::with_config) to handle these errors.
 	pub fn new(bot: &'a mut B, computer: Computer, map: &str, sc2_version: Option<&'a str>) -> Self {
+		Self::with_config(bot, computer, map, sc2_version, LaunchConfig::default())
+			.unwrap_or_else(|e| panic!("{}", e))
+	}
+	/// Constructs new single player runner with given launch configuration.
+	pub fn with_config(
+		bot: &'a mut B,
+		computer: Computer,
+		map: &str,
+		sc2_version: Option<&'a str>,
+		config: LaunchConfig,
+	) -> SC2Result<Self> {
 		debug!("Starting game vs computer");
-		let sc2_path = get_path_to_sc2();
-		let map_path = get_map_path(&sc2_path, map);
+		let sc2_path = config.find_sc2()?;
+		let map_path = config.find_map(&sc2_path, map)?;
 
-		Self {
+		Ok(Self {
 			bot,
+			config,
 			sc2_path,
 			sc2_version,
 			computer,
 			map_path,
 			save_replay_as: None,
 			realtime: false,
-		}
+		})
 	}
 
 	/// Launches SC2 client and connects bot to the API.
 	pub fn launch(&mut self) -> SC2Result<()> {
 		let port = get_unused_port();
 		debug!("Launching SC2 process");
-		self.bot.process = Some(launch_client(&self.sc2_path, port, self.sc2_version));
+		self.bot.process = Some(launch_client(
+			&self.sc2_path,
+			port,
+			self.sc2_version,
+			&self.config,
+		)?);
 		debug!("Connecting to websocket");
 		self.bot.api = Some(API::new(connect_to_websocket(HOST, port)?));
 		Ok(())
@@ -141,13 +167,9 @@ where
 		let res = api.send(req)?;
 		let res_create_game = res.get_create_game();
 		if res_create_game.has_error() {
-			let err = format!(
-				"{:?}: {}",
-				res_create_game.get_error(),
-				res_create_game.get_error_details()
-			);
+			let err = ProtoError::new(res_create_game.get_error(), res_create_game.get_error_details());
 			error!("{}", err);
-			panic!("{}", err);
+			return Err(Box::new(err));
 		}
 
 		debug!("Sending JoinGame request");
@@ -175,7 +197,12 @@ where
 	/// # Panics
 	/// Panics if the map doesn't exist in maps directory.
 	pub fn set_map(&mut self, map: &str) {
-		self.map_path = get_map_path(&self.sc2_path, map);
+		self.try_set_map(map).unwrap_or_else(|e| panic!("{}", e));
+	}
+	/// Changes map to play on, returns error if the map doesn't exist in maps directories.
+	pub fn try_set_map(&mut self, map: &str) -> SC2Result<()> {
+		self.map_path = self.config.find_map(&self.sc2_path, map)?;
+		Ok(())
 	}
 
 	/// Manually closes SC2 client.
@@ -191,6 +218,7 @@ where
 {
 	bot: &'a mut B,
 	human: Human,
+	config: LaunchConfig,
 	sc2_path: String,
 	sc2_version: Option<&'a str>,
 	/// Configuration of human opponent.
@@ -207,26 +235,42 @@ where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
 	/// Constructs new multi player runner.
+	///
+	/// # Panics
+	/// Panics if SC2 installation or the map can't be found,
+	/// use [`with_config`](Self::with_config) to handle these errors.
 	pub fn new(
 		bot: &'a mut B,
 		human_settings: PlayerSettings<'a>,
 		map: &str,
 		sc2_version: Option<&'a str>,
 	) -> Self {
+		Self::with_config(bot, human_settings, map, sc2_version, LaunchConfig::default())
+			.unwrap_or_else(|e| panic!("{}", e))
+	}
+	/// Constructs new multi player runner with given launch configuration.
+	pub fn with_config(
+		bot: &'a mut B,
+		human_settings: PlayerSettings<'a>,
+		map: &str,
+		sc2_version: Option<&'a str>,
+		config: LaunchConfig,
+	) -> SC2Result<Self> {
 		debug!("Starting human vs bot");
-		let sc2_path = get_path_to_sc2();
-		let map_path = get_map_path(&sc2_path, map);
+		let sc2_path = config.find_sc2()?;
+		let map_path = config.find_map(&sc2_path, map)?;
 
-		Self {
+		Ok(Self {
 			bot,
 			human: Human::default(),
+			config,
 			sc2_path,
 			sc2_version,
 			human_settings,
 			map_path,
 			save_replay_as: None,
 			realtime: false,
-		}
+		})
 	}
 
 	/// Launches SC2 clients and connects bot to the API.
@@ -236,9 +280,19 @@ where
 		let (port_bot, port_human) = (ports[0], ports[1]);
 
 		debug!("Launching host SC2 process");
-		self.human.process = Some(launch_client(&self.sc2_path, port_human, self.sc2_version));
+		self.human.process = Some(launch_client(
+			&self.sc2_path,
+			port_human,
+			self.sc2_version,
+			&self.config,
+		)?);
 		debug!("Launching client SC2 process");
-		self.bot.process = Some(launch_client(&self.sc2_path, port_bot, self.sc2_version));
+		self.bot.process = Some(launch_client(
+			&self.sc2_path,
+			port_bot,
+			self.sc2_version,
+			&self.config,
+		)?);
 
 		debug!("Connecting to host websocket");
 		self.human.api = Some(API::new(connect_to_websocket(HOST, port_human)?));
@@ -267,13 +321,9 @@ where
 		let res = human_api.send(req)?;
 		let res_create_game = res.get_create_game();
 		if res_create_game.has_error() {
-			let err = format!(
-				"{:?}: {}",
-				res_create_game.get_error(),
-				res_create_game.get_error_details()
-			);
+			let err = ProtoError::new(res_create_game.get_error(), res_create_game.get_error_details());
 			error!("{}", err);
-			panic!("{}", err);
+			return Err(Box::new(err));
 		}
 
 		debug!("Sending JoinGame request to both processes");
@@ -313,7 +363,12 @@ where
 	/// # Panics
 	/// Panics if the map doesn't exist in maps directory.
 	pub fn set_map(&mut self, map: &str) {
-		self.map_path = get_map_path(&self.sc2_path, map);
+		self.try_set_map(map).unwrap_or_else(|e| panic!("{}", e));
+	}
+	/// Changes map to play on, returns error if the map doesn't exist in maps directories.
+	pub fn try_set_map(&mut self, map: &str) -> SC2Result<()> {
+		self.map_path = self.config.find_map(&self.sc2_path, map)?;
+		Ok(())
 	}
 
 	/// Manually closes SC2 clients.
@@ -386,10 +441,165 @@ pub struct LaunchOptions<'a> {
 	pub save_replay_as: Option<&'a str>,
 	/// Play games in real time mode or not.
 	pub realtime: bool,
+	/// Configuration of SC2 installation, maps and client process.
+	pub config: LaunchConfig,
+	/// Write log of every game to a new file in given directory.
+	/// See [`logging`](crate::logging) module for details.
+	pub log_dir: Option<&'a str>,
+}
+
+/// Arguments passed to bot by ladder managers (e.g. AI Arena).
+///
+/// Both `--Arg value` and `--Arg=value` forms are accepted, unknown arguments are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LadderArgs {
+	/// Address of ladder server (`--LadderServer`).
+	pub ladder_server: Option<String>,
+	/// Port of SC2 client to connect to (`--GamePort`).
+	pub game_port: Option<i32>,
+	/// Start of ports range used to join the game (`--StartPort`).
+	pub start_port: Option<i32>,
+	/// Id of opponent (`--OpponentId`).
+	pub opponent_id: Option<String>,
+	/// Play game in real time mode (`--RealTime`).
+	pub realtime: bool,
+}
+impl LadderArgs {
+	/// Parses arguments of current process.
+	pub fn from_env() -> SC2Result<Self> {
+		Self::parse(std::env::args().skip(1))
+	}
+	/// Parses given arguments (without name of the program).
+	pub fn parse<I, S>(args: I) -> SC2Result<Self>
+	where
+		I: IntoIterator<Item = S>,
+		S: AsRef<str>,
+	{
+		let mut parsed = Self::default();
+		let mut args = args.into_iter().map(|arg| arg.as_ref().to_string());
+		while let Some(arg) = args.next() {
+			let (name, inline) = match arg.split_once('=') {
+				Some((name, value)) => (name.to_string(), Some(value.to_string())),
+				None => (arg.clone(), None),
+			};
+			if name == "--RealTime" {
+				parsed.realtime = match inline.as_deref() {
+					None | Some("true") | Some("1") => true,
+					Some("false") | Some("0") => false,
+					Some(_) => return Err(Box::new(LaunchError::InvalidArgument(arg))),
+				};
+				continue;
+			}
+			if !matches!(
+				name.as_str(),
+				"--LadderServer" | "--GamePort" | "--StartPort" | "--OpponentId"
+			) {
+				continue;
+			}
+			let value = inline
+				.or_else(|| args.next())
+				.ok_or_else(|| LaunchError::InvalidArgument(arg.clone()))?;
+			let port = || {
+				value
+					.parse::<i32>()
+					.map_err(|_| LaunchError::InvalidArgument(format!("{} {}", name, value)))
+			};
+			match name.as_str() {
+				"--LadderServer" => parsed.ladder_server = Some(value.clone()),
+				"--GamePort" => parsed.game_port = Some(port()?),
+				"--StartPort" => parsed.start_port = Some(port()?),
+				_ => parsed.opponent_id = Some(value.clone()),
+			}
+		}
+		Ok(parsed)
+	}
+	/// Checks if bot was launched by ladder manager, i.e. game and start ports are given.
+	pub fn is_ladder(&self) -> bool {
+		self.game_port.is_some() && self.start_port.is_some()
+	}
 }
 
 // Runners
 
+/// Standard entry point of the bot.
+///
+/// Joins ladder game if bot was launched with ladder arguments (see [`LadderArgs`]),
+/// otherwise runs game vs given computer opponent on given map.
+/// Log of the game is written to file if [`log_dir`](LaunchOptions::log_dir) is set.
+///
+/// ```no_run
+/// use rust_sc2::prelude::*;
+/// # #[bot] #[derive(Default)] struct MyBot;
+/// # impl Player for MyBot {
+/// #     fn get_player_settings(&self) -> PlayerSettings<'_> { PlayerSettings::new(Race::Random) }
+/// # }
+///
+/// fn main() -> SC2Result<()> {
+///     run_from_args(
+///         &mut MyBot::default(),
+///         Computer::new(Race::Random, Difficulty::VeryEasy, None),
+///         "Simple64",
+///         LaunchOptions {
+///             log_dir: Some("logs"),
+///             ..Default::default()
+///         },
+///     )
+/// }
+/// ```
+pub fn run_from_args<B>(
+	bot: &mut B,
+	computer: Computer,
+	map_name: &str,
+	mut options: LaunchOptions,
+) -> SC2Result<()>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let args = LadderArgs::from_env()?;
+	match (args.game_port, args.start_port) {
+		(Some(game_port), Some(start_port)) => {
+			let name = format!("vs_{}", args.opponent_id.as_deref().unwrap_or("unknown"));
+			with_log(options.log_dir, &name, || {
+				run_ladder_game(
+					bot,
+					args.ladder_server.as_deref().unwrap_or(HOST),
+					game_port,
+					start_port,
+					args.opponent_id.as_deref(),
+					args.realtime,
+				)
+			})
+		}
+		_ => {
+			options.realtime |= args.realtime;
+			run_vs_computer(bot, computer, map_name, options)
+		}
+	}
+}
+
+// Runs game writing its log to a new file if directory is given.
+// Game is still played without the file if log can't be started (e.g. other logger is already set).
+fn with_log<F>(log_dir: Option<&str>, name: &str, run: F) -> SC2Result<()>
+where
+	F: FnOnce() -> SC2Result<()>,
+{
+	let logging = log_dir.is_some_and(|dir| match start_game_log(dir, name, LevelFilter::Debug) {
+		Ok(path) => {
+			debug!("Writing log to {}", path.display());
+			true
+		}
+		Err(e) => {
+			warn!("Can't write log to {}: {}", dir, e);
+			false
+		}
+	});
+	let result = run();
+	if logging {
+		finish_game_log();
+	}
+	result
+}
+
 /// Simple function to run game vs built-in AI.
 pub fn run_vs_computer<B>(
 	bot: &mut B,
@@ -400,12 +610,15 @@ pub fn run_vs_computer<B>(
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
-	let mut runner = RunnerSingle::new(bot, computer, map_name, options.sc2_version);
-	runner.launch()?;
-	runner.realtime = options.realtime;
-	runner.save_replay_as = options.save_replay_as;
-	runner.run_game()?;
-	Ok(())
+	let name = format!("vs_{:?}_{:?}", computer.race, computer.difficulty);
+	with_log(options.log_dir, &name, || {
+		let mut runner =
+			RunnerSingle::with_config(bot, computer, map_name, options.sc2_version, options.config)?;
+		runner.launch()?;
+		runner.realtime = options.realtime;
+		runner.save_replay_as = options.save_replay_as;
+		runner.run_game()
+	})
 }
 
 /// Simple function to join ladder game.
@@ -466,12 +679,14 @@ pub fn run_vs_human<B>(
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
-	let mut runner = RunnerMulti::new(bot, human_settings, map_name, options.sc2_version);
-	runner.launch()?;
-	runner.realtime = options.realtime;
-	runner.save_replay_as = options.save_replay_as;
-	runner.run_game()?;
-	Ok(())
+	with_log(options.log_dir, "vs_human", || {
+		let mut runner =
+			RunnerMulti::with_config(bot, human_settings, map_name, options.sc2_version, options.config)?;
+		runner.launch()?;
+		runner.realtime = options.realtime;
+		runner.save_replay_as = options.save_replay_as;
+		runner.run_game()
+	})
 }
 
 // Portpicker
@@ -498,6 +713,12 @@ fn get_unused_ports(n: usize) -> Vec<i32> {
 fn set_static_data(bot: &mut Bot) -> SC2Result<()> {
 	let api = bot.api();
 
+	debug!("Requesting base build");
+	let mut req = Request::new();
+	req.mut_ping();
+	let res = api.send(req)?;
+	let base_build = res.get_ping().get_base_build();
+
 	debug!("Requesting GameInfo");
 	let mut req = Request::new();
 	req.mut_game_info();
@@ -518,6 +739,13 @@ fn set_static_data(bot: &mut Bot) -> SC2Result<()> {
 	bot.game_info = game_info;
 	bot.game_data = game_data;
 
+	let balance = bot.balance_tables.select(base_build).clone();
+	for issue in balance.validate(&bot.game_data) {
+		warn!("Balance table for base version {}: {}", balance.base_version, issue);
+	}
+	bot.balance = Rs::new(balance);
+	bot.base_build = base_build;
+
 	Ok(())
 }
 
@@ -613,10 +841,12 @@ where
 	bot.prepare_start();
 	bot.prepare_step();
 
-	for e in events {
+	for &e in &events {
 		bot.on_event(e)?;
 	}
 	bot.on_start()?;
+	dispatch_triggers(bot, &events)?;
+	dispatch_commands(bot)?;
 
 	let bot_actions = bot.get_actions();
 	if !bot_actions.is_empty() {
@@ -656,10 +886,14 @@ where
 	let events = update_state(bot, res.get_observation())?;
 	bot.prepare_step();
 
-	for e in events {
+	for &e in &events {
 		bot.on_event(e)?;
 	}
-	bot.on_step(iteration)?;
+	dispatch_triggers(bot, &events)?;
+	dispatch_commands(bot)?;
+	if !bot.commands.is_paused() {
+		bot.on_step(iteration)?;
+	}
 	if bot.game_left {
 		let mut req = Request::new();
 		req.mut_leave_game();
@@ -719,12 +953,17 @@ fn save_replay(api: &API, path: &str) -> SC2Result<()> {
 	Ok(())
 }
 
-fn launch_client(sc2_path: &str, port: i32, sc2_version: Option<&str>) -> Child {
-	let (base_version, data_hash) = match sc2_version {
-		Some(ver) => get_version_info(ver),
-		None => (get_latest_base_version(sc2_path), ""),
-	};
-	let sc2_full_path = format!("{}/Versions/Base{}/{}", sc2_path, base_version, SC2_BINARY);
+fn launch_client(
+	sc2_path: &str,
+	port: i32,
+	sc2_version: Option<&str>,
+	config: &LaunchConfig,
+) -> SC2Result<Child> {
+	let version = config.version_info(sc2_path, sc2_version)?;
+	let sc2_full_path = format!(
+		"{}/Versions/Base{}/{}",
+		sc2_path, version.base_version, SC2_BINARY
+	);
 
 	let mut process = if cfg!(feature = "wine_sc2") {
 		let wine = std::env::var("WINE").unwrap_or_else(|_| "wine".to_string());
@@ -760,10 +999,11 @@ fn launch_client(sc2_path: &str, port: i32, sc2_version: Option<&str>) -> Child
 		// 0 - windowed, 1 - fullscreen
 		.arg("-displayMode")
 		.arg("0");
-	if !data_hash.is_empty() {
-		process.arg("-dataVersion").arg(data_hash);
+	if !version.data_hash.is_empty() {
+		process.arg("-dataVersion").arg(&version.data_hash);
 	}
-	process.spawn().expect("Can't launch SC2 process.")
+	process.args(config.args());
+	Ok(process.spawn().map_err(LaunchError::Io)?)
 }
 
 fn connect_to_websocket(host: &str, port: i32) -> SC2Result<WS> {
@@ -0,0 +1,322 @@
+//! Chat commands for interactive debugging, e.g. when playing against bot with
+//! [`run_vs_human`](crate::client::run_vs_human).
+//!
+//! Commands are registered in [`commands`](crate::bot::Bot::commands) field of bot.
+//! Every step the runner parses messages from [`state.chat`](crate::game_state::GameState::chat)
+//! which start with [`prefix`](ChatCommands::prefix) and calls handlers of matching commands.
+//! Commands are dispatched after triggers and before [`on_step`](crate::Player::on_step).
+//!
+//! Registry is empty by default, built-in commands can be added with [`add_defaults`](ChatCommands::add_defaults):
+//! - `!help` - lists registered commands
+//! - `!show [layer]` / `!hide <layer>` - enables or disables debug layer (lists layers without argument)
+//! - `!pause` - toggles pause of bot, when paused [`on_step`](crate::Player::on_step) isn't called
+//! - `!speed <loops>` - sets [`game_step`](crate::bot::Bot::set_game_step)
+//! - `!kill [my|enemy|all|<unit type>]` - kills units (enemy units by default)
+//! - `!spawn <unit type> [count] [my|enemy]` - spawns units in the center of the map
+//! - `!fog <on|off>` - reveals or hides the map
+//!
+//! Built-in commands use cheats, so they shouldn't be enabled on ladder.
+//!
+//! ```
+//! use rust_sc2::prelude::*;
+//!
+//! let mut bot = rust_sc2::bot::Bot::default();
+//! bot.commands.add_defaults();
+//! bot.commands.add("minerals", "gives 5000 minerals", |bot, _cmd| {
+//!     bot.debug.cheat_minerals();
+//!     Ok(())
+//! });
+//! ```
+
+use crate::{
+	bot::{Bot, MaybeSendSync},
+	ids::UnitTypeId,
+	utils::ids_by_name,
+	SC2Result,
+};
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// Parsed chat command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatCommand {
+	/// Id of player who sent the command.
+	pub player_id: u32,
+	/// Name of the command in lower case (without prefix).
+	pub name: String,
+	/// Arguments of the command separated by whitespace.
+	pub args: Vec<String>,
+}
+impl ChatCommand {
+	/// Parses command from chat message. Returns `None` if message doesn't start with prefix.
+	pub fn parse(player_id: u32, message: &str, prefix: &str) -> Option<Self> {
+		let mut words = message.trim().strip_prefix(prefix)?.split_whitespace();
+		Some(Self {
+			player_id,
+			name: words.next()?.to_lowercase(),
+			args: words.map(|w| w.to_string()).collect(),
+		})
+	}
+	/// Returns argument with given index.
+	pub fn arg(&self, index: usize) -> Option<&str> {
+		self.args.get(index).map(|arg| arg.as_str())
+	}
+}
+
+#[cfg(feature = "rayon")]
+type CommandHandler = Box<dyn FnMut(&mut Bot, &ChatCommand) -> SC2Result<()> + Send + Sync>;
+#[cfg(not(feature = "rayon"))]
+type CommandHandler = Box<dyn FnMut(&mut Bot, &ChatCommand) -> SC2Result<()>>;
+
+struct Command {
+	name: String,
+	help: String,
+	handler: Option<CommandHandler>,
+}
+
+/// Registry of chat commands.
+/// Can be accessed through [`commands`](crate::bot::Bot::commands) field of bot.
+pub struct ChatCommands {
+	commands: Vec<Command>,
+	/// Prefix of chat messages which are commands. [Default: `!`]
+	pub prefix: String,
+	paused: bool,
+	fog_revealed: bool,
+}
+impl Default for ChatCommands {
+	fn default() -> Self {
+		Self {
+			commands: vec![],
+			prefix: "!".to_string(),
+			paused: false,
+			fog_revealed: false,
+		}
+	}
+}
+impl ChatCommands {
+	/// Registers command with given name and description shown in `!help`.
+	/// Replaces handler if command with the same name is already registered.
+	///
+	/// When `rayon` feature is enabled handler must be `Send + Sync`.
+	pub fn add<F>(&mut self, name: &str, help: &str, f: F)
+	where
+		F: FnMut(&mut Bot, &ChatCommand) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		let command = Command {
+			name: name.to_lowercase(),
+			help: help.to_string(),
+			handler: Some(Box::new(f)),
+		};
+		match self.commands.iter_mut().find(|c| c.name == command.name) {
+			Some(c) => *c = command,
+			None => self.commands.push(command),
+		}
+	}
+	/// Removes command with given name. Returns `true` if command was registered.
+	pub fn remove(&mut self, name: &str) -> bool {
+		let name = name.to_lowercase();
+		let len = self.commands.len();
+		self.commands.retain(|c| c.name != name);
+		self.commands.len() != len
+	}
+	/// Checks if command with given name is registered.
+	pub fn contains(&self, name: &str) -> bool {
+		let name = name.to_lowercase();
+		self.commands.iter().any(|c| c.name == name)
+	}
+	/// Returns names and descriptions of registered commands.
+	pub fn list(&self) -> impl Iterator<Item = (&str, &str)> {
+		self.commands.iter().map(|c| (c.name.as_str(), c.help.as_str()))
+	}
+	/// Removes all registered commands.
+	pub fn clear(&mut self) {
+		self.commands.clear();
+	}
+	/// Returns number of registered commands.
+	pub fn len(&self) -> usize {
+		self.commands.len()
+	}
+	/// Checks if there're no registered commands.
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+	/// Checks if bot is paused by `!pause` command.
+	pub fn is_paused(&self) -> bool {
+		self.paused
+	}
+	/// Pauses or resumes bot.
+	pub fn set_paused(&mut self, paused: bool) {
+		self.paused = paused;
+	}
+
+	/// Registers built-in commands listed in [module documentation](self).
+	pub fn add_defaults(&mut self) {
+		self.add("help", "lists commands", |bot, _| {
+			let lines = bot
+				.commands
+				.list()
+				.map(|(name, help)| format!("{}{} - {}", bot.commands.prefix, name, help))
+				.collect::<Vec<_>>();
+			for line in lines {
+				bot.chat_ally(&line);
+			}
+			Ok(())
+		});
+		self.add("show", "enables debug layer", |bot, cmd| {
+			match cmd.arg(0) {
+				Some(layer) => bot.debug.enable_layer(layer),
+				None => {
+					let layers = bot
+						.debug
+						.layers()
+						.map(|(layer, enabled)| format!("{}: {}", layer, if enabled { "on" } else { "off" }))
+						.collect::<Vec<_>>();
+					bot.chat_ally(&format!("Layers: {}", layers.join(", ")));
+				}
+			}
+			Ok(())
+		});
+		self.add("hide", "disables debug layer", |bot, cmd| {
+			match cmd.arg(0) {
+				Some(layer) => bot.debug.disable_layer(layer),
+				None => bot.chat_ally("Usage: hide <layer>"),
+			}
+			Ok(())
+		});
+		self.add("pause", "toggles pause of bot", |bot, _| {
+			let paused = !bot.commands.is_paused();
+			bot.commands.set_paused(paused);
+			bot.chat_ally(if paused { "Bot paused" } else { "Bot resumed" });
+			Ok(())
+		});
+		self.add("speed", "sets game step", |bot, cmd| {
+			match cmd
+				.arg(0)
+				.and_then(|arg| arg.parse::<u32>().ok())
+				.filter(|step| *step > 0)
+			{
+				Some(step) => bot.set_game_step(step),
+				None => bot.chat_ally("Usage: speed <loops>"),
+			}
+			Ok(())
+		});
+		self.add(
+			"kill",
+			"kills units: my, enemy, all (both players) or of given type",
+			|bot, cmd| {
+				let arg = cmd.arg(0).unwrap_or("enemy");
+				let units = match arg.to_lowercase().as_str() {
+					"my" => bot.units.my.all.clone(),
+					"enemy" => bot.units.enemy.all.clone(),
+					// Neutral units (e.g. minerals) aren't killed
+					"all" => {
+						let mut units = bot.units.my.all.clone();
+						units.extend(bot.units.enemy.all.iter().cloned());
+						units
+					}
+					_ => match unit_type_by_name(arg) {
+						Some(type_id) => bot.units.all.of_type(type_id),
+						None => {
+							bot.chat_ally(&format!("Unknown unit type: {}", arg));
+							return Ok(());
+						}
+					},
+				};
+				bot.debug
+					.kill_units(&units.iter().map(|u| u.tag()).collect::<Vec<_>>());
+				Ok(())
+			},
+		);
+		self.add("spawn", "spawns units: <type> [count] [my|enemy]", |bot, cmd| {
+			let type_id = match cmd.arg(0).and_then(unit_type_by_name) {
+				Some(type_id) => type_id,
+				None => {
+					bot.chat_ally("Usage: spawn <unit type> [count] [my|enemy]");
+					return Ok(());
+				}
+			};
+			let count = cmd.arg(1).and_then(|arg| arg.parse().ok()).unwrap_or(1);
+			let owner = match cmd.arg(2) {
+				Some(owner) if owner.eq_ignore_ascii_case("enemy") => bot.enemy_player_id,
+				_ => bot.player_id,
+			};
+			let pos = bot.game_info.map_center;
+			bot.debug.create_units(&[(type_id, Some(owner), pos, count)]);
+			Ok(())
+		});
+		self.add("fog", "reveals (off) or hides (on) the map", |bot, cmd| {
+			let reveal = match cmd.arg(0) {
+				Some("off") => true,
+				Some("on") => false,
+				_ => {
+					bot.chat_ally("Usage: fog <on|off>");
+					return Ok(());
+				}
+			};
+			// Show map cheat toggles visibility, so it's sent only when state changes
+			if reveal != bot.commands.fog_revealed {
+				bot.debug.show_map();
+				bot.commands.fog_revealed = reveal;
+			}
+			Ok(())
+		});
+	}
+}
+impl fmt::Debug for ChatCommands {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "ChatCommands({})", self.commands.len())
+	}
+}
+
+lazy_static! {
+	static ref UNIT_TYPES: FxHashMap<String, UnitTypeId> = ids_by_name();
+}
+
+// Finds unit type by its name ignoring case, e.g. "marine" or "SiegeTank"
+fn unit_type_by_name(name: &str) -> Option<UnitTypeId> {
+	UNIT_TYPES.get(&name.to_lowercase()).copied()
+}
+
+/// Parses commands from chat messages of other players and calls their handlers.
+pub(crate) fn dispatch_commands(bot: &mut Bot) -> SC2Result<()> {
+	if bot.commands.is_empty() {
+		return Ok(());
+	}
+	let commands = bot
+		.state
+		.chat
+		.iter()
+		.filter(|m| m.player_id != bot.player_id)
+		.filter_map(|m| ChatCommand::parse(m.player_id, &m.message, &bot.commands.prefix))
+		.collect::<Vec<_>>();
+
+	for cmd in &commands {
+		// Handler is taken out of registry while it's called, so it can use the registry
+		let handler = bot
+			.commands
+			.commands
+			.iter_mut()
+			.find(|c| c.name == cmd.name)
+			.and_then(|c| c.handler.take());
+		let mut handler = match handler {
+			Some(handler) => handler,
+			None => {
+				let message = format!("Unknown command: {}{}", bot.commands.prefix, cmd.name);
+				bot.chat_ally(&message);
+				continue;
+			}
+		};
+		let result = handler(bot, cmd);
+		// Handler is returned unless command was removed or replaced by itself
+		if let Some(c) = bot
+			.commands
+			.commands
+			.iter_mut()
+			.find(|c| c.name == cmd.name && c.handler.is_none())
+		{
+			c.handler = Some(handler);
+		}
+		result?;
+	}
+	Ok(())
+}
@@ -341,6 +341,7 @@ lazy_static! {
 		UnitTypeId::Tempest => UnitTypeId::FleetBeacon,
 		UnitTypeId::Carrier => UnitTypeId::FleetBeacon,
 		UnitTypeId::Mothership => UnitTypeId::FleetBeacon,
+		UnitTypeId::FleetBeacon => UnitTypeId::Stargate,
 		UnitTypeId::RoboticsFacility => UnitTypeId::CyberneticsCore,
 		UnitTypeId::RoboticsBay => UnitTypeId::RoboticsFacility,
 		UnitTypeId::Colossus => UnitTypeId::RoboticsBay,
@@ -371,6 +372,7 @@ lazy_static! {
 		UnitTypeId::Hive => UnitTypeId::InfestationPit,
 		UnitTypeId::Viper => UnitTypeId::Hive,
 		UnitTypeId::UltraliskCavern => UnitTypeId::Hive,
+		UnitTypeId::Ultralisk => UnitTypeId::UltraliskCavern,
 		UnitTypeId::GreaterSpire => UnitTypeId::Hive,
 		UnitTypeId::BroodLord => UnitTypeId::GreaterSpire,
 	];
@@ -783,6 +785,70 @@ lazy_static! {
 		UpgradeId::GlialReconstitution => vec![UnitTypeId::RoachWarren],
 		UpgradeId::CentrificalHooks => vec![UnitTypeId::BanelingNest],
 	];
+	/// Structures required to start research of upgrades (excluding researchers themselves).
+	pub static ref UPGRADE_TECH_REQUIREMENTS: HashMap<UpgradeId, UnitTypeId> = hashmap![
+		UpgradeId::TerranInfantryWeaponsLevel2 => UnitTypeId::Armory,
+		UpgradeId::TerranInfantryWeaponsLevel3 => UnitTypeId::Armory,
+		UpgradeId::TerranInfantryArmorsLevel2 => UnitTypeId::Armory,
+		UpgradeId::TerranInfantryArmorsLevel3 => UnitTypeId::Armory,
+		UpgradeId::ProtossGroundWeaponsLevel2 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossGroundWeaponsLevel3 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossGroundArmorsLevel2 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossGroundArmorsLevel3 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossShieldsLevel2 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossShieldsLevel3 => UnitTypeId::TwilightCouncil,
+		UpgradeId::ProtossAirWeaponsLevel2 => UnitTypeId::FleetBeacon,
+		UpgradeId::ProtossAirWeaponsLevel3 => UnitTypeId::FleetBeacon,
+		UpgradeId::ProtossAirArmorsLevel2 => UnitTypeId::FleetBeacon,
+		UpgradeId::ProtossAirArmorsLevel3 => UnitTypeId::FleetBeacon,
+		UpgradeId::ZergMeleeWeaponsLevel2 => UnitTypeId::Lair,
+		UpgradeId::ZergMeleeWeaponsLevel3 => UnitTypeId::Hive,
+		UpgradeId::ZergMissileWeaponsLevel2 => UnitTypeId::Lair,
+		UpgradeId::ZergMissileWeaponsLevel3 => UnitTypeId::Hive,
+		UpgradeId::ZergGroundArmorsLevel2 => UnitTypeId::Lair,
+		UpgradeId::ZergGroundArmorsLevel3 => UnitTypeId::Hive,
+		UpgradeId::ZergFlyerWeaponsLevel2 => UnitTypeId::Lair,
+		UpgradeId::ZergFlyerWeaponsLevel3 => UnitTypeId::Hive,
+		UpgradeId::ZergFlyerArmorsLevel2 => UnitTypeId::Lair,
+		UpgradeId::ZergFlyerArmorsLevel3 => UnitTypeId::Hive,
+		UpgradeId::Zerglingattackspeed => UnitTypeId::Hive,
+		UpgradeId::GlialReconstitution => UnitTypeId::Lair,
+		UpgradeId::TunnelingClaws => UnitTypeId::Lair,
+		UpgradeId::CentrificalHooks => UnitTypeId::Lair,
+	];
+	/// Previous levels required to start research of leveled upgrades.
+	pub static ref UPGRADE_PREVIOUS_LEVEL: HashMap<UpgradeId, UpgradeId> = hashmap![
+		UpgradeId::TerranInfantryWeaponsLevel2 => UpgradeId::TerranInfantryWeaponsLevel1,
+		UpgradeId::TerranInfantryWeaponsLevel3 => UpgradeId::TerranInfantryWeaponsLevel2,
+		UpgradeId::TerranInfantryArmorsLevel2 => UpgradeId::TerranInfantryArmorsLevel1,
+		UpgradeId::TerranInfantryArmorsLevel3 => UpgradeId::TerranInfantryArmorsLevel2,
+		UpgradeId::TerranVehicleWeaponsLevel2 => UpgradeId::TerranVehicleWeaponsLevel1,
+		UpgradeId::TerranVehicleWeaponsLevel3 => UpgradeId::TerranVehicleWeaponsLevel2,
+		UpgradeId::TerranShipWeaponsLevel2 => UpgradeId::TerranShipWeaponsLevel1,
+		UpgradeId::TerranShipWeaponsLevel3 => UpgradeId::TerranShipWeaponsLevel2,
+		UpgradeId::TerranVehicleAndShipArmorsLevel2 => UpgradeId::TerranVehicleAndShipArmorsLevel1,
+		UpgradeId::TerranVehicleAndShipArmorsLevel3 => UpgradeId::TerranVehicleAndShipArmorsLevel2,
+		UpgradeId::ProtossGroundWeaponsLevel2 => UpgradeId::ProtossGroundWeaponsLevel1,
+		UpgradeId::ProtossGroundWeaponsLevel3 => UpgradeId::ProtossGroundWeaponsLevel2,
+		UpgradeId::ProtossGroundArmorsLevel2 => UpgradeId::ProtossGroundArmorsLevel1,
+		UpgradeId::ProtossGroundArmorsLevel3 => UpgradeId::ProtossGroundArmorsLevel2,
+		UpgradeId::ProtossShieldsLevel2 => UpgradeId::ProtossShieldsLevel1,
+		UpgradeId::ProtossShieldsLevel3 => UpgradeId::ProtossShieldsLevel2,
+		UpgradeId::ProtossAirWeaponsLevel2 => UpgradeId::ProtossAirWeaponsLevel1,
+		UpgradeId::ProtossAirWeaponsLevel3 => UpgradeId::ProtossAirWeaponsLevel2,
+		UpgradeId::ProtossAirArmorsLevel2 => UpgradeId::ProtossAirArmorsLevel1,
+		UpgradeId::ProtossAirArmorsLevel3 => UpgradeId::ProtossAirArmorsLevel2,
+		UpgradeId::ZergMeleeWeaponsLevel2 => UpgradeId::ZergMeleeWeaponsLevel1,
+		UpgradeId::ZergMeleeWeaponsLevel3 => UpgradeId::ZergMeleeWeaponsLevel2,
+		UpgradeId::ZergMissileWeaponsLevel2 => UpgradeId::ZergMissileWeaponsLevel1,
+		UpgradeId::ZergMissileWeaponsLevel3 => UpgradeId::ZergMissileWeaponsLevel2,
+		UpgradeId::ZergGroundArmorsLevel2 => UpgradeId::ZergGroundArmorsLevel1,
+		UpgradeId::ZergGroundArmorsLevel3 => UpgradeId::ZergGroundArmorsLevel2,
+		UpgradeId::ZergFlyerWeaponsLevel2 => UpgradeId::ZergFlyerWeaponsLevel1,
+		UpgradeId::ZergFlyerWeaponsLevel3 => UpgradeId::ZergFlyerWeaponsLevel2,
+		UpgradeId::ZergFlyerArmorsLevel2 => UpgradeId::ZergFlyerArmorsLevel1,
+		UpgradeId::ZergFlyerArmorsLevel3 => UpgradeId::ZergFlyerArmorsLevel2,
+	];
 
 	pub static ref DAMAGE_BONUS_PER_UPGRADE: HashMap<UnitTypeId, BonusesForTarget> = hashmap![
 		// Protoss
@@ -1008,6 +1074,151 @@ lazy_static! {
 			speed: 2.0,
 		}],
 	];
+	/// Maximum health and shield of units without upgrades.
+	pub(crate) static ref UNIT_HITS: HashMap<UnitTypeId, (u32, u32)> = hashmap![
+		// Terran
+		UnitTypeId::SCV => (45, 0),
+		UnitTypeId::MULE => (60, 0),
+		UnitTypeId::Marine => (45, 0),
+		UnitTypeId::Marauder => (125, 0),
+		UnitTypeId::Reaper => (60, 0),
+		UnitTypeId::Ghost => (100, 0),
+		UnitTypeId::Hellion => (90, 0),
+		UnitTypeId::HellionTank => (135, 0),
+		UnitTypeId::SiegeTank => (175, 0),
+		UnitTypeId::SiegeTankSieged => (175, 0),
+		UnitTypeId::Cyclone => (120, 0),
+		UnitTypeId::WidowMine => (90, 0),
+		UnitTypeId::WidowMineBurrowed => (90, 0),
+		UnitTypeId::Thor => (400, 0),
+		UnitTypeId::ThorAP => (400, 0),
+		UnitTypeId::VikingFighter => (135, 0),
+		UnitTypeId::VikingAssault => (135, 0),
+		UnitTypeId::Medivac => (150, 0),
+		UnitTypeId::Liberator => (180, 0),
+		UnitTypeId::LiberatorAG => (180, 0),
+		UnitTypeId::Raven => (140, 0),
+		UnitTypeId::Banshee => (140, 0),
+		UnitTypeId::Battlecruiser => (550, 0),
+		UnitTypeId::AutoTurret => (150, 0),
+		UnitTypeId::MissileTurret => (250, 0),
+		UnitTypeId::Bunker => (400, 0),
+		UnitTypeId::PlanetaryFortress => (1500, 0),
+		// Protoss
+		UnitTypeId::Probe => (20, 20),
+		UnitTypeId::Zealot => (100, 50),
+		UnitTypeId::Sentry => (40, 40),
+		UnitTypeId::Stalker => (80, 80),
+		UnitTypeId::Adept => (70, 70),
+		UnitTypeId::HighTemplar => (40, 40),
+		UnitTypeId::DarkTemplar => (40, 80),
+		UnitTypeId::Archon => (10, 350),
+		UnitTypeId::Immortal => (200, 100),
+		UnitTypeId::Colossus => (200, 150),
+		UnitTypeId::Disruptor => (100, 100),
+		UnitTypeId::Observer => (40, 20),
+		UnitTypeId::WarpPrism => (80, 100),
+		UnitTypeId::Phoenix => (120, 60),
+		UnitTypeId::VoidRay => (150, 100),
+		UnitTypeId::Oracle => (100, 60),
+		UnitTypeId::Tempest => (200, 100),
+		UnitTypeId::Carrier => (300, 150),
+		UnitTypeId::Interceptor => (40, 40),
+		UnitTypeId::Mothership => (350, 350),
+		UnitTypeId::PhotonCannon => (150, 150),
+		UnitTypeId::ShieldBattery => (150, 150),
+		// Zerg
+		UnitTypeId::Drone => (40, 0),
+		UnitTypeId::Queen => (175, 0),
+		UnitTypeId::Zergling => (35, 0),
+		UnitTypeId::Baneling => (30, 0),
+		UnitTypeId::Roach => (145, 0),
+		UnitTypeId::Ravager => (120, 0),
+		UnitTypeId::Hydralisk => (90, 0),
+		UnitTypeId::LurkerMP => (190, 0),
+		UnitTypeId::LurkerMPBurrowed => (190, 0),
+		UnitTypeId::Infestor => (90, 0),
+		UnitTypeId::SwarmHostMP => (160, 0),
+		UnitTypeId::Ultralisk => (500, 0),
+		UnitTypeId::Mutalisk => (120, 0),
+		UnitTypeId::Corruptor => (200, 0),
+		UnitTypeId::BroodLord => (225, 0),
+		UnitTypeId::Viper => (150, 0),
+		UnitTypeId::Overlord => (200, 0),
+		UnitTypeId::Overseer => (200, 0),
+		UnitTypeId::Broodling => (30, 0),
+		UnitTypeId::LocustMP => (50, 0),
+		UnitTypeId::SpineCrawler => (300, 0),
+		UnitTypeId::SporeCrawler => (400, 0),
+	];
+	/// Radiuses of units which aren't structures, used when there's no observed unit to take it from.
+	pub(crate) static ref UNIT_RADIUS: HashMap<UnitTypeId, f32> = hashmap![
+		// Terran
+		UnitTypeId::SCV => 0.375,
+		UnitTypeId::MULE => 0.375,
+		UnitTypeId::Marine => 0.375,
+		UnitTypeId::Marauder => 0.5625,
+		UnitTypeId::Reaper => 0.375,
+		UnitTypeId::Ghost => 0.375,
+		UnitTypeId::Hellion => 0.625,
+		UnitTypeId::HellionTank => 0.625,
+		UnitTypeId::WidowMine => 0.5,
+		UnitTypeId::WidowMineBurrowed => 0.5,
+		UnitTypeId::SiegeTank => 0.875,
+		UnitTypeId::SiegeTankSieged => 0.875,
+		UnitTypeId::Cyclone => 0.75,
+		UnitTypeId::Thor => 1.25,
+		UnitTypeId::ThorAP => 1.25,
+		UnitTypeId::VikingFighter => 0.75,
+		UnitTypeId::VikingAssault => 0.75,
+		UnitTypeId::Medivac => 0.75,
+		UnitTypeId::Liberator => 0.75,
+		UnitTypeId::LiberatorAG => 0.75,
+		UnitTypeId::Raven => 0.625,
+		UnitTypeId::Banshee => 0.75,
+		UnitTypeId::Battlecruiser => 1.25,
+		UnitTypeId::AutoTurret => 0.75,
+		// Protoss
+		UnitTypeId::Probe => 0.375,
+		UnitTypeId::Zealot => 0.5,
+		UnitTypeId::Stalker => 0.625,
+		UnitTypeId::Sentry => 0.5,
+		UnitTypeId::Adept => 0.5,
+		UnitTypeId::HighTemplar => 0.375,
+		UnitTypeId::DarkTemplar => 0.375,
+		UnitTypeId::Archon => 1.0,
+		UnitTypeId::Immortal => 0.75,
+		UnitTypeId::Colossus => 1.0,
+		UnitTypeId::Disruptor => 0.5,
+		UnitTypeId::Observer => 0.5,
+		UnitTypeId::WarpPrism => 0.875,
+		UnitTypeId::Phoenix => 0.75,
+		UnitTypeId::VoidRay => 1.0,
+		UnitTypeId::Oracle => 0.75,
+		UnitTypeId::Tempest => 1.25,
+		UnitTypeId::Carrier => 1.25,
+		UnitTypeId::Mothership => 1.375,
+		// Zerg
+		UnitTypeId::Drone => 0.375,
+		UnitTypeId::Zergling => 0.375,
+		UnitTypeId::Baneling => 0.375,
+		UnitTypeId::Queen => 0.875,
+		UnitTypeId::Roach => 0.625,
+		UnitTypeId::Ravager => 0.75,
+		UnitTypeId::Hydralisk => 0.625,
+		UnitTypeId::LurkerMP => 0.9375,
+		UnitTypeId::LurkerMPBurrowed => 0.9375,
+		UnitTypeId::Infestor => 0.75,
+		UnitTypeId::SwarmHostMP => 0.8125,
+		UnitTypeId::Ultralisk => 1.0,
+		UnitTypeId::Overlord => 1.0,
+		UnitTypeId::Overseer => 0.75,
+		UnitTypeId::Mutalisk => 0.5,
+		UnitTypeId::Corruptor => 0.625,
+		UnitTypeId::BroodLord => 1.0,
+		UnitTypeId::Viper => 0.75,
+		UnitTypeId::LocustMP => 0.375,
+	];
 	/// Radiuses of Inhibitor Zones mapped to their ids.
 	pub static ref INHIBITOR_ZONE_RADIUS: HashMap<UnitTypeId, f32> = hashmap![
 		UnitTypeId::InhibitorZoneSmall => 4.0,
@@ -1,20 +1,53 @@
 //! Items for interacting with Debug API.
+//!
+//! Besides basic shapes [`Debugger`] can draw heatmaps, paths, unit labels and circles on terrain.
+//! Drawings can be organized into named layers, which can be toggled at runtime,
+//! and made persistent, so they are drawn for given number of game loops without being pushed again.
+//!
+//! ```
+//! # let mut bot = rust_sc2::bot::Bot::default();
+//! use rust_sc2::prelude::*;
+//!
+//! bot.debug.in_layer("paths", |debug| {
+//!     debug.draw_path(&[Point2::new(10.0, 10.0), Point2::new(20.0, 15.0)], Some((0, 255, 0)));
+//! });
+//! bot.debug.persistent(22, |debug| {
+//!     debug.draw_circle(Point2::new(30.0, 30.0), 5.0, None);
+//! });
+//! bot.debug.toggle_layer("paths");
+//! assert!(!bot.debug.is_layer_enabled("paths"));
+//! ```
 
 use crate::{
+	bot::{LockOwned, LockU32, Rs},
 	geometry::{Point2, Point3},
 	ids::UnitTypeId,
+	pixel_map::{ByteMap, PixelMap},
+	unit::Unit,
 	IntoProto,
 };
 use num_traits::ToPrimitive;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sc2_proto::debug::{
 	DebugBox, DebugCommand as ProtoDebugCommand, DebugDraw as ProtoDebugDraw, DebugEndGame_EndResult,
 	DebugGameState as ProtoDebugGameState, DebugLine, DebugSetUnitValue_UnitValue, DebugSphere, DebugText,
 };
+use std::{f32::consts::TAU, fmt::Write};
 
 type Color = (u32, u32, u32);
 type ScreenPos = (f32, f32);
 
+/// Offset of drawings above the terrain, so they aren't hidden by it.
+const TERRAIN_OFFSET: f32 = 0.1;
+/// Number of segments in circles drawn on terrain.
+const CIRCLE_SEGMENTS: usize = 24;
+
+struct PersistentDraw {
+	layer: Option<String>,
+	expires: u32,
+	drawing: DebugDraw,
+}
+
 /// Helper struct for interacting with Debug API.
 /// Can be accessed through [`debug`] field of bot.
 ///
@@ -24,9 +57,23 @@ pub struct Debugger {
 	debug_commands: Vec<DebugCommand>,
 	debug_drawings: Vec<DebugDraw>,
 	kill_tags: FxHashSet<u64>,
+	layers: FxHashMap<String, bool>,
+	current_layer: Option<String>,
+	duration: Option<u32>,
+	persistent_drawings: Vec<PersistentDraw>,
+	pub(crate) terrain_height: Rs<ByteMap>,
+	pub(crate) game_loop: Rs<LockU32>,
 }
 impl Debugger {
 	pub(crate) fn get_commands(&mut self) -> &[DebugCommand] {
+		let game_loop = self.game_loop.get_locked();
+		self.persistent_drawings.retain(|item| item.expires > game_loop);
+		let persistent = self
+			.visible_persistent()
+			.map(|item| item.drawing.clone())
+			.collect::<Vec<_>>();
+		self.debug_drawings.extend(persistent);
+
 		let commands = &mut self.debug_commands;
 
 		if !self.debug_drawings.is_empty() {
@@ -42,9 +89,100 @@ impl Debugger {
 		self.debug_commands.clear();
 	}
 
+	// Persistent drawings of disabled layers are kept, but not sent until layer is enabled again
+	fn visible_persistent(&self) -> impl Iterator<Item = &PersistentDraw> {
+		let game_loop = self.game_loop.get_locked();
+		self.persistent_drawings.iter().filter(move |item| {
+			item.expires > game_loop
+				&& item
+					.layer
+					.as_ref()
+					.is_none_or(|layer| self.is_layer_enabled(layer))
+		})
+	}
+	fn push(&mut self, drawing: DebugDraw) {
+		match self.duration {
+			Some(game_loops) => self.persistent_drawings.push(PersistentDraw {
+				layer: self.current_layer.clone(),
+				expires: self.game_loop.get_locked() + game_loops,
+				drawing,
+			}),
+			None => {
+				if self
+					.current_layer
+					.as_ref()
+					.is_none_or(|layer| self.is_layer_enabled(layer))
+				{
+					self.debug_drawings.push(drawing);
+				}
+			}
+		}
+	}
+
+	/// Returns number of drawings which will be sent to the game on the current step,
+	/// including visible persistent ones.
+	pub fn pending_drawings(&self) -> usize {
+		self.debug_drawings.len() + self.visible_persistent().count()
+	}
+
+	/// Draws everything inside given closure on the layer with given name.
+	/// Layers are enabled by default and can be toggled with
+	/// [`enable_layer`](Self::enable_layer), [`disable_layer`](Self::disable_layer)
+	/// and [`toggle_layer`](Self::toggle_layer).
+	pub fn in_layer<F: FnOnce(&mut Self)>(&mut self, layer: &str, f: F) {
+		self.layers.entry(layer.to_string()).or_insert(true);
+		let previous = self.current_layer.replace(layer.to_string());
+		f(self);
+		self.current_layer = previous;
+	}
+	/// Makes everything drawn inside given closure persistent:
+	/// drawings will be sent every step until given number of game loops passes.
+	pub fn persistent<F: FnOnce(&mut Self)>(&mut self, game_loops: u32, f: F) {
+		let previous = self.duration.replace(game_loops.max(1));
+		f(self);
+		self.duration = previous;
+	}
+	/// Enables layer with given name.
+	pub fn enable_layer(&mut self, layer: &str) {
+		self.layers.insert(layer.to_string(), true);
+	}
+	/// Disables layer with given name, so nothing is drawn on it.
+	/// Persistent drawings of disabled layer are hidden, but not removed.
+	pub fn disable_layer(&mut self, layer: &str) {
+		self.layers.insert(layer.to_string(), false);
+	}
+	/// Toggles layer with given name. Returns `true` if layer is enabled now.
+	pub fn toggle_layer(&mut self, layer: &str) -> bool {
+		let enabled = !self.is_layer_enabled(layer);
+		self.layers.insert(layer.to_string(), enabled);
+		enabled
+	}
+	/// Checks if layer with given name is enabled.
+	pub fn is_layer_enabled(&self, layer: &str) -> bool {
+		self.layers.get(layer).copied().unwrap_or(true)
+	}
+	/// Returns names of all known layers with their states.
+	pub fn layers(&self) -> impl Iterator<Item = (&str, bool)> {
+		self.layers
+			.iter()
+			.map(|(name, enabled)| (name.as_str(), *enabled))
+	}
+	/// Removes all persistent drawings.
+	pub fn clear_persistent(&mut self) {
+		self.persistent_drawings.clear();
+	}
+
+	/// Returns position in 3d space on the terrain surface.
+	pub fn on_terrain(&self, pos: Point2) -> Point3 {
+		let height = self
+			.terrain_height
+			.get(<(usize, usize)>::from(pos))
+			.map_or(0.0, |h| *h as f32 * 32.0 / 255.0 - 16.0);
+		pos.to3(height + TERRAIN_OFFSET)
+	}
+
 	fn draw_text(&mut self, text: &str, pos: DebugPos, color: Option<Color>, size: Option<u32>) {
-		self.debug_drawings
-			.push(DebugDraw::Text(text.to_string(), pos, color, size));
+		self.push(DebugDraw::Text(text.to_string(), pos, color, size));
 	}
 	/// Draws text in game world with 3d coordinates.
 	pub fn draw_text_world(&mut self, text: &str, pos: Point3, color: Option<Color>, size: Option<u32>) {
@@ -62,21 +200,85 @@ impl Debugger {
 	}
 	/// Draws line in game world from `p0` to `p1`.
 	pub fn draw_line(&mut self, p0: Point3, p1: Point3, color: Option<Color>) {
-		self.debug_drawings.push(DebugDraw::Line(p0, p1, color));
+		self.push(DebugDraw::Line(p0, p1, color));
 	}
 	/// Draws box in game world with corners `p0` and `p1`.
 	pub fn draw_box(&mut self, p0: Point3, p1: Point3, color: Option<Color>) {
-		self.debug_drawings.push(DebugDraw::Box(p0, p1, color));
+		self.push(DebugDraw::Box(p0, p1, color));
 	}
 	/// Draws cube in game world with given half size of edge.
 	pub fn draw_cube(&mut self, pos: Point3, half_edge: f32, color: Option<Color>) {
 		let offset = Point3::new(half_edge, half_edge, half_edge);
-		self.debug_drawings
-			.push(DebugDraw::Box(pos - offset, pos + offset, color));
+		self.push(DebugDraw::Box(pos - offset, pos + offset, color));
 	}
 	/// Draws sphere in game world with given radius.
 	pub fn draw_sphere(&mut self, pos: Point3, radius: f32, color: Option<Color>) {
-		self.debug_drawings.push(DebugDraw::Sphere(pos, radius, color));
+		self.push(DebugDraw::Sphere(pos, radius, color));
+	}
+	/// Draws circle with given radius on the terrain surface.
+	pub fn draw_circle(&mut self, pos: Point2, radius: f32, color: Option<Color>) {
+		let points = (0..=CIRCLE_SEGMENTS)
+			.map(|i| self.on_terrain(pos.towards_angle(i as f32 * TAU / CIRCLE_SEGMENTS as f32, radius)))
+			.collect::<Vec<_>>();
+		for segment in points.windows(2) {
+			self.draw_line(segment[0], segment[1], color);
+		}
+	}
+	/// Draws path on the terrain surface connecting given points.
+	pub fn draw_path(&mut self, path: &[Point2], color: Option<Color>) {
+		let points = path.iter().map(|p| self.on_terrain(*p)).collect::<Vec<_>>();
+		for segment in points.windows(2) {
+			self.draw_line(segment[0], segment[1], color);
+		}
+		if let Some(last) = points.last() {
+			self.draw_sphere(*last, 0.25, color);
+		}
+	}
+	/// Draws label with type, tag and orders of given unit above it.
+	pub fn draw_unit_label(&mut self, unit: &Unit, color: Option<Color>) {
+		let mut text = format!("{:?} [{}]", unit.type_id(), unit.tag());
+		for order in unit.orders() {
+			let _ = write!(text, "\n{:?} {:?}", order.ability, order.target);
+		}
+		let pos = unit.position3d();
+		self.draw_text_world(&text, pos.offset(0.0, 0.0, unit.radius()), color, None);
+	}
+	/// Draws heatmap of given size on the terrain surface.
+	///
+	/// `value` is called for each tile and returns value in range `0..=1`,
+	/// which is drawn with color from blue (`0`) to red (`1`). Tiles with `None` aren't drawn.
+	///
+	/// Only call it for small areas, since every tile is a separate drawing.
+	pub fn draw_heatmap<F>(&mut self, (width, height): (usize, usize), value: F)
+	where
+		F: Fn((usize, usize)) -> Option<f32>,
+	{
+		for x in 0..width {
+			for y in 0..height {
+				if let Some(v) = value((x, y)) {
+					let v = v.clamp(0.0, 1.0);
+					let color = ((v * 255.0) as u32, 0, ((1.0 - v) * 255.0) as u32);
+					let pos = self.on_terrain(Point2::new(x as f32 + 0.5, y as f32 + 0.5));
+					self.draw_box(
+						pos.offset(-0.4, -0.4, 0.0),
+						pos.offset(0.4, 0.4, 0.0),
+						Some(color),
+					);
+				}
+			}
+		}
+	}
+	/// Draws tiles of [`PixelMap`] which are set with given color.
+	pub fn draw_pixel_map(&mut self, map: &PixelMap, color: Option<Color>) {
+		let (width, height) = map.dim();
+		for x in 0..width {
+			for y in 0..height {
+				if map[(x, y)].is_set() {
+					let pos = self.on_terrain(Point2::new(x as f32 + 0.5, y as f32 + 0.5));
+					self.draw_box(pos.offset(-0.4, -0.4, 0.0), pos.offset(0.4, 0.4, 0.0), color);
+				}
+			}
+		}
 	}
 	/// Spawns units using given commands in format: (unit type, owner's player id, position, count).
 	pub fn create_units<'a, T>(&mut self, cmds: T)
@@ -68,7 +68,7 @@ impl FromProto<ResponseData> for GameData {
 }
 
 /// Cost of an item (`UnitTypeId` or `UpgradeId`) in resources, supply and time.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cost {
 	pub minerals: u32,
@@ -10,9 +10,12 @@ use crate::{
 use rustc_hash::FxHashMap;
 use sc2_proto::sc2api::ResponseGameInfo;
 use std::path::Path;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Structure where all map information stored.
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameInfo {
 	/// Map name bot playing on, which depends on sc2 localization language.
 	pub map_name: String,
@@ -106,6 +109,7 @@ impl FromProto<ResponseGameInfo> for GameInfo {
 
 /// Information about player.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PlayerInfo {
 	/// Player id.
 	pub id: u32,
@@ -12,18 +12,21 @@ use crate::{
 	Event, FromProto, Player, SC2Result,
 };
 use num_traits::FromPrimitive;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use sc2_proto::{
-	query::RequestQueryAvailableAbilities,
+	query::{RequestQueryAvailableAbilities, ResponseQueryAvailableAbilities},
 	raw::{Alliance as ProtoAlliance, PowerSource as ProtoPowerSource},
 	sc2api::{Alert as ProtoAlert, Request, ResponseObservation},
 };
 use std::ops::{Deref, DerefMut};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Information about current state on current step.
 ///
 /// Can be accessed through [`state`](crate::bot::Bot::state) field.
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GameState {
 	/// Actions executed on previous step.
 	pub actions: Vec<Action>,
@@ -43,6 +46,30 @@ pub(crate) fn update_state<B>(
 where
 	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
 {
+	// Available abilities
+	let mut req = Request::new();
+	let req_query_abilities = req.mut_query().mut_abilities();
+	for u in response_observation.get_observation().get_raw_data().get_units() {
+		if matches!(u.get_alliance(), ProtoAlliance::value_Self) {
+			let mut req_unit = RequestQueryAvailableAbilities::new();
+			req_unit.set_unit_tag(u.get_tag());
+			req_query_abilities.push(req_unit);
+		}
+	}
+	let res = bot.api().send(req)?;
+
+	Ok(apply_observation(
+		bot,
+		response_observation,
+		res.get_query().get_abilities(),
+	))
+}
+
+pub(crate) fn apply_observation(
+	bot: &mut Bot,
+	response_observation: &ResponseObservation,
+	abilities: &[ResponseQueryAvailableAbilities],
+) -> Vec<Event> {
 	// Game state
 	let state = &mut bot.state;
 
@@ -155,6 +182,7 @@ where
 			bot.last_units_seen.write_lock().remove(u);
 			Some(Alliance::Own)
 		} else {
+			bot.seen_enemy_tags.remove(u);
 			let removed = bot.saved_hallucinations.remove(u);
 
 			#[cfg(feature = "enemies_cache")]
@@ -187,12 +215,21 @@ where
 	raw.dead_units = dead_units;
 
 	// Upgrades
-	*raw.upgrades.write_lock() = raw_player
+	let upgrades = raw_player
 		.get_upgrade_ids()
 		.iter()
 		.filter(|&u| UpgradeId::from_u32(*u).is_some())
 		.map(|u| UpgradeId::from_u32(*u).unwrap_or_else(|| panic!("There's no `UpgradeId` with value {}", u)))
 		.collect::<FxHashSet<_>>();
+	{
+		let last_upgrades = raw.upgrades.read_lock();
+		for u in &upgrades {
+			if !last_upgrades.contains(u) {
+				events.push(Event::UpgradeComplete(*u));
+			}
+		}
+	}
+	*raw.upgrades.write_lock() = upgrades;
 
 	// Map
 	let map_state = res_raw.get_map_state();
@@ -200,20 +237,7 @@ where
 	*raw.creep.write_lock() = PixelMap::from_proto(map_state.get_creep());
 
 	// Available abilities
-	let mut req = Request::new();
-	let req_query_abilities = req.mut_query().mut_abilities();
-	for u in res_raw.get_units() {
-		if matches!(u.get_alliance(), ProtoAlliance::value_Self) {
-			let mut req_unit = RequestQueryAvailableAbilities::new();
-			req_unit.set_unit_tag(u.get_tag());
-			req_query_abilities.push(req_unit);
-		}
-	}
-
-	let res = bot.api().send(req)?;
-	*bot.abilities_units.write_lock() = res
-		.get_query()
-		.get_abilities()
+	*bot.abilities_units.write_lock() = abilities
 		.iter()
 		.map(|a| {
 			(
@@ -238,6 +262,14 @@ where
 	// Set visiblity
 	bot.state.observation.raw.visibility = visibility;
 
+	// Saving previous state of units to compare with the new one
+	let last_units = bot
+		.units
+		.all
+		.iter()
+		.map(|u| (u.tag(), (u.type_id(), u.is_idle())))
+		.collect::<FxHashMap<_, _>>();
+
 	// Updating units
 	bot.update_units(units);
 
@@ -275,6 +307,45 @@ where
 		bot.under_construction.remove(&tag);
 	}
 
+	{
+		let last_units_hits = bot.last_units_hits.read_lock();
+		for u in &bot.units.all {
+			let tag = u.tag();
+			if let Some(&(last_type, last_idle)) = last_units.get(&tag) {
+				if let Some(&last_hits) = last_units_hits.get(&tag) {
+					let hits = u.hits();
+					if hits < last_hits && u.is_visible() {
+						events.push(Event::UnitDamaged(tag, last_hits - hits));
+					}
+				}
+				let type_id = u.type_id();
+				if type_id != last_type {
+					events.push(Event::UnitTypeChanged(tag, last_type, type_id));
+				}
+				if !last_idle && u.is_mine() && u.is_idle() {
+					events.push(Event::UnitIdle(tag));
+				}
+			}
+		}
+	}
+	let inner: &mut Bot = bot;
+	for u in &inner.units.enemy.all {
+		if !u.is_snapshot() && inner.seen_enemy_tags.insert(u.tag()) {
+			events.push(Event::EnemyUnitSeen(u.tag()));
+		}
+	}
+	// Expansion is known until its townhall dies, so it isn't reported again
+	// when it goes out of vision or when townhall lifts off and lands back
+	let dead_units = &bot.state.observation.raw.dead_units;
+	bot.enemy_expansions.retain(|_, tag| !dead_units.contains(tag));
+	for exp in &bot.expansions {
+		if let (true, Some(tag)) = (exp.alliance.is_enemy(), exp.base) {
+			if exp.loc != bot.enemy_start && bot.enemy_expansions.insert(exp.loc, tag).is_none() {
+				events.push(Event::EnemyExpanded(exp.loc));
+			}
+		}
+	}
+
 	if bot.enemy_race.is_random() {
 		if let Some(race) = bot
 			.units
@@ -289,11 +360,12 @@ where
 		}
 	}
 
-	Ok(events)
+	events
 }
 
 /// Messege in game chat.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ChatMessage {
 	/// Id of player who sent that message.
 	pub player_id: u32,
@@ -304,6 +376,7 @@ pub struct ChatMessage {
 /// Bot's observation stored here.
 /// Can be accessed through [`state.observation`](GameState::observation).
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Observation {
 	pub(crate) game_loop: Rs<LockU32>,
 	/// Common information from the observation.
@@ -326,6 +399,7 @@ impl Observation {
 /// Bot's observation stored here.
 /// Can be accessed through [`state.observation.raw`](Observation::raw).
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RawData {
 	/// Protoss power from pylons.
 	pub psionic_matrix: Vec<PsionicMatrix>,
@@ -347,6 +421,7 @@ pub struct RawData {
 
 /// Power matrix from the pylon or warp prism, used to give power to buildings and warp units on it.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PsionicMatrix {
 	/// Position of psionic matrix source.
 	pub pos: Point2,
@@ -370,6 +445,7 @@ impl FromProto<&ProtoPowerSource> for PsionicMatrix {
 ///
 /// All effects stored in [state.observation.raw.effects](RawData::effects).
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Effect {
 	/// Type of the effect.
 	pub id: EffectId,
@@ -385,6 +461,7 @@ pub struct Effect {
 
 /// The alliance of unit or effect to your bot.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Alliance {
 	/// Your own objects.
 	Own,
@@ -422,6 +499,7 @@ impl FromProto<ProtoAlliance> for Alliance {
 
 /// Radar point on the map.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Radar {
 	/// Position where radar is.
 	pub pos: Point2,
@@ -431,6 +509,7 @@ pub struct Radar {
 
 /// Common information of player.
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Common {
 	/// In-game player id.
 	pub player_id: u32,
@@ -460,7 +539,8 @@ pub struct Common {
 /// All alerts stored in [`state.observation.alerts`](Observation::alerts).
 #[allow(missing_docs)]
 #[allow(clippy::enum_variant_names)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Alert {
 	AlertError,
 	AddOnComplete,
@@ -515,6 +595,7 @@ impl FromProto<ProtoAlert> for Alert {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AvailableAbility {
 	pub id: AbilityId,
 	pub requires_point: bool,
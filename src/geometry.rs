@@ -9,10 +9,13 @@ use std::{
 	iter::Sum,
 	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Size of 2D rectangle.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Size {
 	pub x: usize,
 	pub y: usize,
@@ -27,6 +30,7 @@ impl Size {
 /// Rectangle from (x0, y0) to (x1, y1).
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect {
 	pub x0: usize,
 	pub y0: usize,
@@ -43,6 +47,7 @@ impl Rect {
 /// Point on 2D grid, the most frequently used geometric primitive.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point2 {
 	pub x: f32,
 	pub y: f32,
@@ -436,6 +441,7 @@ impl IntoProto<Point2D> for Point2 {
 /// Point in 3D game world.
 #[allow(missing_docs)]
 #[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point3 {
 	pub x: f32,
 	pub y: f32,
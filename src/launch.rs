@@ -0,0 +1,335 @@
+//! Configuration of SC2 client launch: installation, maps, versions and process arguments.
+//!
+//! By default everything is detected automatically like before: installation is taken from
+//! `SC2PATH` environment variable or default location for current OS, maps are searched in
+//! `Maps` folder of installation and the latest installed version is launched.
+//! [`LaunchConfig`] allows to override any of this and reports [`LaunchError`] instead of panicking.
+//!
+//! ```no_run
+//! use rust_sc2::launch::{LaunchConfig, VersionRegistry, WindowGeometry};
+//! use rust_sc2::prelude::*;
+//! # #[bot] #[derive(Default)] struct MyBot;
+//! # impl Player for MyBot {
+//! #     fn get_player_settings(&self) -> PlayerSettings<'_> { PlayerSettings::new(Race::Random) }
+//! # }
+//!
+//! # fn main() -> SC2Result<()> {
+//! let mut versions = VersionRegistry::default();
+//! versions.load("versions.txt")?;
+//!
+//! let config = LaunchConfig {
+//!     sc2_path: Some("/opt/StarCraftII".into()),
+//!     map_dirs: vec!["./maps".into()],
+//!     window: Some(WindowGeometry::new(1024, 768)),
+//!     versions,
+//!     ..Default::default()
+//! };
+//! run_vs_computer(
+//!     &mut MyBot::default(),
+//!     Computer::new(Race::Random, Difficulty::VeryEasy, None),
+//!     "AcropolisLE",
+//!     LaunchOptions {
+//!         sc2_version: Some("4.10"),
+//!         config,
+//!         ..Default::default()
+//!     },
+//! )
+//! # }
+//! ```
+
+use crate::paths;
+use std::{
+	error::Error,
+	fmt, fs, io,
+	path::{Path, PathBuf},
+};
+
+/// Error occurred while looking for SC2 installation, maps or launching the client.
+#[derive(Debug)]
+pub enum LaunchError {
+	/// SC2 installation wasn't found at given path.
+	InstallationNotFound(PathBuf),
+	/// `Versions` folder of installation doesn't contain any `Base*` versions.
+	NoVersions(PathBuf),
+	/// Requested version isn't present in [`VersionRegistry`].
+	UnknownVersion(String),
+	/// None of given map directories exist.
+	NoMapDirectories(Vec<PathBuf>),
+	/// Map wasn't found in any of searched directories.
+	MapNotFound {
+		/// Name of the map.
+		map: String,
+		/// Directories where map was searched.
+		searched: Vec<PathBuf>,
+	},
+	/// Line of versions file can't be parsed.
+	InvalidVersionLine {
+		/// Number of the line (starting from 1).
+		line: usize,
+		/// Content of the line.
+		content: String,
+	},
+	/// Command line argument is invalid or doesn't have a value.
+	InvalidArgument(String),
+	/// Failed to convert path with `winepath`.
+	Winepath(String),
+	/// I/O error while reading files or spawning the process.
+	Io(io::Error),
+}
+impl fmt::Display for LaunchError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InstallationNotFound(path) => {
+				write!(f, "Can't find SC2 installation in: {}", path.display())
+			}
+			Self::NoVersions(path) => write!(f, "Can't find any SC2 versions in: {}", path.display()),
+			Self::UnknownVersion(version) => write!(f, "Can't find info about version: {}", version),
+			Self::NoMapDirectories(dirs) => write!(f, "Can't find maps folder in: {:?}", dirs),
+			Self::MapNotFound { map, searched } => write!(f, "Map {} doesn't exist in: {:?}", map, searched),
+			Self::InvalidVersionLine { line, content } => {
+				write!(f, "Invalid version info on line {}: {:?}", line, content)
+			}
+			Self::InvalidArgument(arg) => write!(f, "Invalid command line argument: {}", arg),
+			Self::Winepath(err) => write!(f, "Failed to run winepath: {}", err),
+			Self::Io(err) => write!(f, "{}", err),
+		}
+	}
+}
+impl Error for LaunchError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Io(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+impl From<io::Error> for LaunchError {
+	fn from(err: io::Error) -> Self {
+		Self::Io(err)
+	}
+}
+
+/// Base version and data hash of SC2 version, needed to launch it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+	/// Number of `Base*` folder in `Versions` folder of installation.
+	pub base_version: u32,
+	/// Hash of game data passed to the client with `-dataVersion`.
+	pub data_hash: String,
+}
+
+/// Known SC2 versions, initialized with built-in list and can be extended from file.
+#[derive(Debug, Clone)]
+pub struct VersionRegistry(Vec<(String, VersionInfo)>);
+impl Default for VersionRegistry {
+	fn default() -> Self {
+		let mut registry = Self::empty();
+		for (names, base_version, data_hash) in BUILTIN_VERSIONS {
+			for name in names.split('|') {
+				registry.insert(name, *base_version, data_hash);
+			}
+		}
+		registry
+	}
+}
+impl VersionRegistry {
+	/// Makes registry without any versions.
+	pub fn empty() -> Self {
+		Self(Vec::new())
+	}
+	/// Adds version to the registry, replacing old info if version is already present.
+	pub fn insert(&mut self, version: &str, base_version: u32, data_hash: &str) {
+		let info = VersionInfo {
+			base_version,
+			data_hash: data_hash.to_string(),
+		};
+		match self.0.iter_mut().find(|(name, _)| name == version) {
+			Some((_, old)) => *old = info,
+			None => self.0.push((version.to_string(), info)),
+		}
+	}
+	/// Returns info about given version.
+	pub fn get(&self, version: &str) -> Option<&VersionInfo> {
+		self.0
+			.iter()
+			.find(|(name, _)| name == version)
+			.map(|(_, info)| info)
+	}
+	/// Returns info about given version or error if it's unknown.
+	pub fn resolve(&self, version: &str) -> Result<&VersionInfo, LaunchError> {
+		self.get(version)
+			.ok_or_else(|| LaunchError::UnknownVersion(version.to_string()))
+	}
+	/// Returns all known versions.
+	pub fn versions(&self) -> impl Iterator<Item = (&str, &VersionInfo)> {
+		self.0.iter().map(|(name, info)| (name.as_str(), info))
+	}
+
+	/// Adds versions from text, where every line is `<version> <base version> <data hash>`.
+	/// Empty lines and lines starting with `#` are ignored.
+	///
+	/// ```
+	/// # use rust_sc2::launch::VersionRegistry;
+	/// let mut versions = VersionRegistry::empty();
+	/// versions.parse("# My versions\n4.10 75689 B89B5D6FA7CBF6452E721311BFBC6CB2").unwrap();
+	/// assert_eq!(versions.get("4.10").unwrap().base_version, 75689);
+	/// ```
+	pub fn parse(&mut self, text: &str) -> Result<(), LaunchError> {
+		for (i, line) in text.lines().enumerate() {
+			let content = line.trim();
+			if content.is_empty() || content.starts_with('#') {
+				continue;
+			}
+			let invalid = || LaunchError::InvalidVersionLine {
+				line: i + 1,
+				content: line.to_string(),
+			};
+			let mut parts = content.split_whitespace();
+			let (version, base_version, data_hash) = match (parts.next(), parts.next(), parts.next()) {
+				(Some(version), Some(base_version), Some(data_hash)) if parts.next().is_none() => {
+					(version, base_version.parse().map_err(|_| invalid())?, data_hash)
+				}
+				_ => return Err(invalid()),
+			};
+			self.insert(version, base_version, data_hash);
+		}
+		Ok(())
+	}
+	/// Adds versions from file in format described in [`parse`](Self::parse).
+	pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), LaunchError> {
+		self.parse(&fs::read_to_string(path)?)
+	}
+}
+
+/// Position and size of SC2 window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+	/// Width of the window in pixels.
+	pub width: u32,
+	/// Height of the window in pixels.
+	pub height: u32,
+	/// Position of the window's left top corner on the screen.
+	pub position: Option<(i32, i32)>,
+}
+impl WindowGeometry {
+	/// Window of given size placed by the OS.
+	pub fn new(width: u32, height: u32) -> Self {
+		Self {
+			width,
+			height,
+			position: None,
+		}
+	}
+	/// Sets position of the window's left top corner.
+	pub fn at(self, x: i32, y: i32) -> Self {
+		Self {
+			position: Some((x, y)),
+			..self
+		}
+	}
+}
+
+/// Configuration of SC2 installation and client process.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchConfig {
+	/// Path to SC2 installation, otherwise taken from `SC2PATH` variable or default for current OS.
+	pub sc2_path: Option<PathBuf>,
+	/// Directories to search maps in before `Maps` folder of installation.
+	/// Maps are also searched in subfolders (e.g. `Maps/Ladder2019Season3`).
+	pub map_dirs: Vec<PathBuf>,
+	/// Additional arguments passed to SC2 process.
+	pub extra_args: Vec<String>,
+	/// Geometry of SC2 window, otherwise default one is used.
+	pub window: Option<WindowGeometry>,
+	/// Directory for temporary files of SC2 process.
+	pub temp_dir: Option<PathBuf>,
+	/// Known versions which can be requested by name.
+	pub versions: VersionRegistry,
+}
+impl LaunchConfig {
+	/// Returns path to SC2 installation.
+	pub fn find_sc2(&self) -> Result<String, LaunchError> {
+		let path = match &self.sc2_path {
+			Some(path) => path.to_string_lossy().into_owned(),
+			None => paths::get_path_to_sc2()?,
+		};
+		if Path::new(&path).is_dir() {
+			Ok(path)
+		} else {
+			Err(LaunchError::InstallationNotFound(path.into()))
+		}
+	}
+	/// Returns path to map with given name, which can also be relative path
+	/// (e.g. `Ladder2019Season3/AcropolisLE`) or path to `.SC2Map` file.
+	pub fn find_map(&self, sc2_path: &str, map: &str) -> Result<String, LaunchError> {
+		let mut dirs = self.map_dirs.clone();
+		dirs.push(Path::new(sc2_path).join("Maps"));
+		dirs.push(Path::new(sc2_path).join("maps"));
+		paths::find_map(&dirs, map)
+	}
+	/// Returns base version and data hash of requested version
+	/// or the latest installed base version if version isn't specified.
+	pub fn version_info(&self, sc2_path: &str, version: Option<&str>) -> Result<VersionInfo, LaunchError> {
+		match version {
+			Some(version) => self.versions.resolve(version).cloned(),
+			None => Ok(VersionInfo {
+				base_version: paths::get_latest_base_version(sc2_path)?,
+				data_hash: String::new(),
+			}),
+		}
+	}
+	pub(crate) fn args(&self) -> Vec<String> {
+		let mut args = Vec::new();
+		if let Some(window) = &self.window {
+			args.extend([
+				"-windowwidth".to_string(),
+				window.width.to_string(),
+				"-windowheight".to_string(),
+				window.height.to_string(),
+			]);
+			if let Some((x, y)) = window.position {
+				args.extend([
+					"-windowx".to_string(),
+					x.to_string(),
+					"-windowy".to_string(),
+					y.to_string(),
+				]);
+			}
+		}
+		if let Some(dir) = &self.temp_dir {
+			args.extend(["-tempDir".to_string(), dir.to_string_lossy().into_owned()]);
+		}
+		args.extend(self.extra_args.iter().cloned());
+		args
+	}
+}
+
+// (Versions separated with `|`, Base version, Data hash)
+const BUILTIN_VERSIONS: &[(&str, u32, &str)] = &[
+	("5.0.2", 81102, "DC0A1182FB4ABBE8E29E3EC13CF46F68"),
+	("5.0.1", 81009, "0D28678BC32E7F67A238F19CD3E0A2CE"),
+	("5.0.0|5.0|5", 80949, "9AE39C332883B8BF6AA190286183ED72"),
+	("4.12.1", 80188, "44DED5AED024D23177C742FC227C615A"),
+	("4.12.0|4.12", 79998, "B47567DEE5DC23373BFF57194538DFD3"),
+	("4.11.4", 78285, "69493AFAB5C7B45DDB2F3442FD60F0CF"),
+	("4.11.3", 77661, "A15B8E4247434B020086354F39856C51"),
+	("4.11.2", 77535, "FC43E0897FCC93E4632AC57CBC5A2137"),
+	("4.11.1", 77474, "F92D1127A291722120AC816F09B2E583"),
+	("4.11.0|4.11", 77379, "70E774E722A58287EF37D487605CD384"),
+	("4.10.4", 76811, "FF9FA4EACEC5F06DEB27BD297D73ED67"),
+	("4.10.3", 76114, "CDB276D311F707C29BA664B7754A7293"),
+	("4.10.2", 76052, "D0F1A68AA88BA90369A84CD1439AA1C3"),
+	("4.10.1", 75800, "DDFFF9EC4A171459A4F371C6CC189554"),
+	("4.10.0|4.10", 75689, "B89B5D6FA7CBF6452E721311BFBC6CB2"),
+	("4.9.3", 75025, "C305368C63621480462F8F516FB64374"),
+	("4.9.2", 74741, "614480EF79264B5BD084E57F912172FF"),
+	("4.9.1", 74456, "218CB2271D4E2FA083470D30B1A05F02"),
+	("4.9.0|4.9", 74071, "70C74A2DCA8A0D8E7AE8647CAC68ACCA"),
+	("4.8.6", 73620, "AA18FEAD6573C79EF707DF44ABF1BE61"),
+	("4.8.5", 73559, "B2465E73AED597C74D0844112D582595"),
+	("4.8.4", 73286, "CD040C0675FD986ED37A4CA3C88C8EB5"),
+	("4.8.3", 72282, "0F14399BBD0BA528355FF4A8211F845B"),
+	("4.8.2", 71663, "FE90C92716FC6F8F04B74268EC369FA5"),
+	("4.8.1", 71523, "FCAF3F050B7C0CC7ADCF551B61B9B91E"),
+	("4.8.0|4.8", 71061, "760581629FC458A1937A05ED8388725B"),
+];
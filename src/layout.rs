@@ -0,0 +1,415 @@
+//! Base layout planner, which reserves slots for buildings around expansion.
+//!
+//! Slots are placed with 1 tile gaps between them, so units can always walk around buildings.
+//! Tiles between townhall and resources and paths from townhall to nearby ramps are never used,
+//! and every slot is checked against ground pathing map to make sure it doesn't cut off any area.
+//!
+//! Layouts are planned with [`plan_layout`](crate::bot::Bot::plan_layout) method of bot.
+
+use crate::{
+	bot::{Bot, PathfindingUnitType},
+	distance::Distance,
+	geometry::Point2,
+	placement::{addon_position, footprint},
+	player::Race,
+};
+use std::{cmp::Ordering, collections::VecDeque};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Size of townhall in tiles.
+const TOWNHALL_SIZE: usize = 5;
+/// Radius of psionic matrix of pylon.
+const PYLON_POWER_RADIUS: f32 = 6.5;
+
+/// Kind of [`BuildingSlot`].
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SlotKind {
+	/// 3x3 production or tech structure (with space for addon for terran).
+	Production,
+	/// 2x2 supply depot or pylon.
+	Supply,
+	/// 2x2 static defense (e.g. photon cannon or spine crawler) near mineral line.
+	Defense,
+}
+
+/// Place reserved for a building.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuildingSlot {
+	/// Position where building should be placed.
+	pub pos: Point2,
+	/// Size of building in tiles.
+	pub size: usize,
+	/// What kind of building this slot is for.
+	pub kind: SlotKind,
+	/// `true` if space for addon is reserved too.
+	pub addon: bool,
+}
+impl BuildingSlot {
+	/// Checks if slot isn't occupied on the local [`placement`](crate::bot::Bot::placement) grid.
+	pub fn is_free(&self, bot: &Bot) -> bool {
+		bot.placement.is_area_free(self.pos, self.size)
+			&& (!self.addon || bot.placement.is_area_free(addon_position(self.pos), 2))
+	}
+	fn tiles(&self) -> Vec<(usize, usize)> {
+		let mut tiles = footprint(self.pos, (self.size, self.size)).collect::<Vec<_>>();
+		if self.addon {
+			tiles.extend(footprint(addon_position(self.pos), (2, 2)));
+		}
+		tiles
+	}
+}
+
+/// Options for [`plan_layout`](crate::bot::Bot::plan_layout).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LayoutOptions {
+	/// Number of production slots. [Default: `6`]
+	pub production: usize,
+	/// Number of supply slots (ignored for zerg). [Default: `8`]
+	pub supply: usize,
+	/// Number of static defense slots. [Default: `2`]
+	pub defense: usize,
+	/// Maximum distance of slots from townhall. [Default: `18`]
+	pub radius: f32,
+}
+impl Default for LayoutOptions {
+	fn default() -> Self {
+		Self {
+			production: 6,
+			supply: 8,
+			defense: 2,
+			radius: 18.0,
+		}
+	}
+}
+
+/// Planned layout of buildings around expansion.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BaseLayout {
+	/// Townhall position of the expansion.
+	pub base: Point2,
+	/// Race this layout was planned for.
+	pub race: Race,
+	/// Reserved slots in order they should be used.
+	pub slots: Vec<BuildingSlot>,
+}
+impl BaseLayout {
+	/// Returns all slots of given kind.
+	pub fn slots_of(&self, kind: SlotKind) -> impl Iterator<Item = &BuildingSlot> {
+		self.slots.iter().filter(move |s| s.kind == kind)
+	}
+	/// Returns first slot of given kind, which isn't occupied yet.
+	pub fn free_slot(&self, bot: &Bot, kind: SlotKind) -> Option<&BuildingSlot> {
+		self.slots_of(kind).find(|s| s.is_free(bot))
+	}
+}
+
+struct Planner<'a> {
+	bot: &'a Bot,
+	base: Point2,
+	race: Race,
+	origin: (usize, usize),
+	dim: (usize, usize),
+	walkable: Vec<bool>,
+	reserved: Vec<bool>,
+	// Tiles of slots and tiles adjacent to them
+	taken: Vec<bool>,
+	reachable: Vec<bool>,
+	reachable_count: usize,
+	seeds: Vec<(usize, usize)>,
+	slots: Vec<BuildingSlot>,
+}
+impl<'a> Planner<'a> {
+	fn new(bot: &'a Bot, base: Point2, race: Race, radius: f32) -> Option<Self> {
+		let map = bot.pathfinding_map.as_ref()?;
+		let pathing = map.ground_pathing_map();
+		let (width, height) = (pathing.len(), pathing.first()?.len());
+
+		let extent = radius as usize + TOWNHALL_SIZE + 1;
+		let (x, y) = <(usize, usize)>::from(base);
+		let origin = (x.saturating_sub(extent), y.saturating_sub(extent));
+		let dim = (
+			(x + extent + 1).min(width) - origin.0,
+			(y + extent + 1).min(height) - origin.1,
+		);
+
+		let mut walkable = vec![false; dim.0 * dim.1];
+		for i in 0..dim.0 {
+			for j in 0..dim.1 {
+				walkable[i * dim.1 + j] = pathing[origin.0 + i][origin.1 + j] > 0;
+			}
+		}
+
+		let mut planner = Self {
+			bot,
+			base,
+			race,
+			origin,
+			dim,
+			walkable,
+			reserved: vec![false; dim.0 * dim.1],
+			taken: vec![false; dim.0 * dim.1],
+			reachable: vec![false; dim.0 * dim.1],
+			reachable_count: 0,
+			seeds: vec![],
+			slots: vec![],
+		};
+
+		// Townhall itself blocks the path and should have space around
+		let townhall = footprint(base, (TOWNHALL_SIZE, TOWNHALL_SIZE)).collect::<Vec<_>>();
+		for &tile in &townhall {
+			if let Some(i) = planner.index(tile) {
+				planner.walkable[i] = false;
+			}
+		}
+		planner.take(&townhall);
+		planner.seeds = footprint(base, (TOWNHALL_SIZE + 2, TOWNHALL_SIZE + 2))
+			.filter(|tile| !townhall.contains(tile))
+			.collect();
+		let (reachable, count) = planner.flood(&[]);
+		planner.reachable = reachable;
+		planner.reachable_count = count;
+
+		Some(planner)
+	}
+
+	fn index(&self, (x, y): (usize, usize)) -> Option<usize> {
+		let (i, j) = (x.checked_sub(self.origin.0)?, y.checked_sub(self.origin.1)?);
+		if i < self.dim.0 && j < self.dim.1 {
+			Some(i * self.dim.1 + j)
+		} else {
+			None
+		}
+	}
+
+	fn reserve(&mut self, pos: Point2, spread: isize) {
+		let (x, y) = <(usize, usize)>::from(pos);
+		for dx in -spread..=spread {
+			for dy in -spread..=spread {
+				let tile = ((x as isize + dx) as usize, (y as isize + dy) as usize);
+				if let Some(i) = self.index(tile) {
+					self.reserved[i] = true;
+				}
+			}
+		}
+	}
+	fn reserve_line(&mut self, from: Point2, to: Point2, spread: isize) {
+		let steps = (from.distance(to) * 2.0).ceil() as usize;
+		for step in 0..=steps {
+			let t = step as f32 / steps.max(1) as f32;
+			self.reserve(from + (to - from) * t, spread);
+		}
+	}
+
+	fn take(&mut self, tiles: &[(usize, usize)]) {
+		for &(x, y) in tiles {
+			for dx in -1..=1 {
+				for dy in -1..=1 {
+					let tile = ((x as isize + dx) as usize, (y as isize + dy) as usize);
+					if let Some(i) = self.index(tile) {
+						self.taken[i] = true;
+					}
+				}
+			}
+		}
+	}
+
+	// Finds tiles reachable from townhall if given tiles are blocked
+	fn flood(&self, blocked: &[usize]) -> (Vec<bool>, usize) {
+		let mut visited = vec![false; self.walkable.len()];
+		for &i in blocked {
+			visited[i] = true;
+		}
+		let mut queue = self
+			.seeds
+			.iter()
+			.filter_map(|&tile| self.index(tile))
+			.filter(|&i| self.walkable[i] && !visited[i])
+			.collect::<VecDeque<_>>();
+		for &i in &queue {
+			visited[i] = true;
+		}
+
+		let mut count = 0;
+		while let Some(i) = queue.pop_front() {
+			count += 1;
+			let (x, y) = (i / self.dim.1, i % self.dim.1);
+			let neighbors = [
+				(x.wrapping_sub(1), y),
+				(x + 1, y),
+				(x, y.wrapping_sub(1)),
+				(x, y + 1),
+			];
+			for (nx, ny) in neighbors {
+				if nx < self.dim.0 && ny < self.dim.1 {
+					let n = nx * self.dim.1 + ny;
+					if self.walkable[n] && !visited[n] {
+						visited[n] = true;
+						queue.push_back(n);
+					}
+				}
+			}
+		}
+		for &i in blocked {
+			visited[i] = false;
+		}
+		(visited, count)
+	}
+
+	fn try_place(&mut self, slot: BuildingSlot) -> bool {
+		let tiles = slot.tiles();
+		let mut indices = Vec::with_capacity(tiles.len());
+		for &tile in &tiles {
+			match self.index(tile) {
+				Some(i)
+					if self.walkable[i]
+						&& !self.reserved[i]
+						&& !self.taken[i] && self.bot.placement.is_free(tile) =>
+				{
+					indices.push(i)
+				}
+				_ => return false,
+			}
+		}
+
+		// Slot shouldn't cut off any area from the townhall
+		let lost = indices.iter().filter(|&&i| self.reachable[i]).count();
+		let (reachable, count) = self.flood(&indices);
+		if count + lost != self.reachable_count {
+			return false;
+		}
+
+		for &i in &indices {
+			self.walkable[i] = false;
+		}
+		self.reachable = reachable;
+		self.reachable_count = count;
+		self.take(&tiles);
+		self.slots.push(slot);
+		true
+	}
+
+	fn place(&mut self, kind: SlotKind, count: usize, size: usize, addon: bool, near: Point2, radius: f32) {
+		if count == 0 {
+			return;
+		}
+		let base = self.base;
+		let offset = if size % 2 == 1 { 0.5 } else { 0.0 };
+		let mut candidates = (0..self.dim.0)
+			.flat_map(|i| (0..self.dim.1).map(move |j| (i, j)))
+			.map(|(i, j)| {
+				Point2::new(
+					(self.origin.0 + i) as f32 + offset,
+					(self.origin.1 + j) as f32 + offset,
+				)
+			})
+			.filter(|pos| pos.distance_squared(base) <= radius * radius)
+			.collect::<Vec<_>>();
+		candidates.sort_by(|a, b| {
+			a.distance_squared(near)
+				.partial_cmp(&b.distance_squared(near))
+				.unwrap_or(Ordering::Equal)
+		});
+
+		let mut placed = 0;
+		for pos in candidates {
+			if kind.is_production()
+				&& self.race.is_protoss()
+				&& !self
+					.slots
+					.iter()
+					.any(|s| s.kind.is_supply() && s.pos.distance_squared(pos) <= PYLON_POWER_RADIUS.powi(2))
+			{
+				continue;
+			}
+			if self.try_place(BuildingSlot {
+				pos,
+				size,
+				kind,
+				addon,
+			}) {
+				placed += 1;
+				if placed >= count {
+					break;
+				}
+			}
+		}
+	}
+}
+
+pub(crate) fn plan_layout(bot: &Bot, base: Point2, race: Race, options: LayoutOptions) -> Option<BaseLayout> {
+	let expansion = bot
+		.expansions
+		.iter()
+		.find(|exp| exp.loc.distance_squared(base) < 1.0)?;
+	let mut planner = Planner::new(bot, expansion.loc, race, options.radius)?;
+
+	// Mineral line
+	let resources = expansion
+		.minerals
+		.iter()
+		.chain(expansion.geysers.iter())
+		.filter_map(|tag| bot.units.resources.get(*tag))
+		.map(|u| u.position())
+		.collect::<Vec<_>>();
+	for pos in resources {
+		planner.reserve_line(expansion.loc, pos, 1);
+	}
+
+	// Paths to nearby ramps
+	let max_distance = options.radius + TOWNHALL_SIZE as f32;
+	for ramp in &bot.ramps.all {
+		let (top, bottom) = match (ramp.top_center(), ramp.bottom_center()) {
+			(Some(top), Some(bottom)) => (Point2::from(top), Point2::from(bottom)),
+			_ => continue,
+		};
+		if top.distance_squared(expansion.loc) > max_distance * max_distance {
+			continue;
+		}
+		for &point in &ramp.points {
+			planner.reserve(Point2::from(point), 2);
+		}
+		let start = expansion.loc.towards(top, TOWNHALL_SIZE as f32 / 2.0 + 1.0);
+		match bot.get_path(start, bottom, PathfindingUnitType::Ground, false, false) {
+			Some((path, _)) => {
+				for pos in path {
+					planner.reserve(pos, 1);
+				}
+			}
+			None => planner.reserve_line(start, top, 1),
+		}
+	}
+
+	// Buildings are placed on the side opposite to resources
+	let back = if expansion.center.distance_squared(expansion.loc) > 0.0 {
+		expansion.loc.towards(expansion.center, -7.0)
+	} else {
+		expansion.loc
+	};
+	let front = (expansion.loc + expansion.center) / 2.0;
+	let radius = options.radius;
+
+	match race {
+		Race::Terran => {
+			planner.place(SlotKind::Production, options.production, 3, true, back, radius);
+			planner.place(SlotKind::Supply, options.supply, 2, false, back, radius);
+		}
+		Race::Protoss => {
+			planner.place(SlotKind::Supply, options.supply, 2, false, back, radius);
+			planner.place(SlotKind::Production, options.production, 3, false, back, radius);
+		}
+		_ => planner.place(SlotKind::Production, options.production, 3, false, back, radius),
+	}
+	planner.place(SlotKind::Defense, options.defense, 2, false, front, radius);
+
+	Some(BaseLayout {
+		base: expansion.loc,
+		race,
+		slots: planner.slots,
+	})
+}
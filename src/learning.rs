@@ -0,0 +1,285 @@
+//! Per-opponent history of games stored in local files, used to adapt strategy between games.
+//!
+//! Every opponent has its own file `<opponent id>.txt` in the store directory.
+//! Games are appended to the file, so store can be loaded in [`on_start`](crate::Player::on_start)
+//! and game can be recorded in [`on_end`](crate::Player::on_end), which doesn't have mutable access to bot.
+//!
+//! ```no_run
+//! use rust_sc2::learning::{GameRecord, LearningStore};
+//! use rust_sc2::prelude::*;
+//!
+//! const STRATEGIES: &[&str] = &["4Gate", "Expand", "DarkShrine"];
+//!
+//! #[bot]
+//! #[derive(Default)]
+//! struct MyBot {
+//!     store: Option<LearningStore>,
+//!     strategy: String,
+//! }
+//! impl Player for MyBot {
+//!     fn get_player_settings(&self) -> PlayerSettings<'_> {
+//!         PlayerSettings::new(Race::Protoss)
+//!     }
+//!     fn on_start(&mut self) -> SC2Result<()> {
+//!         let store = LearningStore::load("data", &self.opponent_id)?;
+//!         self.strategy = store.select_ucb1(STRATEGIES, 2f32.sqrt()).unwrap_or(STRATEGIES[0]).to_string();
+//!         self.store = Some(store);
+//!         Ok(())
+//!     }
+//!     fn on_end(&self, result: GameResult) -> SC2Result<()> {
+//!         if let Some(store) = &self.store {
+//!             store.append(&GameRecord::new(self, result, &self.strategy))?;
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use crate::{bot::Bot, player::GameResult, strategy::EnemyStrategy, utils::sanitize_file_name, SC2Result};
+use rand::prelude::*;
+use rustc_hash::FxHashMap;
+use std::{
+	fs::{self, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+/// Minimal confidence of enemy strategy estimate to record it in game history.
+const OPENING_CONFIDENCE: f32 = 0.5;
+
+/// Result of one game against opponent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+	/// Result of the game.
+	pub result: GameResult,
+	/// Name of strategy chosen by bot.
+	pub strategy: String,
+	/// Map the game was played on.
+	pub map: String,
+	/// Duration of the game in game seconds.
+	pub duration: f32,
+	/// Opponent's openings recognized during the game.
+	pub enemy_strategies: Vec<String>,
+}
+impl GameRecord {
+	/// Makes record of the current game with given result and chosen strategy.
+	/// Opponent's openings are taken from [`strategy`](Bot::strategy) estimates.
+	pub fn new(bot: &Bot, result: GameResult, strategy: &str) -> Self {
+		Self {
+			result,
+			strategy: strategy.to_string(),
+			map: bot.game_info.map_name.clone(),
+			duration: bot.time,
+			enemy_strategies: bot
+				.strategy
+				.estimates()
+				.iter()
+				.filter(|e| e.confidence >= OPENING_CONFIDENCE)
+				.map(|e| strategy_name(&e.strategy))
+				.collect(),
+		}
+	}
+
+	fn to_line(&self) -> String {
+		format!(
+			"{:?}\t{}\t{}\t{:.1}\t{}",
+			self.result,
+			clean(&self.strategy),
+			clean(&self.map),
+			self.duration,
+			self.enemy_strategies
+				.iter()
+				.map(|s| clean(s).replace(',', " "))
+				.collect::<Vec<_>>()
+				.join(",")
+		)
+	}
+	fn from_line(line: &str) -> Option<Self> {
+		let mut parts = line.split('\t');
+		let result = match parts.next()? {
+			"Victory" => GameResult::Victory,
+			"Defeat" => GameResult::Defeat,
+			"Tie" => GameResult::Tie,
+			"Undecided" => GameResult::Undecided,
+			_ => return None,
+		};
+		Some(Self {
+			result,
+			strategy: parts.next()?.to_string(),
+			map: parts.next()?.to_string(),
+			duration: parts.next()?.parse().ok()?,
+			enemy_strategies: parts
+				.next()
+				.unwrap_or_default()
+				.split(',')
+				.filter(|s| !s.is_empty())
+				.map(|s| s.to_string())
+				.collect(),
+		})
+	}
+}
+
+/// Number of games and wins with some strategy.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StrategyStats {
+	/// Number of finished games (undecided ones are not counted).
+	pub games: u32,
+	/// Number of wins, tie counts as half of win.
+	pub wins: f32,
+}
+impl StrategyStats {
+	/// Ratio of wins to games, `0` if there're no games.
+	pub fn win_rate(&self) -> f32 {
+		if self.games == 0 {
+			0.0
+		} else {
+			self.wins / self.games as f32
+		}
+	}
+}
+
+/// History of games against one opponent.
+#[derive(Debug, Clone)]
+pub struct LearningStore {
+	path: PathBuf,
+	/// Id of the opponent.
+	pub opponent_id: String,
+	/// Games played against the opponent, from oldest to newest.
+	pub games: Vec<GameRecord>,
+}
+impl LearningStore {
+	/// Loads history of games against given opponent from store directory.
+	/// Returns empty history if there're no games yet. Lines which can't be parsed are skipped.
+	pub fn load<P: AsRef<Path>>(dir: P, opponent_id: &str) -> SC2Result<Self> {
+		let opponent_id = if opponent_id.is_empty() {
+			"unknown"
+		} else {
+			opponent_id
+		};
+		let path = dir
+			.as_ref()
+			.join(format!("{}.txt", sanitize_file_name(opponent_id)));
+		let games = match fs::read_to_string(&path) {
+			Ok(text) => text
+				.lines()
+				.filter(|line| !line.starts_with('#'))
+				.filter_map(GameRecord::from_line)
+				.collect(),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+			Err(e) => return Err(e.into()),
+		};
+		Ok(Self {
+			path,
+			opponent_id: opponent_id.to_string(),
+			games,
+		})
+	}
+	/// Path to the file of the store.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+	/// Appends game to the file of the store (doesn't change loaded [`games`](Self::games)).
+	pub fn append(&self, record: &GameRecord) -> SC2Result<()> {
+		if let Some(dir) = self.path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+		writeln!(file, "{}", record.to_line())?;
+		Ok(())
+	}
+	/// Adds game to loaded games and appends it to the file.
+	pub fn record(&mut self, record: GameRecord) -> SC2Result<()> {
+		self.append(&record)?;
+		self.games.push(record);
+		Ok(())
+	}
+	/// Rewrites the file with loaded games, e.g. after old games were removed.
+	pub fn save(&self) -> SC2Result<()> {
+		if let Some(dir) = self.path.parent() {
+			fs::create_dir_all(dir)?;
+		}
+		let mut text = String::new();
+		for game in &self.games {
+			text.push_str(&game.to_line());
+			text.push('\n');
+		}
+		fs::write(&self.path, text)?;
+		Ok(())
+	}
+
+	/// Returns number of games and wins with given strategy.
+	pub fn stats(&self, strategy: &str) -> StrategyStats {
+		let mut stats = StrategyStats::default();
+		for game in self.games.iter().filter(|g| g.strategy == strategy) {
+			match game.result {
+				GameResult::Victory => stats.wins += 1.0,
+				GameResult::Tie => stats.wins += 0.5,
+				GameResult::Defeat => {}
+				GameResult::Undecided => continue,
+			}
+			stats.games += 1;
+		}
+		stats
+	}
+	/// Returns how many times every opening of the opponent was recognized.
+	pub fn enemy_strategies(&self) -> FxHashMap<&str, usize> {
+		let mut counts = FxHashMap::default();
+		for s in self.games.iter().flat_map(|g| &g.enemy_strategies) {
+			*counts.entry(s.as_str()).or_default() += 1;
+		}
+		counts
+	}
+
+	/// Selects strategy with UCB1 algorithm: strategies which weren't played yet are tried first,
+	/// then the one with the highest `win rate + exploration * sqrt(ln(total games) / games)`.
+	/// Common value of `exploration` is `sqrt(2)`.
+	pub fn select_ucb1<'a>(&self, strategies: &[&'a str], exploration: f32) -> Option<&'a str> {
+		let stats = strategies.iter().map(|s| (*s, self.stats(s))).collect::<Vec<_>>();
+		if let Some((s, _)) = stats.iter().find(|(_, st)| st.games == 0) {
+			return Some(s);
+		}
+		let total = stats.iter().map(|(_, st)| st.games).sum::<u32>() as f32;
+		stats
+			.iter()
+			.map(|(s, st)| {
+				let n = st.games as f32;
+				(*s, st.win_rate() + exploration * (total.ln() / n).sqrt())
+			})
+			.fold(None, |best: Option<(&str, f32)>, (s, score)| match best {
+				Some((_, best_score)) if best_score >= score => best,
+				_ => Some((s, score)),
+			})
+			.map(|(s, _)| s)
+	}
+	/// Selects random strategy with probability `epsilon`, otherwise the one with the highest win rate.
+	/// Strategies which weren't played yet are considered as having win rate `1`.
+	pub fn select_epsilon_greedy<'a>(&self, strategies: &[&'a str], epsilon: f32) -> Option<&'a str> {
+		let mut rng = thread_rng();
+		if rng.gen::<f32>() < epsilon {
+			return strategies.choose(&mut rng).copied();
+		}
+		strategies
+			.iter()
+			.map(|s| {
+				let stats = self.stats(s);
+				(*s, if stats.games == 0 { 1.0 } else { stats.win_rate() })
+			})
+			.fold(None, |best: Option<(&str, f32)>, (s, rate)| match best {
+				Some((_, best_rate)) if best_rate >= rate => best,
+				_ => Some((s, rate)),
+			})
+			.map(|(s, _)| s)
+	}
+}
+
+fn strategy_name(strategy: &EnemyStrategy) -> String {
+	match strategy {
+		EnemyStrategy::Custom(name) => name.clone(),
+		s => format!("{:?}", s),
+	}
+}
+
+// Removes characters used as separators in the file
+fn clean(s: &str) -> String {
+	s.replace(['\t', '\n', '\r'], " ")
+}
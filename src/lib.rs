@@ -63,8 +63,8 @@ pub mod prelude {
 		action::Target,
 		bot::{PathfindingUnitType, PlacementOptions},
 		client::{
-			run_ladder_game, run_vs_computer, run_vs_human, LaunchOptions, RunnerMulti, RunnerSingle,
-			SC2Result,
+			run_from_args, run_ladder_game, run_vs_computer, run_vs_human, LadderArgs, LaunchOptions,
+			RunnerMulti, RunnerSingle, SC2Result,
 		},
 		consts::{ALL_PRODUCERS, PRODUCERS, RESEARCHERS, TECH_REQUIREMENTS},
 		distance::{Center, Distance, DistanceIterator, DistanceSlice},
@@ -89,8 +89,11 @@ mod paths;
 
 pub mod action;
 pub mod api;
+pub mod army;
+pub mod balance;
 pub mod bot;
 pub mod client;
+pub mod commands;
 pub mod consts;
 pub mod debug;
 pub mod distance;
@@ -100,15 +103,35 @@ pub mod game_state;
 pub mod geometry;
 pub mod dicts;
 pub mod ids;
+pub mod launch;
+pub mod learning;
+pub mod layout;
+pub mod logging;
 pub mod pixel_map;
 pub mod player;
+pub mod production;
+pub mod placement;
 pub mod ramp;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod scenario;
 pub mod score;
+pub mod scouting;
+pub mod snapshot;
+pub mod spells;
+pub mod stats;
+pub mod strategy;
+pub mod supply;
+pub mod tech_tree;
+pub mod triggers;
 pub mod unit;
 pub mod units;
 pub mod utils;
+pub mod wall;
 
 use game_state::Alliance;
+use geometry::Point2;
+use ids::{UnitTypeId, UpgradeId};
 use player::{GameResult, Race};
 
 /// Implements `Deref` and `DerefMut` for accessing `Bot` fields directly.
@@ -214,6 +237,20 @@ pub enum Event {
 	ConstructionComplete(u64),
 	/// Detected actual race of random opponent.
 	RandomRaceDetected(Race),
+	/// Unit lost some hit points (health + shield) since previous step (all units: your, enemy, neutral).
+	/// Second value is amount of damage taken.
+	UnitDamaged(u64, u32),
+	/// Unit changed its type, i.e. morphed, sieged, burrowed, lifted off, etc.
+	/// Contains tag, previous type and current type of the unit.
+	UnitTypeChanged(u64, UnitTypeId, UnitTypeId),
+	/// Enemy unit entered vision for the first time.
+	EnemyUnitSeen(u64),
+	/// Research of upgrade finished (your only).
+	UpgradeComplete(UpgradeId),
+	/// Unit finished all its orders (your only).
+	UnitIdle(u64),
+	/// Opponent took new expansion. Contains location of the expansion.
+	EnemyExpanded(Point2),
 }
 
 /// Trait that bots must implement.
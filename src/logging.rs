@@ -0,0 +1,110 @@
+//! Simple logger, which writes messages of [`log`] crate to a separate file for every game.
+//!
+//! Logger is installed on first call of [`start_game_log`], which can be called again
+//! before every game to switch to a new file. Runners do it automatically when
+//! [`log_dir`](crate::client::LaunchOptions::log_dir) is set in launch options.
+//!
+//! If another logger is already installed, [`start_game_log`] returns an error without creating a file.
+//!
+//! [`log`]: https://docs.rs/log
+
+use crate::{utils::sanitize_file_name, SC2Result};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+	fs::{self, File},
+	io::{BufWriter, ErrorKind, Write},
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Mutex,
+	},
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+struct FileLogger {
+	file: Mutex<Option<BufWriter<File>>>,
+}
+impl Log for FileLogger {
+	fn enabled(&self, metadata: &Metadata) -> bool {
+		metadata.level() <= log::max_level()
+	}
+	fn log(&self, record: &Record) {
+		if !self.enabled(record.metadata()) {
+			return;
+		}
+		if let Ok(mut file) = self.file.lock() {
+			if let Some(file) = file.as_mut() {
+				let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+				let _ = writeln!(
+					file,
+					"[{}.{:03}] {:<5} {}: {}",
+					time.as_secs(),
+					time.subsec_millis(),
+					record.level(),
+					record.target(),
+					record.args()
+				);
+			}
+		}
+	}
+	fn flush(&self) {
+		if let Ok(mut file) = self.file.lock() {
+			if let Some(file) = file.as_mut() {
+				let _ = file.flush();
+			}
+		}
+	}
+}
+
+static LOGGER: FileLogger = FileLogger {
+	file: Mutex::new(None),
+};
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// Starts writing log messages of given level and more important ones to new file in given directory.
+/// Directory is created if it doesn't exist.
+///
+/// File is named `<name>_<unix timestamp>.log` (or `<name>_<unix timestamp>_<n>.log`
+/// if such file already exists), its path is returned.
+pub fn start_game_log<P: AsRef<Path>>(dir: P, name: &str, level: LevelFilter) -> SC2Result<PathBuf> {
+	// Logger is installed first, so no files are created if another one is already set
+	let mut current = LOGGER.file.lock().map_err(|e| e.to_string())?;
+	if !INSTALLED.load(Ordering::Acquire) {
+		log::set_logger(&LOGGER).map_err(|e| e.to_string())?;
+		INSTALLED.store(true, Ordering::Release);
+	}
+
+	let dir = dir.as_ref();
+	fs::create_dir_all(dir)?;
+	let time = SystemTime::now().duration_since(UNIX_EPOCH)?;
+	let stem = format!("{}_{}", sanitize_file_name(name), time.as_secs());
+	let mut path = dir.join(format!("{}.log", stem));
+	// Games started in the same second get numbered files instead of truncating each other's logs
+	let mut n = 1;
+	let file = loop {
+		match File::create_new(&path) {
+			Ok(file) => break BufWriter::new(file),
+			Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+				path = dir.join(format!("{}_{}.log", stem, n));
+				n += 1;
+			}
+			Err(e) => return Err(e.into()),
+		}
+	};
+
+	if let Some(old) = current.as_mut() {
+		old.flush()?;
+	}
+	*current = Some(file);
+	log::set_max_level(level);
+	Ok(path)
+}
+
+/// Flushes and closes current log file. Messages are ignored until next [`start_game_log`].
+pub fn finish_game_log() {
+	if let Ok(mut file) = LOGGER.file.lock() {
+		if let Some(mut file) = file.take() {
+			let _ = file.flush();
+		}
+	}
+}
@@ -10,6 +10,8 @@ use std::{
 	fmt,
 	ops::{Index, IndexMut},
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// 2-Dimensional Array of pixels, where each pixel is `Set` or is `Empty`.
 pub type PixelMap = Array2<Pixel>;
@@ -80,6 +82,7 @@ impl FromProto<&ImageData> for VisibilityMap {
 /// Base for the most 2d maps.
 #[variant_checkers]
 #[derive(FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Pixel {
 	/// When pixel is set, this tile is obstacle (e.g. not pathable | not placeable)
 	/// or has something on it (e.g. has creep).
@@ -101,6 +104,7 @@ impl fmt::Debug for Pixel {
 /// Base for visibility maps.
 #[variant_checkers]
 #[derive(Debug, FromPrimitive, ToPrimitive, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Visibility {
 	/// Position is hidden (i.e. weren't explored before)
 	#[default]
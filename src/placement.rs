@@ -0,0 +1,188 @@
+//! Locally maintained building placement grid.
+//!
+//! Grid is seeded from [`placement_grid`](crate::game_info::GameInfo::placement_grid)
+//! and updated every step from footprints of structures, resources and destructables,
+//! so placement can be checked without sending queries to the server.
+//! Creep and psionic matrix are checked for each building when it's placed.
+//!
+//! Used by [`can_place_local`](crate::bot::Bot::can_place_local) method of bot and by
+//! [`find_placement`](crate::bot::Bot::find_placement) when [`confirm`] option is unset.
+//!
+//! [`confirm`]: crate::bot::PlacementOptions::confirm
+
+use crate::{bot::Bot, distance::Distance, geometry::Point2, ids::UnitTypeId, player::Race, unit::Unit};
+use ndarray::Array2;
+
+/// Minimal gap in tiles between townhall and resources.
+const TOWNHALL_RESOURCE_GAP: usize = 3;
+
+/// Grid of tiles, where buildings can be placed.
+#[derive(Debug, Clone, Default)]
+pub struct PlacementGrid {
+	free: Array2<bool>,
+	resources: Array2<bool>,
+}
+impl PlacementGrid {
+	pub(crate) fn update(bot: &mut Bot) {
+		let placement = &bot.game_info.placement_grid;
+		if placement.is_empty() {
+			return;
+		}
+
+		// Grids are reused between steps, since map size doesn't change
+		let grid = &mut bot.placement;
+		if grid.free.dim() != placement.dim() {
+			grid.free = placement.map(|p| p.is_empty());
+			grid.resources = Array2::default(placement.dim());
+		} else {
+			grid.free.zip_mut_with(placement, |free, p| *free = p.is_empty());
+			grid.resources.fill(false);
+		}
+
+		let units = &bot.units;
+		for u in units.mineral_fields.iter() {
+			grid.block(u.position(), (2, 1), true);
+		}
+		for u in units.vespene_geysers.iter() {
+			grid.block(u.position(), (3, 3), true);
+		}
+		for u in units.destructables.iter() {
+			let size = (u.radius() * 2.0).round() as usize;
+			grid.block(u.position(), (size, size), false);
+		}
+		for u in units.my.structures.iter().chain(units.enemy.structures.iter()) {
+			if u.is_flying() {
+				continue;
+			}
+			if let Some(size) = u.building_size() {
+				grid.block(u.position(), (size, size), false);
+			}
+		}
+	}
+
+	fn block(&mut self, pos: Point2, size: (usize, usize), resource: bool) {
+		for tile in footprint(pos, size) {
+			if let Some(free) = self.free.get_mut(tile) {
+				*free = false;
+				if resource {
+					self.resources[tile] = true;
+				}
+			}
+		}
+	}
+
+	/// Checks if given tile isn't occupied by anything.
+	pub fn is_free<P: Into<(usize, usize)>>(&self, pos: P) -> bool {
+		self.free.get(pos.into()).copied().unwrap_or(false)
+	}
+	/// Checks if square area of given size with center in given position is free.
+	pub fn is_area_free(&self, pos: Point2, size: usize) -> bool {
+		footprint(pos, (size, size)).all(|tile| self.is_free(tile))
+	}
+	/// Checks if there're resources within given distance (in tiles) from square area
+	/// of given size with center in given position.
+	pub fn is_near_resources(&self, pos: Point2, size: usize, gap: usize) -> bool {
+		footprint(pos, (size + gap * 2, size + gap * 2))
+			.any(|tile| self.resources.get(tile).copied().unwrap_or(false))
+	}
+}
+
+// Tiles covered by area of given size with center in given position
+pub(crate) fn footprint(pos: Point2, (width, height): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+	let left = (pos.x - width as f32 / 2.0).round() as isize;
+	let bottom = (pos.y - height as f32 / 2.0).round() as isize;
+	(left..left + width as isize)
+		.flat_map(move |x| (bottom..bottom + height as isize).map(move |y| (x, y)))
+		.filter(|(x, y)| *x >= 0 && *y >= 0)
+		.map(|(x, y)| (x as usize, y as usize))
+}
+
+/// Returns size of given building in tiles or `None` if it isn't a building.
+pub(crate) fn building_size(bot: &Bot, building: UnitTypeId) -> Option<usize> {
+	if building.is_addon() {
+		return Some(2);
+	}
+	let ability = bot.game_data.units.get(&building)?.ability?;
+	let radius = bot.game_data.abilities.get(&ability)?.footprint_radius?;
+	Some((radius * 2.0) as usize)
+}
+
+/// Checks if given building can be placed on given position according to local grid.
+pub(crate) fn can_place(bot: &Bot, building: UnitTypeId, pos: Point2, addon: bool) -> bool {
+	if is_gas_building(building) {
+		return bot
+			.units
+			.vespene_geysers
+			.iter()
+			.find(|g| g.position().distance_squared(pos) < 1.0)
+			.is_some_and(|g| is_geyser_free(bot, g));
+	}
+	let size = match building_size(bot, building) {
+		Some(size) => size,
+		None => return false,
+	};
+	let grid = &bot.placement;
+	if !grid.is_area_free(pos, size) {
+		return false;
+	}
+	if addon && !grid.is_area_free(addon_position(pos), 2) {
+		return false;
+	}
+	if building.is_townhall() && grid.is_near_resources(pos, size, TOWNHALL_RESOURCE_GAP) {
+		return false;
+	}
+
+	let race = bot
+		.game_data
+		.units
+		.get(&building)
+		.map_or(Race::Random, |data| data.race);
+	match race {
+		Race::Zerg => {
+			// Only hatchery can be placed outside of creep
+			building == UnitTypeId::Hatchery || footprint(pos, (size, size)).all(|tile| bot.has_creep(tile))
+		}
+		_ => {
+			if footprint(pos, (size, size)).any(|tile| bot.has_creep(tile)) {
+				return false;
+			}
+			!race.is_protoss()
+				|| matches!(building, UnitTypeId::Nexus | UnitTypeId::Pylon)
+				|| bot
+					.state
+					.observation
+					.raw
+					.psionic_matrix
+					.iter()
+					.any(|m| m.pos.distance_squared(pos) <= m.radius * m.radius)
+		}
+	}
+}
+
+/// Returns position of addon of structure placed on given position.
+pub(crate) fn addon_position(pos: Point2) -> Point2 {
+	pos.offset(2.5, -0.5)
+}
+
+fn is_gas_building(building: UnitTypeId) -> bool {
+	matches!(
+		building,
+		UnitTypeId::Refinery
+			| UnitTypeId::RefineryRich
+			| UnitTypeId::Assimilator
+			| UnitTypeId::AssimilatorRich
+			| UnitTypeId::Extractor
+			| UnitTypeId::ExtractorRich
+	)
+}
+
+/// Checks if there's no gas building on given geyser.
+pub(crate) fn is_geyser_free(bot: &Bot, geyser: &Unit) -> bool {
+	let pos = geyser.position();
+	!bot.units
+		.my
+		.gas_buildings
+		.iter()
+		.chain(bot.units.enemy.gas_buildings.iter())
+		.any(|g| g.position().distance_squared(pos) < 1.0)
+}
@@ -138,6 +138,7 @@ impl IntoProto<ProtoAIBuild> for AIBuild {
 }
 
 /// Type of the player, used when joining a game.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum PlayerType {
 	/// Bot or Human.
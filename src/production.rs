@@ -0,0 +1,428 @@
+//! Production manager, which distributes training of units between available producers.
+//!
+//! Desired composition is set in [`production`](crate::bot::Bot::production) field of bot
+//! as ratios of unit types. Every call of [`run_production`](crate::bot::Bot::run_production)
+//! orders the most needed units while there're resources, supply and free producers:
+//! - Larva trains one unit each.
+//! - Production structures train one unit at a time or two if they have reactor.
+//! - Warp gates warp units in psionic matrix when their warp ability is off cooldown.
+//!
+//! It can also inject larva with queens and use chrono boost of nexuses automatically.
+//! Morphs of units (e.g. banelings or ravagers) aren't handled.
+//!
+//! ```no_run
+//! use rust_sc2::prelude::*;
+//!
+//! #[bot]
+//! #[derive(Default)]
+//! struct ZergBot;
+//! impl Player for ZergBot {
+//!     fn get_player_settings(&self) -> PlayerSettings<'_> {
+//!         PlayerSettings::new(Race::Zerg)
+//!     }
+//!     fn on_start(&mut self) -> SC2Result<()> {
+//!         self.production.set_ratio(UnitTypeId::Drone, 2.0);
+//!         self.production.set_ratio(UnitTypeId::Roach, 1.0);
+//!         self.production.set_limit(UnitTypeId::Drone, 60);
+//!         self.production.inject = true;
+//!         Ok(())
+//!     }
+//!     fn on_step(&mut self, _iteration: usize) -> SC2Result<()> {
+//!         self.run_production();
+//!         Ok(())
+//!     }
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	bot::Bot,
+	consts::{ALL_PRODUCERS, WARPGATE_ABILITIES},
+	distance::Distance,
+	geometry::Point2,
+	ids::{AbilityId, BuffId, UnitTypeId},
+	unit::Unit,
+	units::iter::UnitsIterator,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Ordering;
+
+/// Energy required for larva injection.
+const INJECT_ENERGY: u32 = 25;
+/// Energy required for chrono boost.
+const CHRONO_ENERGY: u32 = 50;
+
+#[derive(Debug, Clone)]
+struct Goal {
+	unit: UnitTypeId,
+	weight: f32,
+	limit: Option<usize>,
+}
+
+/// Settings of production manager.
+/// Can be accessed through [`production`](crate::bot::Bot::production) field of bot.
+#[derive(Debug, Clone)]
+pub struct Production {
+	goals: Vec<Goal>,
+	/// Stop ordering units when the most needed one can't be afforded,
+	/// instead of spending resources on less needed units. [Default: `false`]
+	pub wait_for_resources: bool,
+	/// Position near which units are warped in.
+	/// If not set, matrices closest to enemy start location are used. [Default: `None`]
+	pub warp_in_near: Option<Point2>,
+	/// Inject larva into hatcheries with queens. [Default: `false`]
+	pub inject: bool,
+	/// Maximum distance from queen to hatchery she injects. [Default: `10.0`]
+	pub inject_distance: f32,
+	/// Use chrono boost of nexuses on busy structures. [Default: `false`]
+	pub chrono: bool,
+	/// Types of structures which are chrono boosted first, in order of priority.
+	/// Other busy structures are boosted after them. [Default: `[]`]
+	pub chrono_priority: Vec<UnitTypeId>,
+}
+impl Default for Production {
+	fn default() -> Self {
+		Self {
+			goals: vec![],
+			wait_for_resources: false,
+			warp_in_near: None,
+			inject: false,
+			inject_distance: 10.0,
+			chrono: false,
+			chrono_priority: vec![],
+		}
+	}
+}
+impl Production {
+	/// Sets desired ratio of given unit type in composition.
+	/// E.g. ratios `2` for zealots and `1` for stalkers mean two zealots for each stalker.
+	pub fn set_ratio(&mut self, unit: UnitTypeId, weight: f32) {
+		match self.goals.iter_mut().find(|g| g.unit == unit) {
+			Some(goal) => goal.weight = weight,
+			None => self.goals.push(Goal {
+				unit,
+				weight,
+				limit: None,
+			}),
+		}
+	}
+	/// Sets maximum number of units of given type, after which it's not produced anymore.
+	/// Does nothing if there's no ratio for this type.
+	pub fn set_limit(&mut self, unit: UnitTypeId, limit: usize) {
+		if let Some(goal) = self.goals.iter_mut().find(|g| g.unit == unit) {
+			goal.limit = Some(limit);
+		}
+	}
+	/// Removes given unit type from composition. Returns `true` if it was there.
+	pub fn remove(&mut self, unit: UnitTypeId) -> bool {
+		let len = self.goals.len();
+		self.goals.retain(|g| g.unit != unit);
+		self.goals.len() != len
+	}
+	/// Removes all unit types from composition.
+	pub fn clear(&mut self) {
+		self.goals.clear();
+	}
+	/// Returns unit types and their ratios in composition.
+	pub fn ratios(&self) -> impl Iterator<Item = (UnitTypeId, f32)> + '_ {
+		self.goals.iter().map(|g| (g.unit, g.weight))
+	}
+	/// Returns unit types sorted by how much they're needed, given function which counts existing units.
+	/// Types which reached their limit or have non-positive ratio are skipped.
+	pub fn priorities<F>(&self, count: F) -> Vec<UnitTypeId>
+	where
+		F: Fn(UnitTypeId) -> usize,
+	{
+		let mut needs = self
+			.goals
+			.iter()
+			.filter(|g| g.weight > 0.0)
+			.filter_map(|g| {
+				let count = count(g.unit);
+				if g.limit.is_some_and(|limit| count >= limit) {
+					None
+				} else {
+					Some((g.unit, (count + 1) as f32 / g.weight))
+				}
+			})
+			.collect::<Vec<_>>();
+		needs.sort_by(|(_, n1), (_, n2)| n1.partial_cmp(n2).unwrap_or(Ordering::Equal));
+		needs.into_iter().map(|(unit, _)| unit).collect()
+	}
+}
+
+/// What was ordered by [`run_production`](crate::bot::Bot::run_production).
+#[derive(Debug, Clone, Default)]
+pub struct ProductionReport {
+	/// Ordered units and tags of their producers.
+	pub trained: Vec<(UnitTypeId, u64)>,
+	/// Tags of hatcheries which were injected.
+	pub injected: Vec<u64>,
+	/// Tags of structures which were chrono boosted.
+	pub chrono_boosted: Vec<u64>,
+}
+
+pub(crate) fn run(bot: &mut Bot, production: &Production) -> ProductionReport {
+	let mut report = ProductionReport::default();
+	if production.inject {
+		inject(bot, production, &mut report);
+	}
+	if production.chrono {
+		chrono(bot, production, &mut report);
+	}
+	if production.goals.is_empty() {
+		return report;
+	}
+
+	let mut counts = production
+		.goals
+		.iter()
+		.filter(|g| bot.game_data.units.contains_key(&g.unit))
+		.map(|g| (g.unit, bot.counter().all().alias().count(g.unit)))
+		.collect::<FxHashMap<_, _>>();
+	let mut used = FxHashMap::<u64, usize>::default();
+	let mut warp_positions = None;
+
+	'ordering: loop {
+		for unit in production.priorities(|u| counts.get(&u).copied().unwrap_or(0)) {
+			if !bot.can_afford(unit, true) {
+				if production.wait_for_resources {
+					break 'ordering;
+				}
+				continue;
+			}
+			if let Some(tag) = order_unit(bot, production, unit, &used, &mut warp_positions) {
+				bot.subtract_resources(unit, true);
+				*counts.entry(unit).or_default() += 1;
+				*used.entry(tag).or_default() += 1;
+				report.trained.push((unit, tag));
+				continue 'ordering;
+			}
+		}
+		break;
+	}
+	report
+}
+
+// Finds free producer for given unit and orders it. Returns tag of the producer.
+fn order_unit(
+	bot: &Bot,
+	production: &Production,
+	unit: UnitTypeId,
+	used: &FxHashMap<u64, usize>,
+	warp_positions: &mut Option<Vec<Point2>>,
+) -> Option<u64> {
+	let producers = ALL_PRODUCERS.get(&unit)?;
+	let ability = bot.game_data.units.get(&unit)?.ability?;
+	let is_free = |u: &&Unit, capacity: usize| {
+		used.get(&u.tag()).copied().unwrap_or(0) + u.orders().len() < capacity && u.is_ready()
+	};
+
+	for producer in producers {
+		match producer {
+			UnitTypeId::Larva => {
+				if let Some(larva) = bot
+					.units
+					.my
+					.larvas
+					.iter()
+					.find(|u| is_free(u, 1) && u.has_ability(ability))
+				{
+					larva.train(unit, false);
+					return Some(larva.tag());
+				}
+			}
+			UnitTypeId::WarpGate => {
+				let warp_ability = match WARPGATE_ABILITIES.get(&unit) {
+					Some(ability) => *ability,
+					None => continue,
+				};
+				let gate = bot
+					.units
+					.my
+					.structures
+					.iter()
+					.of_type(UnitTypeId::WarpGate)
+					.find(|u| is_free(u, 1) && u.has_ability(warp_ability));
+				if let Some(gate) = gate {
+					let positions = warp_positions.get_or_insert_with(|| {
+						let near = production.warp_in_near.unwrap_or(bot.enemy_start);
+						let gates = bot.units.my.structures.of_type(UnitTypeId::WarpGate).len();
+						let mut positions = warp_in_positions(bot, near, gates);
+						positions.reverse();
+						positions
+					});
+					if let Some(pos) = positions.pop() {
+						gate.warp_in(unit, pos);
+						return Some(gate.tag());
+					}
+				}
+			}
+			producer => {
+				if let Some(structure) = bot.units.my.structures.iter().of_type(*producer).find(|u| {
+					let capacity = if u.has_reactor() { 2 } else { 1 };
+					is_free(u, capacity) && u.has_ability(ability)
+				}) {
+					structure.train(unit, true);
+					return Some(structure.tag());
+				}
+			}
+		}
+	}
+	None
+}
+
+// Tags of units targeted by orders with given ability of units of given type
+fn ordered_targets(bot: &Bot, unit: UnitTypeId, ability: AbilityId) -> FxHashSet<u64> {
+	bot.units
+		.my
+		.all
+		.iter()
+		.of_type(unit)
+		.flat_map(|u| u.orders())
+		.filter(|order| order.ability == ability)
+		.filter_map(|order| match order.target {
+			Target::Tag(tag) => Some(tag),
+			_ => None,
+		})
+		.collect()
+}
+
+// Injects larva into hatcheries which aren't injected with the closest queens
+fn inject(bot: &Bot, production: &Production, report: &mut ProductionReport) {
+	// Queens are on the way to these hatcheries
+	let targeted = ordered_targets(bot, UnitTypeId::Queen, AbilityId::EffectInjectLarva);
+	let mut queens = bot
+		.units
+		.my
+		.units
+		.iter()
+		.of_type(UnitTypeId::Queen)
+		.filter(|q| q.energy() >= INJECT_ENERGY && !q.is_using(AbilityId::EffectInjectLarva))
+		.cloned()
+		.collect::<Vec<_>>();
+	let max_distance = production.inject_distance * production.inject_distance;
+
+	for hatchery in bot.units.my.townhalls.iter().filter(|h| {
+		h.is_ready()
+			&& matches!(
+				h.type_id(),
+				UnitTypeId::Hatchery | UnitTypeId::Lair | UnitTypeId::Hive
+			) && !h.has_buff(BuffId::QueenSpawnLarvaTimer)
+			&& !targeted.contains(&h.tag())
+	}) {
+		let closest = queens
+			.iter()
+			.enumerate()
+			.map(|(i, q)| (i, q.distance_squared(hatchery)))
+			.filter(|(_, d)| *d <= max_distance)
+			.min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap_or(Ordering::Equal));
+		if let Some((i, _)) = closest {
+			let queen = queens.swap_remove(i);
+			queen.command(AbilityId::EffectInjectLarva, Target::Tag(hatchery.tag()), false);
+			report.injected.push(hatchery.tag());
+		}
+	}
+}
+
+// Uses chrono boost of nexuses on busy structures, the ones from priority list go first
+fn chrono(bot: &Bot, production: &Production, report: &mut ProductionReport) {
+	let mut nexuses = bot
+		.units
+		.my
+		.townhalls
+		.iter()
+		.of_type(UnitTypeId::Nexus)
+		.filter(|n| {
+			n.is_ready() && n.energy() >= CHRONO_ENERGY && !n.is_using(AbilityId::EffectChronoBoostEnergyCost)
+		})
+		.peekable();
+	if nexuses.peek().is_none() {
+		return;
+	}
+
+	let priority = |u: &Unit| {
+		production
+			.chrono_priority
+			.iter()
+			.position(|t| *t == u.type_id())
+			.unwrap_or(production.chrono_priority.len())
+	};
+	// Buff appears on the next step after nexus got the order
+	let targeted = ordered_targets(bot, UnitTypeId::Nexus, AbilityId::EffectChronoBoostEnergyCost);
+	let can_boost = |s: &Unit| {
+		// Nexus using chrono boost isn't busy with production
+		let is_busy = !s.is_idle() && !s.is_using(AbilityId::EffectChronoBoostEnergyCost);
+		s.is_ready() && is_busy && !s.has_buff(BuffId::ChronoBoostEnergyCost) && !targeted.contains(&s.tag())
+	};
+	let mut targets = bot
+		.units
+		.my
+		.structures
+		.iter()
+		.filter(|s| can_boost(s))
+		.collect::<Vec<_>>();
+	targets.sort_by_key(|s| priority(s));
+
+	for (nexus, target) in nexuses.zip(targets) {
+		nexus.command(
+			AbilityId::EffectChronoBoostEnergyCost,
+			Target::Tag(target.tag()),
+			false,
+		);
+		report.chrono_boosted.push(target.tag());
+	}
+}
+
+/// Finds free pathable positions in psionic matrix to warp units in,
+/// sorted by distance to given point.
+pub(crate) fn warp_in_positions(bot: &Bot, near: Point2, count: usize) -> Vec<Point2> {
+	let mut matrices = bot
+		.state
+		.observation
+		.raw
+		.psionic_matrix
+		.iter()
+		.collect::<Vec<_>>();
+	matrices.sort_by(|m1, m2| {
+		m1.pos
+			.distance_squared(near)
+			.partial_cmp(&m2.pos.distance_squared(near))
+			.unwrap_or(Ordering::Equal)
+	});
+
+	let mut positions = Vec::<Point2>::with_capacity(count);
+	for matrix in matrices {
+		if positions.len() >= count {
+			break;
+		}
+		let r = matrix.radius as isize;
+		let center = Point2::new(matrix.pos.x.floor() + 0.5, matrix.pos.y.floor() + 0.5);
+		let max_distance = (matrix.radius - 0.5).powi(2);
+		let mut candidates = (-r..=r)
+			.flat_map(|dx| (-r..=r).map(move |dy| center.offset(dx as f32, dy as f32)))
+			.filter(|p| p.distance_squared(matrix.pos) <= max_distance && bot.is_pathable(*p))
+			.collect::<Vec<_>>();
+		candidates.sort_by(|p1, p2| {
+			p1.distance_squared(near)
+				.partial_cmp(&p2.distance_squared(near))
+				.unwrap_or(Ordering::Equal)
+		});
+
+		for p in candidates {
+			if positions.len() >= count {
+				break;
+			}
+			let occupied = positions.iter().any(|other| other.distance_squared(p) < 1.0)
+				|| bot
+					.units
+					.all
+					.iter()
+					.any(|u| !u.is_flying() && u.distance_squared(p) < (u.radius() + 0.5).powi(2));
+			if !occupied {
+				positions.push(p);
+			}
+		}
+	}
+	positions
+}
@@ -7,9 +7,12 @@ use std::{
 	convert::TryInto,
 	fmt,
 };
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Structured collection of ramps.
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ramps {
 	/// All ramps on the map.
 	pub all: Vec<Ramp>,
@@ -24,6 +27,7 @@ type Pos = (usize, usize);
 /// Ramp data structure with some helpful methods.
 /// All ramps stored in [`Ramps`] in [`ramps`](crate::bot::Bot::ramps) field of bot.
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ramp {
 	/// All points which belong to this ramp.
 	pub points: Vec<Pos>,
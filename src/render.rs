@@ -0,0 +1,311 @@
+//! Offline rendering of map analysis and game state to images (requires `render` feature).
+//!
+//! Everything is rendered from data stored in bot, so it works without SC2 window,
+//! e.g. to debug map analysis or to save state of the game for post-mortem.
+//!
+//! ```no_run
+//! # fn main() -> rust_sc2::SC2Result<()> {
+//! # let bot = rust_sc2::bot::Bot::default();
+//! use rust_sc2::render::{MapRender, RenderLayer};
+//!
+//! // Render everything
+//! MapRender::default().save(&bot, format!("step_{}.png", bot.state.observation.game_loop()))?;
+//!
+//! // Render only selected layers
+//! let render = MapRender {
+//!     layers: vec![RenderLayer::Height, RenderLayer::Pathing, RenderLayer::Chokes],
+//!     scale: 8,
+//! };
+//! render.save(&bot, "chokes.png")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+	bot::{Bot, Locked, PathfindingUnitType},
+	distance::Distance,
+	game_state::Alliance,
+	geometry::Point2,
+	SC2Result,
+};
+use image::RgbImage;
+use std::path::Path;
+
+type Color = [u8; 3];
+
+const MY_COLOR: Color = [0, 220, 0];
+const ENEMY_COLOR: Color = [230, 0, 0];
+const NEUTRAL_COLOR: Color = [240, 240, 240];
+const MINERAL_COLOR: Color = [60, 160, 255];
+const GEYSER_COLOR: Color = [0, 160, 90];
+const RAMP_COLOR: Color = [0, 200, 200];
+const CHOKE_COLOR: Color = [255, 200, 0];
+const CREEP_COLOR: Color = [130, 40, 150];
+const PLACEMENT_COLOR: Color = [60, 120, 60];
+const INFLUENCE_COLOR: Color = [255, 60, 0];
+
+/// Layer of rendered image. Layers are drawn in order they're given.
+#[derive(Debug, Clone, Copy)]
+pub enum RenderLayer {
+	/// Terrain height in grayscale.
+	Height,
+	/// Darkens tiles which aren't pathable.
+	Pathing,
+	/// Tints tiles where buildings can be placed.
+	Placement,
+	/// Tints tiles covered with creep.
+	Creep,
+	/// Darkens tiles in fog of war and hidden tiles.
+	Visibility,
+	/// Influence on pathfinding map for given type of units (requires initialized pathfinding).
+	Influence(PathfindingUnitType),
+	/// Zones of pathfinding map colored differently (requires initialized pathfinding).
+	Zones,
+	/// Chokes of pathfinding map (requires initialized pathfinding).
+	Chokes,
+	/// Points of ramps.
+	Ramps,
+	/// Townhall positions of expansions colored by alliance.
+	Expansions,
+	/// Positions of all visible and snapshot units.
+	Units,
+}
+
+/// Renderer of map and game state.
+#[derive(Debug, Clone)]
+pub struct MapRender {
+	/// Layers to render. [Default: all layers in order of [`RenderLayer`] variants]
+	pub layers: Vec<RenderLayer>,
+	/// Size of one map tile in pixels. [Default: `4`]
+	pub scale: u32,
+}
+impl Default for MapRender {
+	fn default() -> Self {
+		Self {
+			layers: vec![
+				RenderLayer::Height,
+				RenderLayer::Pathing,
+				RenderLayer::Placement,
+				RenderLayer::Creep,
+				RenderLayer::Visibility,
+				RenderLayer::Influence(PathfindingUnitType::Ground),
+				RenderLayer::Zones,
+				RenderLayer::Chokes,
+				RenderLayer::Ramps,
+				RenderLayer::Expansions,
+				RenderLayer::Units,
+			],
+			scale: 4,
+		}
+	}
+}
+impl MapRender {
+	/// Renders image of current state of given bot.
+	pub fn render(&self, bot: &Bot) -> RgbImage {
+		let (width, height) = bot.game_info.pathing_grid.dim();
+		let mut canvas = Canvas {
+			image: RgbImage::new(width as u32 * self.scale, height as u32 * self.scale),
+			height,
+			scale: self.scale,
+		};
+		for layer in &self.layers {
+			canvas.draw_layer(bot, *layer);
+		}
+		canvas.image
+	}
+	/// Renders image of current state of given bot and saves it to given path.
+	/// Format of image is determined by extension of the file (only PNG is supported).
+	pub fn save<P: AsRef<Path>>(&self, bot: &Bot, path: P) -> SC2Result<()> {
+		self.render(bot).save(path)?;
+		Ok(())
+	}
+}
+
+struct Canvas {
+	image: RgbImage,
+	height: usize,
+	scale: u32,
+}
+impl Canvas {
+	// Applies function to color of given map tile; y axis is flipped to have north on top
+	fn tile<F: Fn(Color) -> Color>(&mut self, (x, y): (usize, usize), f: F) {
+		if y >= self.height {
+			return;
+		}
+		let (px, py) = (x as u32 * self.scale, (self.height - 1 - y) as u32 * self.scale);
+		for dx in 0..self.scale {
+			for dy in 0..self.scale {
+				if let Some(pixel) = self.image.get_pixel_mut_checked(px + dx, py + dy) {
+					pixel.0 = f(pixel.0);
+				}
+			}
+		}
+	}
+	fn fill(&mut self, tile: (usize, usize), color: Color) {
+		self.tile(tile, |_| color);
+	}
+	fn blend(&mut self, tile: (usize, usize), color: Color, alpha: f32) {
+		self.tile(tile, |old| blend(old, color, alpha));
+	}
+	// Draws filled square with center in given position
+	fn square(&mut self, pos: Point2, radius: f32, color: Color) {
+		let r = radius.max(0.5);
+		for x in (pos.x - r).floor() as isize..(pos.x + r).ceil() as isize {
+			for y in (pos.y - r).floor() as isize..(pos.y + r).ceil() as isize {
+				if x >= 0 && y >= 0 {
+					self.fill((x as usize, y as usize), color);
+				}
+			}
+		}
+	}
+	fn line(&mut self, from: Point2, to: Point2, color: Color) {
+		let steps = (from.distance(to) * 2.0).ceil().max(1.0) as usize;
+		for i in 0..=steps {
+			let p = from + (to - from) * (i as f32 / steps as f32);
+			self.fill(<(usize, usize)>::from(p), color);
+		}
+	}
+
+	fn draw_layer(&mut self, bot: &Bot, layer: RenderLayer) {
+		let info = &bot.game_info;
+		let raw = &bot.state.observation.raw;
+		match layer {
+			RenderLayer::Height => {
+				for ((x, y), h) in info.terrain_height.indexed_iter() {
+					self.fill((x, y), [*h; 3]);
+				}
+			}
+			RenderLayer::Pathing => {
+				for ((x, y), p) in info.pathing_grid.indexed_iter() {
+					if p.is_set() {
+						self.blend((x, y), [0; 3], 0.6);
+					}
+				}
+			}
+			RenderLayer::Placement => {
+				for ((x, y), p) in info.placement_grid.indexed_iter() {
+					if p.is_empty() {
+						self.blend((x, y), PLACEMENT_COLOR, 0.3);
+					}
+				}
+			}
+			RenderLayer::Creep => {
+				for ((x, y), p) in raw.creep.read_lock().indexed_iter() {
+					// Set pixel of creep map means there's no creep
+					if p.is_empty() {
+						self.blend((x, y), CREEP_COLOR, 0.5);
+					}
+				}
+			}
+			RenderLayer::Visibility => {
+				for ((x, y), v) in raw.visibility.indexed_iter() {
+					if v.is_fogged() {
+						self.blend((x, y), [0; 3], 0.3);
+					} else if !v.is_visible() {
+						self.blend((x, y), [0; 3], 0.6);
+					}
+				}
+			}
+			RenderLayer::Influence(unit_type) => {
+				if let Some(map) = &bot.pathfinding_map {
+					let grid = match unit_type {
+						PathfindingUnitType::Ground => map.ground_pathing_map(),
+						PathfindingUnitType::Reaper => map.reaper_pathing_map(),
+						PathfindingUnitType::Colossus => map.colossus_pathing_map(),
+						PathfindingUnitType::Air => map.air_pathing_map(),
+					};
+					let max = grid.iter().flatten().copied().max().unwrap_or(1).max(2);
+					for (x, column) in grid.iter().enumerate() {
+						for (y, &value) in column.iter().enumerate() {
+							if value > 1 {
+								let alpha = (value - 1) as f32 / (max - 1) as f32;
+								self.blend((x, y), INFLUENCE_COLOR, 0.2 + alpha * 0.7);
+							}
+						}
+					}
+				}
+			}
+			RenderLayer::Zones => {
+				if let Some(map) = &bot.pathfinding_map {
+					let (width, height) = info.pathing_grid.dim();
+					for x in 0..width {
+						for y in 0..height {
+							let zone = map.get_zone((x as f32, y as f32));
+							if zone > 0 {
+								self.blend((x, y), zone_color(zone), 0.35);
+							}
+						}
+					}
+				}
+			}
+			RenderLayer::Chokes => {
+				if let Some(map) = &bot.pathfinding_map {
+					for choke in map.chokes() {
+						for &pixel in choke.pixels() {
+							self.blend(pixel, CHOKE_COLOR, 0.5);
+						}
+						let (a, b) = choke.main_line();
+						self.line(Point2::from(a), Point2::from(b), CHOKE_COLOR);
+					}
+				}
+			}
+			RenderLayer::Ramps => {
+				for ramp in &bot.ramps.all {
+					for &point in &ramp.points {
+						self.blend(point, RAMP_COLOR, 0.6);
+					}
+				}
+			}
+			RenderLayer::Expansions => {
+				for exp in &bot.expansions {
+					let color = match exp.alliance {
+						Alliance::Own | Alliance::Ally => MY_COLOR,
+						Alliance::Enemy => ENEMY_COLOR,
+						Alliance::Neutral => NEUTRAL_COLOR,
+					};
+					let corners = [(-2.5, -2.5), (2.5, -2.5), (2.5, 2.5), (-2.5, 2.5)]
+						.map(|(x, y)| exp.loc.offset(x, y));
+					for i in 0..corners.len() {
+						self.line(corners[i], corners[(i + 1) % corners.len()], color);
+					}
+				}
+			}
+			RenderLayer::Units => {
+				for u in bot.units.all.iter() {
+					let color = if u.is_mineral() {
+						MINERAL_COLOR
+					} else if u.is_geyser() {
+						GEYSER_COLOR
+					} else {
+						match u.alliance() {
+							Alliance::Own | Alliance::Ally => MY_COLOR,
+							Alliance::Enemy => ENEMY_COLOR,
+							Alliance::Neutral => NEUTRAL_COLOR,
+						}
+					};
+					self.square(u.position(), u.radius(), color);
+				}
+			}
+		}
+	}
+}
+
+fn blend(old: Color, new: Color, alpha: f32) -> Color {
+	let mix = |a: u8, b: u8| (a as f32 * (1.0 - alpha) + b as f32 * alpha).round() as u8;
+	[mix(old[0], new[0]), mix(old[1], new[1]), mix(old[2], new[2])]
+}
+
+fn zone_color(zone: i8) -> Color {
+	// Well distributed hues for consecutive zone ids
+	let hue = (zone as f32 * 0.618_034).fract() * 6.0;
+	let x = (1.0 - (hue % 2.0 - 1.0).abs()) * 255.0;
+	let x = x as u8;
+	match hue as u8 {
+		0 => [255, x, 0],
+		1 => [x, 255, 0],
+		2 => [0, 255, x],
+		3 => [0, x, 255],
+		4 => [x, 0, 255],
+		_ => [255, 0, x],
+	}
+}
@@ -0,0 +1,499 @@
+//! Reproducible fights for testing micro of bot.
+//!
+//! [`Scenario`] describes two armies spawned with debug commands on an empty map.
+//! [`run_scenario`] plays the game against computer, where every round it removes all units except
+//! structures, spawns both armies, calls [`on_step`](crate::Player::on_step) of bot until one side
+//! is dead or time is over, and records value of survived units. Results are returned in [`ScenarioReport`],
+//! so micro changes can be checked the same way as unit tests.
+//!
+//! ```no_run
+//! use rust_sc2::prelude::*;
+//! use rust_sc2::scenario::{run_scenario, Scenario};
+//!
+//! #[bot]
+//! #[derive(Default)]
+//! struct MicroBot;
+//! impl Player for MicroBot {
+//!     fn get_player_settings(&self) -> PlayerSettings<'_> {
+//!         PlayerSettings::new(Race::Terran)
+//!     }
+//!     fn on_step(&mut self, _iteration: usize) -> SC2Result<()> {
+//!         // Micro logic
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let scenario = Scenario::new("marines vs zerglings")
+//!     .my(UnitTypeId::Marine, 10, Point2::new(-5.0, 0.0))
+//!     .enemy(UnitTypeId::Zergling, 24, Point2::new(5.0, 0.0))
+//!     .rounds(10);
+//! let report = run_scenario(
+//!     &mut MicroBot::default(),
+//!     &scenario,
+//!     Computer::new(Race::Zerg, Difficulty::VeryEasy, None),
+//!     "Empty128",
+//!     Default::default(),
+//! )
+//! .unwrap();
+//! assert!(report.win_rate() > 0.8);
+//! ```
+
+use crate::{
+	bot::Bot,
+	client::{run_vs_computer, LaunchOptions},
+	geometry::Point2,
+	ids::UnitTypeId,
+	player::{Computer, GameResult},
+	unit::Unit,
+	Event, Player, PlayerSettings, SC2Result,
+};
+use rustc_hash::FxHashSet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::ops::{Deref, DerefMut};
+
+/// Maximum game time to wait for units to be removed or spawned.
+const SETUP_TIMEOUT: f32 = 3.0;
+
+/// Group of units spawned in scenario.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScenarioUnit {
+	/// Type of units.
+	pub type_id: UnitTypeId,
+	/// Number of units.
+	pub count: u32,
+	/// Spawn position relative to the center of the map.
+	pub offset: Point2,
+}
+
+/// Description of fight between two armies.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Scenario {
+	/// Name of the scenario.
+	pub name: String,
+	/// Units of bot.
+	pub my_army: Vec<ScenarioUnit>,
+	/// Units of opponent.
+	pub enemy_army: Vec<ScenarioUnit>,
+	/// How many times upgrades cheat is used for bot (debug API can't give upgrades to opponent).
+	pub upgrade_levels: u32,
+	/// Maximum duration of round in game seconds. [Default: `60`]
+	pub duration: f32,
+	/// Number of rounds. [Default: `1`]
+	pub rounds: u32,
+}
+impl Scenario {
+	/// Creates scenario with given name and without units.
+	pub fn new(name: &str) -> Self {
+		Self {
+			name: name.to_string(),
+			my_army: vec![],
+			enemy_army: vec![],
+			upgrade_levels: 0,
+			duration: 60.0,
+			rounds: 1,
+		}
+	}
+	/// Adds units of bot at given offset from the center of the map.
+	pub fn my(mut self, type_id: UnitTypeId, count: u32, offset: Point2) -> Self {
+		self.my_army.push(ScenarioUnit {
+			type_id,
+			count,
+			offset,
+		});
+		self
+	}
+	/// Adds units of opponent at given offset from the center of the map.
+	pub fn enemy(mut self, type_id: UnitTypeId, count: u32, offset: Point2) -> Self {
+		self.enemy_army.push(ScenarioUnit {
+			type_id,
+			count,
+			offset,
+		});
+		self
+	}
+	/// Sets number of times upgrades cheat is used for bot.
+	pub fn upgrade_levels(mut self, levels: u32) -> Self {
+		self.upgrade_levels = levels;
+		self
+	}
+	/// Sets maximum duration of round in game seconds.
+	pub fn duration(mut self, seconds: f32) -> Self {
+		self.duration = seconds;
+		self
+	}
+	/// Sets number of rounds.
+	pub fn rounds(mut self, rounds: u32) -> Self {
+		self.rounds = rounds;
+		self
+	}
+}
+
+/// How round of scenario ended.
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoundOutcome {
+	/// All units of opponent are dead.
+	Win,
+	/// All units of bot are dead.
+	Loss,
+	/// Units of both sides are dead.
+	Draw,
+	/// Time is over and both sides have units.
+	Timeout,
+}
+
+/// Result of one round of scenario.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RoundResult {
+	/// Value of bot's units at the start of round.
+	pub my_value: f32,
+	/// Value of opponent's units at the start of round.
+	pub enemy_value: f32,
+	/// Value of bot's units survived.
+	pub my_value_left: f32,
+	/// Value of opponent's units survived.
+	pub enemy_value_left: f32,
+	/// Duration of round in game seconds.
+	pub duration: f32,
+	/// How round ended.
+	pub outcome: RoundOutcome,
+}
+impl RoundResult {
+	/// Part of bot's army value survived, in range from `0` to `1`.
+	pub fn my_survived(&self) -> f32 {
+		ratio(self.my_value_left, self.my_value)
+	}
+	/// Part of opponent's army value survived, in range from `0` to `1`.
+	pub fn enemy_survived(&self) -> f32 {
+		ratio(self.enemy_value_left, self.enemy_value)
+	}
+	/// Difference between parts of destroyed opponent's and bot's army values, in range from `-1` to `1`.
+	pub fn trade(&self) -> f32 {
+		self.my_survived() - self.enemy_survived()
+	}
+}
+
+/// Results of all played rounds of scenario.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ScenarioReport {
+	/// Name of the scenario.
+	pub name: String,
+	/// Results of rounds in order they were played.
+	pub rounds: Vec<RoundResult>,
+}
+impl ScenarioReport {
+	/// Number of rounds with given outcome.
+	pub fn count(&self, outcome: RoundOutcome) -> usize {
+		self.rounds.iter().filter(|r| r.outcome == outcome).count()
+	}
+	/// Part of rounds won, `0` if no rounds were played.
+	pub fn win_rate(&self) -> f32 {
+		ratio(self.count(RoundOutcome::Win) as f32, self.rounds.len() as f32)
+	}
+	/// Average value of bot's units survived.
+	pub fn mean_value_left(&self) -> f32 {
+		self.mean(|r| r.my_value_left)
+	}
+	/// Average value of opponent's units survived.
+	pub fn mean_enemy_value_left(&self) -> f32 {
+		self.mean(|r| r.enemy_value_left)
+	}
+	/// Average [`trade`](RoundResult::trade) of rounds.
+	pub fn mean_trade(&self) -> f32 {
+		self.mean(RoundResult::trade)
+	}
+	fn mean<F: Fn(&RoundResult) -> f32>(&self, f: F) -> f32 {
+		ratio(self.rounds.iter().map(f).sum(), self.rounds.len() as f32)
+	}
+}
+
+/// Value of units: sum of their costs, multiplied by part of hits left.
+pub fn army_value<'a, T: IntoIterator<Item = &'a Unit>>(units: T) -> f32 {
+	units
+		.into_iter()
+		.map(|u| {
+			let cost = u.cost();
+			(cost.minerals + cost.vespene) as f32 * ratio(u.hits() as f32, u.hits_max() as f32)
+		})
+		.sum()
+}
+
+fn ratio(a: f32, b: f32) -> f32 {
+	if b > 0.0 {
+		a / b
+	} else {
+		0.0
+	}
+}
+
+/// What should be done on current step of scenario, returned by [`RoundTracker::step`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundStep {
+	/// Waiting for units to be removed or spawned.
+	Setup,
+	/// Armies of scenario should be spawned now.
+	Spawn,
+	/// Round is going on, bot should act.
+	Fight,
+	/// Round ended with given result, units should be removed before the next one.
+	Finished(RoundResult),
+}
+
+#[derive(Debug, Clone)]
+enum Phase {
+	Clear(f32),
+	Spawn {
+		since: f32,
+		existing: FxHashSet<u64>,
+	},
+	Fight {
+		start: f32,
+		my_tags: FxHashSet<u64>,
+		enemy_tags: FxHashSet<u64>,
+		my_value: f32,
+		enemy_value: f32,
+	},
+}
+
+/// State machine of rounds played by [`run_scenario`].
+///
+/// Round starts after units are removed, then armies are spawned and fight
+/// until one side is dead or time is over. Only units spawned for the round are counted,
+/// so workers, larva and eggs produced by townhalls don't affect the result.
+#[derive(Debug, Clone)]
+pub struct RoundTracker {
+	phase: Phase,
+}
+impl RoundTracker {
+	/// Starts waiting for units to be removed at given game time.
+	pub fn new(time: f32) -> Self {
+		Self {
+			phase: Phase::Clear(time),
+		}
+	}
+	/// Checks units of bot and moves to the next phase if current one is done.
+	pub fn step(&mut self, bot: &Bot, scenario: &Scenario) -> RoundStep {
+		let time = bot.time;
+		match &self.phase {
+			Phase::Clear(since) => {
+				if army(bot, false).next().is_none() && army(bot, true).next().is_none()
+					|| time - since > SETUP_TIMEOUT
+				{
+					self.phase = Phase::Spawn {
+						since: time,
+						existing: (bot.units.my.all.iter())
+							.chain(&bot.units.enemy.all)
+							.map(|u| u.tag())
+							.collect(),
+					};
+					return RoundStep::Spawn;
+				}
+				RoundStep::Setup
+			}
+			Phase::Spawn { since, existing } => {
+				let spawned = |enemy: bool, units: &[ScenarioUnit]| {
+					army(bot, enemy)
+						.filter(|u| {
+							!existing.contains(&u.tag()) && units.iter().any(|s| s.type_id == u.type_id())
+						})
+						.collect::<Vec<_>>()
+				};
+				let count = |army: &[ScenarioUnit]| army.iter().map(|u| u.count as usize).sum::<usize>();
+				let my = spawned(false, &scenario.my_army);
+				let enemy = spawned(true, &scenario.enemy_army);
+				if my.len() >= count(&scenario.my_army) && enemy.len() >= count(&scenario.enemy_army)
+					|| time - since > SETUP_TIMEOUT
+				{
+					self.phase = Phase::Fight {
+						start: time,
+						my_tags: my.iter().map(|u| u.tag()).collect(),
+						enemy_tags: enemy.iter().map(|u| u.tag()).collect(),
+						my_value: army_value(my),
+						enemy_value: army_value(enemy),
+					};
+					return RoundStep::Fight;
+				}
+				RoundStep::Setup
+			}
+			Phase::Fight {
+				start,
+				my_tags,
+				enemy_tags,
+				my_value,
+				enemy_value,
+			} => {
+				let my_left = army(bot, false)
+					.filter(|u| my_tags.contains(&u.tag()))
+					.collect::<Vec<_>>();
+				let enemy_left = army(bot, true)
+					.filter(|u| enemy_tags.contains(&u.tag()))
+					.collect::<Vec<_>>();
+				let outcome = match (!my_left.is_empty(), !enemy_left.is_empty()) {
+					(true, false) => RoundOutcome::Win,
+					(false, true) => RoundOutcome::Loss,
+					(false, false) => RoundOutcome::Draw,
+					(true, true) if time - start >= scenario.duration => RoundOutcome::Timeout,
+					_ => return RoundStep::Fight,
+				};
+				let result = RoundResult {
+					my_value: *my_value,
+					enemy_value: *enemy_value,
+					my_value_left: army_value(my_left),
+					enemy_value_left: army_value(enemy_left),
+					duration: time - start,
+					outcome,
+				};
+				self.phase = Phase::Clear(time);
+				RoundStep::Finished(result)
+			}
+		}
+	}
+	/// Checks if round is going on.
+	pub fn is_fight(&self) -> bool {
+		matches!(self.phase, Phase::Fight { .. })
+	}
+}
+
+// Units which are removed between rounds, townhall production (workers, larva, eggs) is ignored
+fn army(bot: &Bot, enemy: bool) -> impl Iterator<Item = &Unit> {
+	let units = if enemy {
+		&bot.units.enemy.all
+	} else {
+		&bot.units.my.all
+	};
+	units.iter().filter(|u| {
+		!(u.is_structure() || u.is_worker() || matches!(u.type_id(), UnitTypeId::Larva | UnitTypeId::Egg))
+	})
+}
+
+struct ScenarioPlayer<'a, B> {
+	bot: &'a mut B,
+	scenario: &'a Scenario,
+	rounds: RoundTracker,
+	report: ScenarioReport,
+}
+impl<B> Deref for ScenarioPlayer<'_, B>
+where
+	B: Deref<Target = Bot>,
+{
+	type Target = Bot;
+
+	fn deref(&self) -> &Bot {
+		self.bot
+	}
+}
+impl<B> DerefMut for ScenarioPlayer<'_, B>
+where
+	B: DerefMut<Target = Bot>,
+{
+	fn deref_mut(&mut self) -> &mut Bot {
+		self.bot
+	}
+}
+impl<B> ScenarioPlayer<'_, B>
+where
+	B: Player + DerefMut<Target = Bot>,
+{
+	fn clear(&mut self) {
+		let tags = self
+			.units
+			.my
+			.all
+			.iter()
+			.chain(&self.units.enemy.all)
+			.filter(|u| !u.is_structure())
+			.map(|u| u.tag())
+			.collect::<Vec<_>>();
+		self.debug.kill_units(&tags);
+	}
+	fn spawn(&mut self) {
+		let center = self.game_info.map_center;
+		let (me, enemy) = (self.player_id, self.enemy_player_id);
+		let cmds = (self.scenario.my_army.iter().map(|u| (u, me)))
+			.chain(self.scenario.enemy_army.iter().map(|u| (u, enemy)))
+			.map(|(u, owner)| (u.type_id, Some(owner), center + u.offset, u.count))
+			.collect::<Vec<_>>();
+		self.debug.create_units(&cmds);
+	}
+}
+impl<B> Player for ScenarioPlayer<'_, B>
+where
+	B: Player + DerefMut<Target = Bot>,
+{
+	fn get_player_settings(&self) -> PlayerSettings<'_> {
+		self.bot.get_player_settings()
+	}
+	fn on_start(&mut self) -> SC2Result<()> {
+		self.disable_fog = true;
+		for _ in 0..self.scenario.upgrade_levels {
+			self.debug.cheat_upgrades();
+		}
+		self.bot.on_start()?;
+		self.clear();
+		self.rounds = RoundTracker::new(self.time);
+		Ok(())
+	}
+	fn on_step(&mut self, iteration: usize) -> SC2Result<()> {
+		match self.rounds.step(self.bot, self.scenario) {
+			RoundStep::Setup => Ok(()),
+			RoundStep::Spawn => {
+				self.spawn();
+				Ok(())
+			}
+			RoundStep::Fight => self.bot.on_step(iteration),
+			RoundStep::Finished(result) => {
+				self.report.rounds.push(result);
+				if self.report.rounds.len() >= self.scenario.rounds as usize {
+					self.leave()
+				} else {
+					self.clear();
+					Ok(())
+				}
+			}
+		}
+	}
+	fn on_end(&self, result: GameResult) -> SC2Result<()> {
+		self.bot.on_end(result)
+	}
+	fn on_event(&mut self, event: Event) -> SC2Result<()> {
+		// Units created and killed during setup aren't reported to bot
+		if self.rounds.is_fight() {
+			self.bot.on_event(event)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/// Plays all rounds of scenario against computer on given map (which should be empty).
+///
+/// [`on_step`](crate::Player::on_step) of bot is called only during rounds,
+/// setup steps between them are handled by the scenario. Game is left after the last round.
+pub fn run_scenario<B>(
+	bot: &mut B,
+	scenario: &Scenario,
+	computer: Computer,
+	map_name: &str,
+	options: LaunchOptions,
+) -> SC2Result<ScenarioReport>
+where
+	B: Player + DerefMut<Target = Bot> + Deref<Target = Bot>,
+{
+	let mut player = ScenarioPlayer {
+		bot,
+		scenario,
+		rounds: RoundTracker::new(0.0),
+		report: ScenarioReport {
+			name: scenario.name.clone(),
+			rounds: vec![],
+		},
+	};
+	run_vs_computer(&mut player, computer, map_name, options)?;
+	Ok(player.report)
+}
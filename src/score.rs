@@ -2,9 +2,12 @@
 
 use crate::{FromProto, IntoSC2};
 use sc2_proto::score::{CategoryScoreDetails, Score as ProtoScore, Score_ScoreType, VitalScoreDetails};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[variant_checkers]
 #[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ScoreType {
 	#[default]
 	Curriculum,
@@ -20,6 +23,7 @@ impl FromProto<Score_ScoreType> for ScoreType {
 }
 
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Category {
 	pub none: f32,
 	pub army: f32,
@@ -40,6 +44,7 @@ impl FromProto<&CategoryScoreDetails> for Category {
 }
 
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Vital {
 	pub life: f32,
 	pub shields: f32,
@@ -59,6 +64,7 @@ impl FromProto<&VitalScoreDetails> for Vital {
 ///
 /// Can be accessed through [state.observation.score](crate::game_state::Observation::score).
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Score {
 	pub score_type: ScoreType,
 	pub total_score: i32,
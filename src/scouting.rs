@@ -0,0 +1,283 @@
+//! Scouting planner, which tracks when different parts of the map were seen last time
+//! and suggests where to look next.
+//!
+//! Map is divided into fixed square regions of [`REGION_SIZE`] tiles, which don't follow terrain.
+//! Region is considered seen when any of its tiles is visible.
+//!
+//! Information is updated automatically every step and stored in
+//! [`scouting`](crate::bot::Bot::scouting) field of bot.
+//! Targets and routes are built with [`scout_targets`] and [`scouting_route`] methods of bot.
+//!
+//! [`scout_targets`]: crate::bot::Bot::scout_targets
+//! [`scouting_route`]: crate::bot::Bot::scouting_route
+
+use crate::{
+	bot::{Bot, Expansion, PathfindingUnitType},
+	consts::FRAMES_PER_SECOND,
+	distance::Distance,
+	game_state::Alliance,
+	geometry::Point2,
+	pixel_map::VisibilityMap,
+};
+use ndarray::Array2;
+use rustc_hash::FxHashMap;
+use std::cmp::Ordering;
+
+/// Size of square regions (in map tiles) the map divided to.
+/// Regions are plain grid cells, not map regions separated by terrain.
+pub const REGION_SIZE: usize = 8;
+/// Additional staleness in seconds for places that were never seen.
+const NEVER_SEEN_BONUS: f32 = 60.0;
+
+/// Kind of suggested [`ScoutTarget`].
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScoutTargetKind {
+	/// Opponent's start location.
+	EnemyStart,
+	/// Expansion taken by opponent.
+	EnemyBase,
+	/// Free expansion, which opponent can take.
+	Expansion,
+	/// Region of the map, which wasn't seen for a long time.
+	Region,
+}
+
+/// Place suggested for scouting by [`scout_targets`](crate::bot::Bot::scout_targets).
+#[derive(Debug, Clone)]
+pub struct ScoutTarget {
+	/// Position to scout.
+	pub pos: Point2,
+	/// What's expected to be found there.
+	pub kind: ScoutTargetKind,
+	/// Game loop when this place was seen last time or `None` if it was never seen.
+	pub last_seen: Option<u32>,
+	/// Value of scouting this place, the higher the better.
+	pub value: f32,
+}
+
+/// Tracks game loops when expansions and regions of the map were seen last time.
+#[derive(Debug, Clone, Default)]
+pub struct Scouting {
+	expansions: FxHashMap<Point2, u32>,
+	regions: Array2<Option<u32>>,
+	game_loop: u32,
+}
+impl Scouting {
+	pub(crate) fn update(&mut self, visibility: &VisibilityMap, expansions: &[Expansion], game_loop: u32) {
+		self.game_loop = game_loop;
+		let (width, height) = visibility.dim();
+		if width == 0 || height == 0 {
+			return;
+		}
+
+		let shape = (width.div_ceil(REGION_SIZE), height.div_ceil(REGION_SIZE));
+		if self.regions.dim() != shape {
+			self.regions = Array2::default(shape);
+		}
+		for ((x, y), v) in visibility.indexed_iter() {
+			if v.is_visible() {
+				self.regions[(x / REGION_SIZE, y / REGION_SIZE)] = Some(game_loop);
+			}
+		}
+
+		for exp in expansions {
+			if visibility
+				.get(<(usize, usize)>::from(exp.loc))
+				.is_some_and(|v| v.is_visible())
+			{
+				self.expansions.insert(exp.loc, game_loop);
+			}
+		}
+	}
+	/// Returns game loop when given expansion location was seen last time.
+	pub fn expansion_last_seen(&self, loc: Point2) -> Option<u32> {
+		self.expansions.get(&loc).copied()
+	}
+	/// Returns game loop when region containing given position was seen last time.
+	pub fn region_last_seen(&self, pos: Point2) -> Option<u32> {
+		let (x, y) = <(usize, usize)>::from(pos);
+		self.regions
+			.get((x / REGION_SIZE, y / REGION_SIZE))
+			.copied()
+			.flatten()
+	}
+	/// Returns amount of seconds passed since given game loop,
+	/// or time of the game with extra penalty if it's `None` (i.e. place was never seen).
+	pub fn staleness(&self, last_seen: Option<u32>) -> f32 {
+		match last_seen {
+			Some(last_seen) => self.game_loop.saturating_sub(last_seen) as f32 / FRAMES_PER_SECOND,
+			None => self.game_loop as f32 / FRAMES_PER_SECOND + NEVER_SEEN_BONUS,
+		}
+	}
+	/// Returns amount of seconds passed since given expansion was seen last time.
+	pub fn expansion_staleness(&self, loc: Point2) -> f32 {
+		self.staleness(self.expansion_last_seen(loc))
+	}
+	/// Returns iterator over centers of all regions with game loops they were seen last time.
+	pub fn regions(&self) -> impl Iterator<Item = (Point2, Option<u32>)> + '_ {
+		let shape = self.regions.dim();
+		let size = (shape.0 * REGION_SIZE, shape.1 * REGION_SIZE);
+		self.regions
+			.indexed_iter()
+			.map(move |(cell, last_seen)| (Point2::from(region_center_tile(cell, size)), *last_seen))
+	}
+}
+
+fn region_center_tile((x, y): (usize, usize), (width, height): (usize, usize)) -> (usize, usize) {
+	(
+		(x * REGION_SIZE + REGION_SIZE / 2).min(width - 1),
+		(y * REGION_SIZE + REGION_SIZE / 2).min(height - 1),
+	)
+}
+
+pub(crate) fn scout_targets(bot: &Bot, count: usize) -> Vec<ScoutTarget> {
+	let scouting = &bot.scouting;
+	let mut targets = vec![];
+
+	let mut add = |pos: Point2, kind: ScoutTargetKind, last_seen: Option<u32>, weight: f32| {
+		let value = scouting.staleness(last_seen) * weight;
+		if value > 0.0 {
+			targets.push(ScoutTarget {
+				pos,
+				kind,
+				last_seen,
+				value,
+			});
+		}
+	};
+
+	let enemy_start = bot.enemy_start;
+	add(
+		enemy_start,
+		ScoutTargetKind::EnemyStart,
+		scouting.expansion_last_seen(enemy_start),
+		3.0,
+	);
+	for exp in &bot.expansions {
+		if exp.loc == enemy_start {
+			continue;
+		}
+		let last_seen = scouting.expansion_last_seen(exp.loc);
+		match exp.alliance {
+			Alliance::Enemy => add(exp.loc, ScoutTargetKind::EnemyBase, last_seen, 2.0),
+			Alliance::Neutral => {
+				// Expansions closer to opponent are more likely to be taken next
+				let weight =
+					if exp.loc.distance_squared(enemy_start) < exp.loc.distance_squared(bot.start_location) {
+						1.5
+					} else {
+						0.5
+					};
+				add(exp.loc, ScoutTargetKind::Expansion, last_seen, weight);
+			}
+			_ => {}
+		}
+	}
+	for (pos, last_seen) in scouting.regions() {
+		if bot.is_pathable(pos) {
+			add(pos, ScoutTargetKind::Region, last_seen, 0.1);
+		}
+	}
+
+	targets.sort_unstable_by(|a, b| b.value.partial_cmp(&a.value).unwrap_or(Ordering::Equal));
+	targets.truncate(count);
+	targets
+}
+
+pub(crate) fn enemy_bases_staleness(bot: &Bot) -> Vec<(Point2, f32)> {
+	let scouting = &bot.scouting;
+	let mut bases = vec![(bot.enemy_start, scouting.expansion_staleness(bot.enemy_start))];
+	bases.extend(
+		bot.expansions
+			.iter()
+			.filter(|exp| exp.alliance.is_enemy() && exp.loc != bot.enemy_start)
+			.map(|exp| (exp.loc, scouting.expansion_staleness(exp.loc))),
+	);
+	bases
+}
+
+pub(crate) fn scouting_route(
+	bot: &mut Bot,
+	start: Point2,
+	targets: &[Point2],
+	unit_type: PathfindingUnitType,
+) -> Option<Vec<Point2>> {
+	let map_type = match unit_type {
+		PathfindingUnitType::Ground => 0,
+		PathfindingUnitType::Reaper => 1,
+		PathfindingUnitType::Colossus => 2,
+		PathfindingUnitType::Air => 3,
+	};
+	let flying = matches!(unit_type, PathfindingUnitType::Air);
+
+	// Known threats for the scout
+	let threats = bot
+		.units
+		.enemy
+		.all
+		.iter()
+		.filter(|u| {
+			if flying {
+				u.can_attack_air()
+			} else {
+				u.can_attack_ground()
+			}
+		})
+		.map(|u| {
+			let range = if flying {
+				u.real_air_range()
+			} else {
+				u.real_ground_range()
+			};
+			(<(usize, usize)>::from(u.position()), range + u.radius() + 1.0)
+		})
+		.collect::<Vec<_>>();
+
+	// Visiting closest targets first
+	let mut order = Vec::with_capacity(targets.len());
+	let mut left = targets.to_vec();
+	let mut current = start;
+	while !left.is_empty() {
+		let (i, _) = left
+			.iter()
+			.enumerate()
+			.min_by(|(_, a), (_, b)| {
+				a.distance_squared(current)
+					.partial_cmp(&b.distance_squared(current))
+					.unwrap_or(Ordering::Equal)
+			})
+			.unwrap();
+		current = left.swap_remove(i);
+		order.push(current);
+	}
+
+	let map = bot.pathfinding_map.as_mut()?;
+	let grid = map.get_map_mut(map_type);
+	let original = grid.map.clone();
+	for (pos, range) in threats {
+		grid.add_influence(vec![pos], 100.0, range);
+	}
+
+	let mut route = vec![];
+	let mut current = start;
+	for target in order {
+		let (path, _) = map.find_path(
+			map_type,
+			current.as_tuple(),
+			target.as_tuple(),
+			false,
+			true,
+			Some(1),
+			None,
+			None,
+		);
+		if !path.is_empty() {
+			route.extend(path.into_iter().map(Point2::from));
+			current = target;
+		}
+	}
+
+	map.get_map_mut(map_type).set_map(original);
+	Some(route)
+}
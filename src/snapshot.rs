@@ -0,0 +1,223 @@
+//! Owned copy of the game state on a single step.
+//!
+//! Most of the bot's data is stored behind shared handles, which are cheap to clone
+//! but can't outlive the bot and can't be deserialized back into a living game.
+//! [`Snapshot`] copies everything needed to reproduce the step into plain owned data,
+//! so it can be dumped to JSON, bincode or any other format supported by
+//! [`serde`] (requires `serde` feature) for analysis, training data or bug reports.
+//!
+//! Static map information isn't included into snapshot, because it doesn't change
+//! during the game. [`GameInfo`](crate::game_info::GameInfo) and [`GameData`](crate::game_data::GameData)
+//! can be serialized separately once per game.
+//!
+//! [`Unit`] and [`Units`] are serialized as [`UnitSnapshot`]s,
+//! so serialized units can be deserialized back as [`UnitSnapshot`].
+//!
+//! [`serde`]: https://docs.rs/serde
+
+use crate::{
+	action::ActionError,
+	bot::{Bot, Expansion, LockOwned, Locked},
+	game_state::{Alert, Alliance, ChatMessage, Common, Effect, PsionicMatrix, Radar},
+	geometry::{Point2, Point3},
+	ids::{BuffId, UnitTypeId, UpgradeId},
+	pixel_map::{PixelMap, VisibilityMap},
+	player::Race,
+	score::Score,
+	unit::{DisplayType, PassengerUnit, RallyTarget, Unit, UnitOrder},
+};
+use rustc_hash::FxHashSet;
+#[cfg(feature = "serde")]
+use {
+	crate::units::Units,
+	serde::{Deserialize, Serialize, Serializer},
+};
+
+/// Owned copy of all raw data of the [`Unit`].
+#[allow(missing_docs)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnitSnapshot {
+	pub display_type: DisplayType,
+	pub alliance: Alliance,
+	pub tag: u64,
+	pub type_id: UnitTypeId,
+	pub owner: u32,
+	pub position: Point2,
+	pub position3d: Point3,
+	pub facing: f32,
+	pub radius: f32,
+	pub build_progress: f32,
+	pub is_cloaked: bool,
+	pub is_revealed: bool,
+	pub buffs: FxHashSet<BuffId>,
+	pub detect_range: f32,
+	pub radar_range: f32,
+	pub is_selected: bool,
+	pub is_on_screen: bool,
+	pub is_blip: bool,
+	pub is_powered: bool,
+	pub is_active: bool,
+	pub attack_upgrade_level: u32,
+	pub armor_upgrade_level: i32,
+	pub shield_upgrade_level: i32,
+	pub health: u32,
+	pub health_max: u32,
+	pub shield: u32,
+	pub shield_max: u32,
+	pub energy: u32,
+	pub energy_max: u32,
+	pub mineral_contents: u32,
+	pub vespene_contents: u32,
+	pub is_flying: bool,
+	pub is_burrowed: bool,
+	pub is_hallucination: bool,
+	pub orders: Vec<UnitOrder>,
+	pub addon_tag: Option<u64>,
+	pub passengers: Vec<PassengerUnit>,
+	pub cargo_space_taken: u32,
+	pub cargo_space_max: u32,
+	pub assigned_harvesters: u32,
+	pub ideal_harvesters: u32,
+	pub weapon_cooldown: f32,
+	pub engaged_target_tag: Option<u64>,
+	pub buff_duration_remain: u32,
+	pub buff_duration_max: u32,
+	pub rally_targets: Vec<RallyTarget>,
+}
+impl From<&Unit> for UnitSnapshot {
+	fn from(u: &Unit) -> Self {
+		let base = &u.base;
+		Self {
+			display_type: *base.display_type.read_lock(),
+			alliance: base.alliance,
+			tag: base.tag,
+			type_id: *base.type_id.read_lock(),
+			owner: base.owner,
+			position: base.position,
+			position3d: base.position3d,
+			facing: base.facing,
+			radius: base.radius,
+			build_progress: base.build_progress,
+			is_cloaked: base.is_cloaked.get_locked(),
+			is_revealed: base.is_revealed.get_locked(),
+			buffs: base.buffs.clone(),
+			detect_range: base.detect_range,
+			radar_range: base.radar_range,
+			is_selected: base.is_selected,
+			is_on_screen: base.is_on_screen,
+			is_blip: base.is_blip,
+			is_powered: base.is_powered,
+			is_active: base.is_active,
+			attack_upgrade_level: base.attack_upgrade_level,
+			armor_upgrade_level: base.armor_upgrade_level,
+			shield_upgrade_level: base.shield_upgrade_level,
+			health: base.health,
+			health_max: base.health_max,
+			shield: base.shield,
+			shield_max: base.shield_max,
+			energy: base.energy,
+			energy_max: base.energy_max,
+			mineral_contents: base.mineral_contents,
+			vespene_contents: base.vespene_contents,
+			is_flying: base.is_flying,
+			is_burrowed: base.is_burrowed.get_locked(),
+			is_hallucination: base.is_hallucination.get_locked(),
+			orders: base.orders.clone(),
+			addon_tag: base.addon_tag,
+			passengers: base.passengers.clone(),
+			cargo_space_taken: base.cargo_space_taken,
+			cargo_space_max: base.cargo_space_max,
+			assigned_harvesters: base.assigned_harvesters,
+			ideal_harvesters: base.ideal_harvesters,
+			weapon_cooldown: base.weapon_cooldown,
+			engaged_target_tag: base.engaged_target_tag,
+			buff_duration_remain: base.buff_duration_remain,
+			buff_duration_max: base.buff_duration_max,
+			rally_targets: base.rally_targets.clone(),
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Unit {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		UnitSnapshot::from(self).serialize(serializer)
+	}
+}
+#[cfg(feature = "serde")]
+impl Serialize for Units {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.iter().map(UnitSnapshot::from))
+	}
+}
+
+/// Owned copy of the game state on current step.
+///
+/// Can be made with [`snapshot`](crate::bot::Bot::snapshot) method of bot.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Snapshot {
+	/// Game loop on which snapshot was made.
+	pub game_loop: u32,
+	/// Bot's race.
+	pub race: Race,
+	/// Opponent's race (`Random` if it's still unknown).
+	pub enemy_race: Race,
+	/// Common information of player (resources, supply, ...).
+	pub common: Common,
+	/// Score at the moment of snapshot.
+	pub score: Score,
+	/// Alerts appeared on this step.
+	pub alerts: Vec<Alert>,
+	/// Messages in game chat.
+	pub chat: Vec<ChatMessage>,
+	/// Errors of actions executed on previous step.
+	pub action_errors: Vec<ActionError>,
+	/// Bot's ready upgrades.
+	pub upgrades: FxHashSet<UpgradeId>,
+	/// Predicted opponent's upgrades.
+	pub enemy_upgrades: FxHashSet<UpgradeId>,
+	/// All units visible on this step.
+	pub units: Vec<UnitSnapshot>,
+	/// Tags of units which died last step.
+	pub dead_units: Vec<u64>,
+	/// Current effects on the map.
+	pub effects: Vec<Effect>,
+	/// Protoss power from pylons and warp prisms.
+	pub psionic_matrix: Vec<PsionicMatrix>,
+	/// Terran radars on the map.
+	pub radars: Vec<Radar>,
+	/// Expansions with their owners.
+	pub expansions: Vec<Expansion>,
+	/// Bot's visibility map.
+	pub visibility: VisibilityMap,
+	/// Creep on the map.
+	pub creep: PixelMap,
+}
+impl Snapshot {
+	pub(crate) fn new(bot: &Bot) -> Self {
+		let observation = &bot.state.observation;
+		let raw = &observation.raw;
+		Self {
+			game_loop: observation.game_loop(),
+			race: bot.race,
+			enemy_race: bot.enemy_race,
+			common: observation.common.clone(),
+			score: observation.score.clone(),
+			alerts: observation.alerts.clone(),
+			chat: bot.state.chat.clone(),
+			action_errors: bot.state.action_errors.clone(),
+			upgrades: raw.upgrades.read_lock().clone(),
+			enemy_upgrades: bot.enemy_upgrades().clone(),
+			units: bot.units.all.iter().map(UnitSnapshot::from).collect(),
+			dead_units: raw.dead_units.clone(),
+			effects: raw.effects.clone(),
+			psionic_matrix: raw.psionic_matrix.clone(),
+			radars: raw.radars.clone(),
+			expansions: bot.expansions.clone(),
+			visibility: raw.visibility.clone(),
+			creep: raw.creep.read_lock().clone(),
+		}
+	}
+}
@@ -0,0 +1,306 @@
+//! Targeting of area-of-effect abilities.
+//!
+//! [`find_spell_target`] looks for a cast position of [`AreaSpell`], which affects
+//! the most valuable enemies, taking into account their predicted movement,
+//! damage to own units and enemies already affected by the same spell.
+//! It's also available as [`find_spell_target`](crate::bot::Bot::find_spell_target) method of bot,
+//! which checks all visible units and effects.
+//!
+//! ```no_run
+//! # let bot = rust_sc2::bot::Bot::default();
+//! use rust_sc2::prelude::*;
+//! use rust_sc2::spells::{AreaSpell, SpellOptions};
+//!
+//! let options = SpellOptions {
+//!     min_value: 300.0,
+//!     ..Default::default()
+//! };
+//! for templar in &bot.units.my.units.of_type(UnitTypeId::HighTemplar) {
+//!     if let Some(target) = bot.find_spell_target(templar, AreaSpell::PsiStorm, &options) {
+//!         templar.command(AreaSpell::PsiStorm.ability(), target.target(), false);
+//!     }
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	consts::FRAMES_PER_SECOND,
+	distance::Distance,
+	game_state::{Alliance, Effect},
+	geometry::Point2,
+	ids::{AbilityId, BuffId, EffectId, UnitTypeId},
+	unit::Unit,
+	units::Units,
+};
+use rustc_hash::FxHashMap;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Minimal value of unit, so that free units (e.g. broodlings) are still worth something.
+const MIN_UNIT_VALUE: f32 = 10.0;
+/// Value of one point of energy drained by EMP.
+const ENERGY_VALUE: f32 = 2.0;
+
+/// Ability with area of effect or high damage to single target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AreaSpell {
+	/// High Templar's Psionic Storm.
+	PsiStorm,
+	/// Infestor's Fungal Growth.
+	FungalGrowth,
+	/// Ghost's EMP Round.
+	Emp,
+	/// Ravager's Corrosive Bile.
+	CorrosiveBile,
+	/// Ghost's Steady Targeting (targets single biological unit).
+	Snipe,
+	/// Reaper's KD8 Charge.
+	Kd8Charge,
+}
+impl AreaSpell {
+	/// Ability used to cast the spell.
+	pub fn ability(self) -> AbilityId {
+		match self {
+			Self::PsiStorm => AbilityId::PsiStormPsiStorm,
+			Self::FungalGrowth => AbilityId::FungalGrowthFungalGrowth,
+			Self::Emp => AbilityId::EMPEMP,
+			Self::CorrosiveBile => AbilityId::EffectCorrosiveBile,
+			Self::Snipe => AbilityId::EffectGhostSnipe,
+			Self::Kd8Charge => AbilityId::KD8ChargeKD8Charge,
+		}
+	}
+	/// Cast range of the spell (without radii of caster and target).
+	pub fn range(self) -> f32 {
+		match self {
+			Self::PsiStorm | Self::CorrosiveBile => 9.0,
+			Self::FungalGrowth | Self::Emp | Self::Snipe => 10.0,
+			Self::Kd8Charge => 5.0,
+		}
+	}
+	/// Radius of area affected by the spell (`0` for single target spells).
+	pub fn radius(self) -> f32 {
+		match self {
+			Self::PsiStorm | Self::Emp => 1.5,
+			Self::FungalGrowth => 2.25,
+			Self::CorrosiveBile | Self::Kd8Charge => 0.5,
+			Self::Snipe => 0.0,
+		}
+	}
+	/// Number of game frames between cast and the moment spell hits.
+	pub fn delay(self) -> u32 {
+		let seconds = match self {
+			Self::PsiStorm | Self::FungalGrowth | Self::Emp => 0.0,
+			Self::CorrosiveBile => 2.5,
+			Self::Snipe => 1.43,
+			Self::Kd8Charge => 1.0,
+		};
+		(seconds * FRAMES_PER_SECOND) as u32
+	}
+	/// Total damage dealt to every affected unit (shields and energy drained for EMP).
+	pub fn damage(self) -> u32 {
+		match self {
+			Self::PsiStorm => 80,
+			Self::FungalGrowth => 30,
+			Self::Emp => 100,
+			Self::CorrosiveBile => 60,
+			Self::Snipe => 170,
+			Self::Kd8Charge => 5,
+		}
+	}
+	/// Buff of units already affected by the spell.
+	pub fn buff(self) -> Option<BuffId> {
+		match self {
+			Self::PsiStorm => Some(BuffId::PsiStorm),
+			Self::FungalGrowth => Some(BuffId::FungalGrowth),
+			_ => None,
+		}
+	}
+	/// Effect left on the map by the spell.
+	pub fn effect(self) -> Option<EffectId> {
+		match self {
+			Self::PsiStorm => Some(EffectId::PsiStormPersistent),
+			Self::CorrosiveBile => Some(EffectId::RavagerCorrosiveBileCP),
+			_ => None,
+		}
+	}
+	/// Checks if the spell damages own units.
+	pub fn friendly_fire(self) -> bool {
+		matches!(self, Self::PsiStorm | Self::Emp | Self::CorrosiveBile)
+	}
+	/// Checks if the spell has single target instead of area.
+	pub fn is_targeted(self) -> bool {
+		matches!(self, Self::Snipe)
+	}
+	/// Checks if the spell can affect given unit.
+	pub fn affects(self, u: &Unit) -> bool {
+		match self {
+			Self::PsiStorm | Self::FungalGrowth => !u.is_structure(),
+			Self::Emp => u.shield() > 0 || u.energy() > 0,
+			Self::CorrosiveBile => true,
+			Self::Snipe => u.is_biological() && !u.is_structure(),
+			Self::Kd8Charge => !u.is_flying() && !u.is_structure(),
+		}
+	}
+	/// Value of damage dealt to given unit: part of unit's cost proportional to hits it loses.
+	/// EMP also values energy drained from the unit.
+	pub fn value(self, u: &Unit) -> f32 {
+		if !self.affects(u) {
+			return 0.0;
+		}
+		let cost = u.cost();
+		let cost = ((cost.minerals + cost.vespene) as f32).max(MIN_UNIT_VALUE);
+		let hits_max = u.hits_max().max(1) as f32;
+		let damage = self.damage();
+		match self {
+			Self::Emp => {
+				cost * u.shield().min(damage) as f32 / hits_max + u.energy().min(damage) as f32 * ENERGY_VALUE
+			}
+			_ => cost * u.hits().min(damage) as f32 / hits_max,
+		}
+	}
+}
+
+/// Additional options for [`find_spell_target`].
+#[derive(Clone)]
+pub struct SpellOptions {
+	/// Minimum value of found target. [Default: `0`]
+	pub min_value: f32,
+	/// Multiplier of value lost by damaging own units. [Default: `1.5`]
+	pub friendly_fire_weight: f32,
+	/// Additional distance caster can move before casting. [Default: `0`]
+	pub gap: f32,
+	/// Multipliers of values of specific unit types, e.g. to prioritize casters. [Default: empty]
+	pub weights: FxHashMap<UnitTypeId, f32>,
+}
+impl Default for SpellOptions {
+	fn default() -> Self {
+		Self {
+			min_value: 0.0,
+			friendly_fire_weight: 1.5,
+			gap: 0.0,
+			weights: Default::default(),
+		}
+	}
+}
+
+/// Target of spell found by [`find_spell_target`].
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpellTarget {
+	/// Position to cast the spell at.
+	pub position: Point2,
+	/// Tag of target unit (only for targeted spells).
+	pub tag: Option<u64>,
+	/// Value of damaged enemies minus weighted value of damaged own units.
+	pub value: f32,
+	/// Number of affected enemies.
+	pub count: usize,
+}
+impl SpellTarget {
+	/// Target to use in command of caster.
+	pub fn target(&self) -> Target {
+		match self.tag {
+			Some(tag) => Target::Tag(tag),
+			None => Target::Pos(self.position),
+		}
+	}
+}
+
+/// Finds the most valuable target of given spell for caster.
+///
+/// Positions of units are predicted at the moment spell hits.
+/// Enemies with spell's [`buff`](AreaSpell::buff) or inside of own spell's [`effect`](AreaSpell::effect)
+/// are skipped, own units in the area decrease value of the target if spell has friendly fire.
+pub fn find_spell_target(
+	caster: &Unit,
+	spell: AreaSpell,
+	enemies: &Units,
+	allies: &Units,
+	effects: &[Effect],
+	options: &SpellOptions,
+) -> Option<SpellTarget> {
+	let delay = spell.delay();
+	let radius = spell.radius();
+	let cast_range = spell.range() + caster.radius() + options.gap;
+	let covered = effects
+		.iter()
+		.filter(|e| e.alliance == Alliance::Own && Some(e.id) == spell.effect())
+		.collect::<Vec<_>>();
+	let is_covered = |pos: Point2| {
+		covered
+			.iter()
+			.any(|e| e.positions.iter().any(|p| p.is_closer(e.radius, pos)))
+	};
+
+	let value = |u: &Unit| spell.value(u) * options.weights.get(&u.type_id()).copied().unwrap_or(1.0);
+	let targets = enemies
+		.iter()
+		.filter(|u| u.is_visible() && !spell.buff().is_some_and(|buff| u.has_buff(buff)))
+		.filter(|u| caster.is_closer(cast_range + radius + u.radius(), *u))
+		.map(|u| (u, u.predict_position(delay)))
+		.filter(|(u, pos)| !is_covered(*pos) && value(u) > 0.0)
+		.map(|(u, pos)| (u, pos, value(u)))
+		.collect::<Vec<_>>();
+
+	if spell.is_targeted() {
+		return targets
+			.iter()
+			.filter(|(u, ..)| caster.is_closer(cast_range + u.radius(), *u))
+			.max_by(|a, b| a.2.total_cmp(&b.2))
+			.filter(|(.., value)| *value >= options.min_value)
+			.map(|(u, pos, value)| SpellTarget {
+				position: *pos,
+				tag: Some(u.tag()),
+				value: *value,
+				count: 1,
+			});
+	}
+
+	let friends = if spell.friendly_fire() {
+		allies
+			.iter()
+			.filter(|u| spell.affects(u) && caster.is_closer(cast_range + radius * 2.0 + u.radius(), *u))
+			.map(|u| (u.predict_position(delay), u.radius(), value(u)))
+			.collect::<Vec<_>>()
+	} else {
+		vec![]
+	};
+
+	// Candidates are predicted positions of enemies and middle points between close enemies
+	let mut candidates = targets.iter().map(|(_, pos, _)| *pos).collect::<Vec<_>>();
+	for (i, (_, a, _)) in targets.iter().enumerate() {
+		for (_, b, _) in &targets[i + 1..] {
+			if a.is_closer(radius * 2.0, *b) {
+				candidates.push((*a + *b) / 2.0);
+			}
+		}
+	}
+
+	candidates
+		.into_iter()
+		.filter(|pos| caster.is_closer(cast_range, *pos))
+		.map(|pos| {
+			let mut target = SpellTarget {
+				position: pos,
+				tag: None,
+				value: 0.0,
+				count: 0,
+			};
+			for (u, p, value) in &targets {
+				if p.is_closer(radius + u.radius(), pos) {
+					target.value += value;
+					target.count += 1;
+				}
+			}
+			for (p, r, value) in &friends {
+				if p.is_closer(radius + r, pos) {
+					target.value -= value * options.friendly_fire_weight;
+				}
+			}
+			target
+		})
+		.filter(|t| t.count > 0)
+		.max_by(|a, b| a.value.total_cmp(&b.value))
+		.filter(|t| t.value >= options.min_value)
+}
@@ -0,0 +1,486 @@
+//! Effective stats of units including upgrades and buffs.
+//!
+//! [`UnitStats`] can be calculated for existing unit with [`Unit::stats`](crate::unit::Unit::stats)
+//! or for hypothetical unit of given type with [`Bot::unit_stats`](crate::bot::Bot::unit_stats),
+//! which uses bot's upgrades or known upgrades of opponent.
+//!
+//! ```
+//! # let bot = rust_sc2::bot::Bot::default();
+//! use rust_sc2::prelude::*;
+//!
+//! if let Some(stats) = bot.unit_stats(UnitTypeId::Hydralisk, true) {
+//!     println!("Enemy hydras have range {} and speed {}", stats.ground_range(), stats.speed);
+//! }
+//! ```
+
+use crate::{
+	balance::BalanceTable,
+	game_data::{Attribute, GameData, TargetType, Weapon},
+	ids::{BuffId, UnitTypeId, UpgradeId},
+	player::Race,
+};
+use rustc_hash::FxHashSet;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Levels of weapon, armor and shield upgrades applied to unit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UpgradeLevels {
+	/// Level of weapon upgrades.
+	pub attack: u32,
+	/// Level of armor upgrades.
+	pub armor: i32,
+	/// Level of shield upgrades.
+	pub shield: i32,
+}
+impl UpgradeLevels {
+	/// Returns levels of upgrades from given set, which apply to unit of given type.
+	pub fn of(
+		type_id: UnitTypeId,
+		race: Race,
+		attributes: &[Attribute],
+		upgrades: &FxHashSet<UpgradeId>,
+	) -> Self {
+		use UpgradeId::*;
+
+		let level = |ids: [UpgradeId; 3]| {
+			ids.iter()
+				.rposition(|u| upgrades.contains(u))
+				.map_or(0, |i| i + 1)
+		};
+		let is_structure = attributes.contains(&Attribute::Structure);
+		let (attack, armor) = if is_structure || type_id.is_worker() {
+			(0, 0)
+		} else {
+			let flying = has_air_upgrades(type_id);
+			match race {
+				Race::Terran => {
+					let vehicle_armor = [
+						TerranVehicleAndShipArmorsLevel1,
+						TerranVehicleAndShipArmorsLevel2,
+						TerranVehicleAndShipArmorsLevel3,
+					];
+					let infantry_armor = [
+						TerranInfantryArmorsLevel1,
+						TerranInfantryArmorsLevel2,
+						TerranInfantryArmorsLevel3,
+					];
+					let vehicle_weapons = [
+						TerranVehicleWeaponsLevel1,
+						TerranVehicleWeaponsLevel2,
+						TerranVehicleWeaponsLevel3,
+					];
+					if flying {
+						(
+							level([
+								TerranShipWeaponsLevel1,
+								TerranShipWeaponsLevel2,
+								TerranShipWeaponsLevel3,
+							]),
+							level(vehicle_armor),
+						)
+					} else if matches!(type_id, UnitTypeId::HellionTank) {
+						(level(vehicle_weapons), level(infantry_armor))
+					} else if attributes.contains(&Attribute::Biological) {
+						(
+							level([
+								TerranInfantryWeaponsLevel1,
+								TerranInfantryWeaponsLevel2,
+								TerranInfantryWeaponsLevel3,
+							]),
+							level(infantry_armor),
+						)
+					} else {
+						(level(vehicle_weapons), level(vehicle_armor))
+					}
+				}
+				Race::Protoss => {
+					if flying {
+						(
+							level([
+								ProtossAirWeaponsLevel1,
+								ProtossAirWeaponsLevel2,
+								ProtossAirWeaponsLevel3,
+							]),
+							level([
+								ProtossAirArmorsLevel1,
+								ProtossAirArmorsLevel2,
+								ProtossAirArmorsLevel3,
+							]),
+						)
+					} else {
+						(
+							level([
+								ProtossGroundWeaponsLevel1,
+								ProtossGroundWeaponsLevel2,
+								ProtossGroundWeaponsLevel3,
+							]),
+							level([
+								ProtossGroundArmorsLevel1,
+								ProtossGroundArmorsLevel2,
+								ProtossGroundArmorsLevel3,
+							]),
+						)
+					}
+				}
+				Race::Zerg => {
+					if flying {
+						(
+							level([
+								ZergFlyerWeaponsLevel1,
+								ZergFlyerWeaponsLevel2,
+								ZergFlyerWeaponsLevel3,
+							]),
+							level([
+								ZergFlyerArmorsLevel1,
+								ZergFlyerArmorsLevel2,
+								ZergFlyerArmorsLevel3,
+							]),
+						)
+					} else {
+						let weapons = if type_id.is_melee() {
+							[
+								ZergMeleeWeaponsLevel1,
+								ZergMeleeWeaponsLevel2,
+								ZergMeleeWeaponsLevel3,
+							]
+						} else {
+							[
+								ZergMissileWeaponsLevel1,
+								ZergMissileWeaponsLevel2,
+								ZergMissileWeaponsLevel3,
+							]
+						};
+						(
+							level(weapons),
+							level([
+								ZergGroundArmorsLevel1,
+								ZergGroundArmorsLevel2,
+								ZergGroundArmorsLevel3,
+							]),
+						)
+					}
+				}
+				Race::Random => (0, 0),
+			}
+		};
+		let shield = if race.is_protoss() {
+			level([ProtossShieldsLevel1, ProtossShieldsLevel2, ProtossShieldsLevel3])
+		} else {
+			0
+		};
+		Self {
+			attack: attack as u32,
+			armor: armor as i32,
+			shield: shield as i32,
+		}
+	}
+}
+
+/// Effective stats of unit calculated from its type data, upgrades and buffs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UnitStats {
+	/// Type of the unit.
+	pub type_id: UnitTypeId,
+	/// Levels of weapon, armor and shield upgrades.
+	pub levels: UpgradeLevels,
+	/// Maximum health (`0` if unknown).
+	pub health_max: u32,
+	/// Maximum shield.
+	pub shield_max: u32,
+	/// Armor of health.
+	pub armor: i32,
+	/// Armor of shield.
+	pub shield_armor: i32,
+	/// How far unit can see.
+	pub sight_range: f32,
+	/// Movement speed off creep.
+	pub speed: f32,
+	/// Movement speed on creep.
+	pub speed_on_creep: f32,
+	/// Weapons with damage, range and cooldown modified by upgrades and buffs.
+	pub weapons: Vec<Weapon>,
+	/// Space that unit takes in transports and bunkers.
+	pub cargo_size: u32,
+	/// Space unit has for passengers (`0` if it's not a transport).
+	pub cargo_capacity: u32,
+}
+impl UnitStats {
+	/// Calculates stats of unit of given type with given upgrades, upgrade levels and buffs.
+	/// Returns `None` if there's no data for this type.
+	pub fn new(
+		type_id: UnitTypeId,
+		data: &GameData,
+		balance: &BalanceTable,
+		upgrades: &FxHashSet<UpgradeId>,
+		levels: UpgradeLevels,
+		buffs: &FxHashSet<BuffId>,
+	) -> Option<Self> {
+		let type_data = data.units.get(&type_id)?;
+		let is_structure = type_data.attributes.contains(&Attribute::Structure);
+		let (mut health_max, shield_max) = balance.hits.get(&type_id).copied().unwrap_or_default();
+		if type_id == UnitTypeId::Marine && upgrades.contains(&UpgradeId::ShieldWall) {
+			health_max += 10;
+		}
+
+		let mut armor = type_data.armor + levels.armor;
+		let mut shield_armor = levels.shield;
+		if type_data.race.is_terran() && is_structure && upgrades.contains(&UpgradeId::TerranBuildingArmor) {
+			armor += 2;
+		}
+		if matches!(type_id, UnitTypeId::Ultralisk | UnitTypeId::UltraliskBurrowed)
+			&& upgrades.contains(&UpgradeId::ChitinousPlating)
+		{
+			armor += 2;
+		}
+		if buffs.contains(&crate::consts::ANTI_ARMOR_BUFF) {
+			armor -= 3;
+			shield_armor -= 3;
+		}
+
+		let speed = movement_speed(balance, type_id, type_data.movement_speed, buffs, upgrades);
+		let (cooldown_modifier, range_modifier) = attack_modifiers(type_id, buffs, upgrades);
+		let bonuses = balance.damage_bonus_per_upgrade.get(&type_id);
+		let weapons = type_data
+			.weapons
+			.iter()
+			.map(|w| {
+				let bonuses = bonuses.and_then(|b| b.get(&w.target));
+				Weapon {
+					target: w.target,
+					damage: w.damage + levels.attack * bonuses.and_then(|b| b.0).unwrap_or(1),
+					damage_bonus: w
+						.damage_bonus
+						.iter()
+						.map(|(attribute, bonus)| {
+							let per_upgrade = bonuses.and_then(|b| b.1.get(attribute)).copied().unwrap_or(0);
+							let extra = attribute_bonus(type_id, *attribute, upgrades, buffs);
+							(*attribute, bonus + levels.attack * per_upgrade + extra)
+						})
+						.collect(),
+					attacks: w.attacks,
+					range: w.range + range_modifier,
+					speed: w.speed * cooldown_modifier,
+				}
+			})
+			.collect();
+
+		let mut cargo_capacity = cargo_capacity(type_id);
+		if type_id == UnitTypeId::Bunker && upgrades.contains(&UpgradeId::NeosteelFrame) {
+			cargo_capacity += 2;
+		}
+
+		Some(Self {
+			type_id,
+			levels,
+			health_max,
+			shield_max,
+			armor,
+			shield_armor,
+			sight_range: type_data.sight_range,
+			speed: creep_speed(balance, type_id, speed, upgrades, false),
+			speed_on_creep: creep_speed(balance, type_id, speed, upgrades, true),
+			weapons,
+			cargo_size: type_data.cargo_size,
+			cargo_capacity,
+		})
+	}
+
+	/// Maximum health plus maximum shield.
+	pub fn hits_max(&self) -> u32 {
+		self.health_max + self.shield_max
+	}
+	/// Range of weapon against ground targets (`0` if unit can't attack ground).
+	pub fn ground_range(&self) -> f32 {
+		self.range(TargetType::Air)
+	}
+	/// Range of weapon against air targets (`0` if unit can't attack air).
+	pub fn air_range(&self) -> f32 {
+		self.range(TargetType::Ground)
+	}
+	/// Damage per second against ground targets without armor and bonuses.
+	pub fn ground_dps(&self) -> f32 {
+		self.dps(TargetType::Air)
+	}
+	/// Damage per second against air targets without armor and bonuses.
+	pub fn air_dps(&self) -> f32 {
+		self.dps(TargetType::Ground)
+	}
+
+	fn weapons_vs(&self, not_target: TargetType) -> impl Iterator<Item = &Weapon> {
+		self.weapons.iter().filter(move |w| w.target != not_target)
+	}
+	fn range(&self, not_target: TargetType) -> f32 {
+		self.weapons_vs(not_target).map(|w| w.range).fold(0.0, f32::max)
+	}
+	fn dps(&self, not_target: TargetType) -> f32 {
+		self.weapons_vs(not_target)
+			.filter(|w| w.speed > 0.0)
+			.map(|w| (w.damage * w.attacks) as f32 / w.speed)
+			.fold(0.0, f32::max)
+	}
+}
+
+/// Multipliers of weapon cooldown and additional range given by buffs and upgrades.
+pub(crate) fn attack_modifiers(
+	type_id: UnitTypeId,
+	buffs: &FxHashSet<BuffId>,
+	upgrades: &FxHashSet<UpgradeId>,
+) -> (f32, f32) {
+	let mut speed_modifier = 1.0;
+	let mut range_modifier = 0.0;
+
+	for buff in buffs {
+		match buff {
+			BuffId::Stimpack | BuffId::StimpackMarauder => speed_modifier /= 1.5,
+			BuffId::TimeWarpProduction => speed_modifier *= 2.0,
+			_ => {}
+		}
+	}
+
+	match type_id {
+		UnitTypeId::Zergling if upgrades.contains(&UpgradeId::Zerglingattackspeed) => speed_modifier /= 1.4,
+		UnitTypeId::Adept if upgrades.contains(&UpgradeId::AdeptPiercingAttack) => speed_modifier /= 1.45,
+		UnitTypeId::Hydralisk if upgrades.contains(&UpgradeId::EvolveGroovedSpines) => range_modifier += 1.0,
+		UnitTypeId::Phoenix if upgrades.contains(&UpgradeId::PhoenixRangeUpgrade) => range_modifier += 2.0,
+		UnitTypeId::Colossus if upgrades.contains(&UpgradeId::ExtendedThermalLance) => range_modifier += 2.0,
+		UnitTypeId::LurkerMPBurrowed if upgrades.contains(&UpgradeId::LurkerRange) => range_modifier += 2.0,
+		UnitTypeId::PlanetaryFortress | UnitTypeId::MissileTurret | UnitTypeId::AutoTurret
+			if upgrades.contains(&UpgradeId::HiSecAutoTracking) =>
+		{
+			range_modifier += 1.0
+		}
+		_ => {}
+	}
+
+	(speed_modifier, range_modifier)
+}
+
+/// Additional bonus damage against attribute given by upgrades and buffs (besides weapon upgrades).
+fn attribute_bonus(
+	type_id: UnitTypeId,
+	attribute: Attribute,
+	upgrades: &FxHashSet<UpgradeId>,
+	buffs: &FxHashSet<BuffId>,
+) -> u32 {
+	match attribute {
+		Attribute::Light if upgrades.contains(&UpgradeId::HighCapacityBarrels) => match type_id {
+			UnitTypeId::Hellion => 5,
+			UnitTypeId::HellionTank => 12,
+			_ => 0,
+		},
+		Attribute::Armored if buffs.contains(&BuffId::VoidRaySwarmDamageBoost) => 6,
+		_ => 0,
+	}
+}
+
+/// Movement speed modified by buffs and upgrades (without creep bonuses).
+pub(crate) fn movement_speed(
+	balance: &BalanceTable,
+	type_id: UnitTypeId,
+	mut speed: f32,
+	buffs: &FxHashSet<BuffId>,
+	upgrades: &FxHashSet<UpgradeId>,
+) -> f32 {
+	// ---- Buffs ----
+	// Ultralisk has passive ability "Frenzied" which makes it immune to speed altering buffs
+	if type_id != UnitTypeId::Ultralisk {
+		for buff in buffs {
+			match buff {
+				BuffId::MedivacSpeedBoost => return speed * 1.7,
+				BuffId::VoidRaySwarmDamageBoost => return speed * 0.75,
+				_ => {
+					if let Some(increase) = balance.speed_buffs.get(buff) {
+						speed *= increase;
+					}
+				}
+			}
+		}
+	}
+
+	// ---- Upgrades ----
+	if let Some((upgrade_id, increase)) = balance.speed_upgrades.get(&type_id) {
+		if upgrades.contains(upgrade_id) {
+			speed *= increase;
+		}
+	}
+
+	speed
+}
+
+/// Movement speed on or off creep, given speed calculated by [`movement_speed`].
+pub(crate) fn creep_speed(
+	balance: &BalanceTable,
+	type_id: UnitTypeId,
+	mut speed: f32,
+	upgrades: &FxHashSet<UpgradeId>,
+	on_creep: bool,
+) -> f32 {
+	if on_creep {
+		// Hydralisks speed upgrade bonus is lower on creep
+		if let Some((upgrade_id, increase)) = balance.on_creep_speed_upgrades.get(&type_id) {
+			if upgrades.contains(upgrade_id) {
+				speed *= increase;
+			}
+		}
+		if let Some(increase) = balance.speed_on_creep.get(&type_id) {
+			speed *= increase;
+		}
+	} else if let Some((upgrade_id, increase)) = balance.off_creep_speed_upgrades.get(&type_id) {
+		if upgrades.contains(upgrade_id) {
+			speed *= increase;
+		}
+	}
+	speed
+}
+
+fn cargo_capacity(type_id: UnitTypeId) -> u32 {
+	match type_id {
+		UnitTypeId::Bunker => 4,
+		UnitTypeId::CommandCenter | UnitTypeId::CommandCenterFlying | UnitTypeId::PlanetaryFortress => 5,
+		UnitTypeId::Medivac
+		| UnitTypeId::WarpPrism
+		| UnitTypeId::WarpPrismPhasing
+		| UnitTypeId::OverlordTransport => 8,
+		_ => 0,
+	}
+}
+
+// Upgrades are given by class of unit, so landed Vikings still use ship upgrades
+fn has_air_upgrades(type_id: UnitTypeId) -> bool {
+	type_id == UnitTypeId::VikingAssault || is_flying_type(type_id)
+}
+
+pub(crate) fn is_flying_type(type_id: UnitTypeId) -> bool {
+	matches!(
+		type_id,
+		UnitTypeId::VikingFighter
+			| UnitTypeId::Medivac
+			| UnitTypeId::Liberator
+			| UnitTypeId::LiberatorAG
+			| UnitTypeId::Raven
+			| UnitTypeId::Banshee
+			| UnitTypeId::Battlecruiser
+			| UnitTypeId::Observer
+			| UnitTypeId::ObserverSiegeMode
+			| UnitTypeId::WarpPrism
+			| UnitTypeId::WarpPrismPhasing
+			| UnitTypeId::Phoenix
+			| UnitTypeId::VoidRay
+			| UnitTypeId::Oracle
+			| UnitTypeId::Tempest
+			| UnitTypeId::Carrier
+			| UnitTypeId::Interceptor
+			| UnitTypeId::Mothership
+			| UnitTypeId::Overlord
+			| UnitTypeId::OverlordTransport
+			| UnitTypeId::Overseer
+			| UnitTypeId::OverseerSiegeMode
+			| UnitTypeId::Mutalisk
+			| UnitTypeId::Corruptor
+			| UnitTypeId::BroodLord
+			| UnitTypeId::Viper
+	)
+}
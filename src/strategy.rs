@@ -0,0 +1,383 @@
+//! Inference of opponent's opening from observed structures, units and their timings.
+//!
+//! Observations are collected automatically every step into [`strategy`](crate::bot::Bot::strategy)
+//! field of bot and matched against the list of [`StrategyRule`]s.
+//! The default ruleset can be extended or replaced through [`rules`](StrategyInference::rules) field.
+//!
+//! ```
+//! use rust_sc2::prelude::*;
+//! use rust_sc2::strategy::{EnemyStrategy, StrategyCondition, StrategyRule};
+//!
+//! let mut bot = rust_sc2::bot::Bot::default();
+//! bot.strategy.rules.push(StrategyRule {
+//!     strategy: EnemyStrategy::Custom("Early Reapers".to_string()),
+//!     race: Some(Race::Terran),
+//!     conditions: vec![
+//!         (StrategyCondition::SeenBefore(UnitTypeId::Reaper, 120.0), 2.0),
+//!         (StrategyCondition::NotSeenBefore(UnitTypeId::CommandCenter, 120.0), 1.0),
+//!     ],
+//! });
+//!
+//! if let Some(estimate) = bot.enemy_strategy() {
+//!     println!("{:?} with confidence {}", estimate.strategy, estimate.confidence);
+//! }
+//! ```
+
+use crate::{
+	bot::Bot,
+	consts::FRAMES_PER_SECOND,
+	distance::Distance,
+	ids::{UnitTypeId, UpgradeId},
+	player::Race,
+};
+use rustc_hash::FxHashMap;
+use std::cmp::Ordering;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Opening of the opponent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EnemyStrategy {
+	/// Barracks built near bot's base.
+	ProxyBarracks,
+	/// Gateways built near bot's base.
+	ProxyGateway,
+	/// Photon cannons built near bot's base.
+	CannonRush,
+	/// Spawning pool built at 12 supply or earlier.
+	TwelvePool,
+	/// Second townhall taken before any production.
+	FastExpand,
+	/// Tech structures on one base.
+	OneBaseTech,
+	/// Strategy defined by user rules.
+	Custom(String),
+}
+
+/// Condition of [`StrategyRule`]. Times are in game seconds.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StrategyCondition {
+	/// Unit or structure of given type was seen before given time.
+	SeenBefore(UnitTypeId, f32),
+	/// Structure of given type was started before given time.
+	/// Start time is estimated from build progress of the structure when it's seen,
+	/// so timings can be recognized even if structure was scouted later.
+	StartedBefore(UnitTypeId, f32),
+	/// Unit or structure of any of given types was seen before given time.
+	SeenAnyBefore(Vec<UnitTypeId>, f32),
+	/// At least given number of units or structures of given type were seen before given time.
+	CountBefore(UnitTypeId, usize, f32),
+	/// Given time passed and unit or structure of given type wasn't seen before it.
+	NotSeenBefore(UnitTypeId, f32),
+	/// Structure of given type was seen closer to bot's start location than to opponent's one
+	/// before given time.
+	ProxyBefore(UnitTypeId, f32),
+	/// At least given number of townhalls were seen before given time.
+	TownhallsBefore(usize, f32),
+	/// Given time passed and less than given number of townhalls were seen before it.
+	FewerTownhallsAt(usize, f32),
+	/// Opponent has given upgrade.
+	Upgrade(UpgradeId),
+}
+impl StrategyCondition {
+	/// Checks if condition is satisfied by absence of something
+	/// ([`NotSeenBefore`](Self::NotSeenBefore) and [`FewerTownhallsAt`](Self::FewerTownhallsAt)).
+	pub fn is_absence(&self) -> bool {
+		matches!(self, Self::NotSeenBefore(..) | Self::FewerTownhallsAt(..))
+	}
+}
+
+/// Rule describing how to recognize some strategy.
+///
+/// Confidence of the rule is a sum of weights of satisfied conditions
+/// divided by the sum of all weights.
+///
+/// Not seeing something is only evidence when the opponent was actually scouted,
+/// so [absence](StrategyCondition::is_absence) conditions count only
+/// if at least one other condition of the rule is satisfied.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrategyRule {
+	/// Strategy recognized by this rule.
+	pub strategy: EnemyStrategy,
+	/// Race of the opponent this rule applies to, `None` means any race.
+	pub race: Option<Race>,
+	/// Conditions with their weights.
+	pub conditions: Vec<(StrategyCondition, f32)>,
+}
+impl StrategyRule {
+	/// Returns default ruleset used by [`StrategyInference`].
+	pub fn defaults() -> Vec<Self> {
+		use StrategyCondition::*;
+		use UnitTypeId as UT;
+
+		vec![
+			Self {
+				strategy: EnemyStrategy::ProxyBarracks,
+				race: Some(Race::Terran),
+				conditions: vec![
+					(ProxyBefore(UT::Barracks, 150.0), 3.0),
+					(SeenBefore(UT::Marine, 120.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::ProxyGateway,
+				race: Some(Race::Protoss),
+				conditions: vec![
+					(ProxyBefore(UT::Gateway, 150.0), 3.0),
+					(SeenBefore(UT::Zealot, 150.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::CannonRush,
+				race: Some(Race::Protoss),
+				conditions: vec![
+					(ProxyBefore(UT::PhotonCannon, 180.0), 3.0),
+					(ProxyBefore(UT::Pylon, 120.0), 1.0),
+					(StartedBefore(UT::Forge, 60.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::TwelvePool,
+				race: Some(Race::Zerg),
+				conditions: vec![
+					(StartedBefore(UT::SpawningPool, 30.0), 2.0),
+					(SeenBefore(UT::Zergling, 100.0), 1.0),
+					(FewerTownhallsAt(2, 60.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::FastExpand,
+				race: Some(Race::Terran),
+				conditions: vec![
+					(TownhallsBefore(2, 80.0), 2.0),
+					(NotSeenBefore(UT::Barracks, 60.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::FastExpand,
+				race: Some(Race::Protoss),
+				conditions: vec![
+					(TownhallsBefore(2, 80.0), 2.0),
+					(NotSeenBefore(UT::Gateway, 45.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::FastExpand,
+				race: Some(Race::Zerg),
+				conditions: vec![
+					(TownhallsBefore(2, 60.0), 2.0),
+					(NotSeenBefore(UT::SpawningPool, 45.0), 1.0),
+				],
+			},
+			Self {
+				strategy: EnemyStrategy::OneBaseTech,
+				race: None,
+				conditions: vec![
+					(
+						SeenAnyBefore(
+							vec![
+								UT::Factory,
+								UT::Starport,
+								UT::Stargate,
+								UT::RoboticsFacility,
+								UT::TwilightCouncil,
+								UT::DarkShrine,
+								UT::Lair,
+								UT::RoachWarren,
+								UT::BanelingNest,
+							],
+							180.0,
+						),
+						2.0,
+					),
+					(FewerTownhallsAt(2, 180.0), 1.0),
+				],
+			},
+		]
+	}
+
+	fn confidence(&self, inference: &StrategyInference, bot: &Bot) -> f32 {
+		if let Some(race) = self.race {
+			if bot.enemy_race != Race::Random && bot.enemy_race != race {
+				return 0.0;
+			}
+		}
+		let total = self.conditions.iter().map(|(_, w)| w).sum::<f32>();
+		if total <= 0.0 {
+			return 0.0;
+		}
+		let (absence, positive) = self
+			.conditions
+			.iter()
+			.filter(|(c, _)| inference.is_satisfied(c, bot))
+			.fold((0.0, 0.0), |(absence, positive), (c, w)| {
+				if c.is_absence() {
+					(absence + w, positive)
+				} else {
+					(absence, positive + w)
+				}
+			});
+		if positive > 0.0 {
+			(positive + absence) / total
+		} else {
+			0.0
+		}
+	}
+}
+
+/// Estimated strategy with its confidence.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StrategyEstimate {
+	/// Recognized strategy.
+	pub strategy: EnemyStrategy,
+	/// Confidence in range `0..=1`.
+	pub confidence: f32,
+}
+
+/// Collects observations of opponent's units and estimates its strategy using rules.
+#[derive(Debug, Clone)]
+pub struct StrategyInference {
+	/// Rules used to estimate strategy, initialized with [`StrategyRule::defaults`].
+	pub rules: Vec<StrategyRule>,
+	counts: FxHashMap<UnitTypeId, Vec<f32>>,
+	proxies: FxHashMap<UnitTypeId, f32>,
+	started: FxHashMap<UnitTypeId, f32>,
+	townhalls: Vec<f32>,
+	estimates: Vec<StrategyEstimate>,
+	time: f32,
+}
+impl Default for StrategyInference {
+	fn default() -> Self {
+		Self {
+			rules: StrategyRule::defaults(),
+			counts: Default::default(),
+			proxies: Default::default(),
+			started: Default::default(),
+			townhalls: Default::default(),
+			estimates: Default::default(),
+			time: 0.0,
+		}
+	}
+}
+impl StrategyInference {
+	pub(crate) fn update(bot: &mut Bot) {
+		let time = bot.time;
+		let inference = &mut bot.strategy;
+		inference.time = time;
+
+		for (id, count) in &bot.enemies_current {
+			let ordered = bot.enemies_ordered.get(id).copied().unwrap_or(0);
+			record(inference.counts.entry(*id).or_default(), count + ordered, time);
+		}
+		for (id, count) in &bot.enemies_ordered {
+			if !bot.enemies_current.contains_key(id) {
+				record(inference.counts.entry(*id).or_default(), *count, time);
+			}
+		}
+
+		let enemy = &bot.units.enemy;
+		record(&mut inference.townhalls, enemy.townhalls.len(), time);
+		for s in &enemy.structures {
+			let started = time - s.build_progress() * s.build_time() / FRAMES_PER_SECOND;
+			inference
+				.started
+				.entry(s.type_id())
+				.and_modify(|t| *t = t.min(started))
+				.or_insert(started);
+			if s.distance_squared(bot.start_location) < s.distance_squared(bot.enemy_start) {
+				inference.proxies.entry(s.type_id()).or_insert(time);
+			}
+		}
+
+		// Rules are taken out to be able to check them against the whole bot
+		let rules = std::mem::take(&mut bot.strategy.rules);
+		let mut estimates = rules
+			.iter()
+			.map(|rule| StrategyEstimate {
+				strategy: rule.strategy.clone(),
+				confidence: rule.confidence(&bot.strategy, bot),
+			})
+			.filter(|e| e.confidence > 0.0)
+			.collect::<Vec<_>>();
+		estimates.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(Ordering::Equal));
+		bot.strategy.rules = rules;
+		bot.strategy.estimates = estimates;
+	}
+
+	fn is_satisfied(&self, condition: &StrategyCondition, bot: &Bot) -> bool {
+		use StrategyCondition::*;
+
+		match condition {
+			SeenBefore(id, time) => self.first_seen(*id).is_some_and(|t| t <= *time),
+			StartedBefore(id, time) => self.started.get(id).is_some_and(|t| t <= time),
+			SeenAnyBefore(ids, time) => ids
+				.iter()
+				.any(|id| self.first_seen(*id).is_some_and(|t| t <= *time)),
+			CountBefore(id, count, time) => self.count_seen_at(*id, *count).is_some_and(|t| t <= *time),
+			NotSeenBefore(id, time) => {
+				self.time >= *time && !self.first_seen(*id).is_some_and(|t| t <= *time)
+			}
+			ProxyBefore(id, time) => self.proxies.get(id).is_some_and(|t| t <= time),
+			TownhallsBefore(count, time) => reached_at(&self.townhalls, *count).is_some_and(|t| t <= *time),
+			FewerTownhallsAt(count, time) => {
+				self.time >= *time && !reached_at(&self.townhalls, *count).is_some_and(|t| t <= *time)
+			}
+			Upgrade(upgrade) => bot.enemy_has_upgrade(*upgrade),
+		}
+	}
+
+	/// Returns estimates with nonzero confidence sorted by confidence in descending order.
+	pub fn estimates(&self) -> &[StrategyEstimate] {
+		&self.estimates
+	}
+	/// Returns estimate with the highest confidence.
+	pub fn best(&self) -> Option<&StrategyEstimate> {
+		self.estimates.first()
+	}
+	/// Returns confidence of given strategy.
+	pub fn confidence(&self, strategy: &EnemyStrategy) -> f32 {
+		self.estimates
+			.iter()
+			.find(|e| &e.strategy == strategy)
+			.map_or(0.0, |e| e.confidence)
+	}
+	/// Returns game time in seconds when unit or structure of given type was seen first time.
+	pub fn first_seen(&self, id: UnitTypeId) -> Option<f32> {
+		self.count_seen_at(id, 1)
+	}
+	/// Returns game time in seconds when given number of units or structures of given type
+	/// were seen simultaneously first time.
+	pub fn count_seen_at(&self, id: UnitTypeId, count: usize) -> Option<f32> {
+		self.counts.get(&id).and_then(|times| reached_at(times, count))
+	}
+	/// Returns estimated game time in seconds when the earliest seen structure
+	/// of given type was started.
+	pub fn started_at(&self, id: UnitTypeId) -> Option<f32> {
+		self.started.get(&id).copied()
+	}
+	/// Returns game time in seconds when proxy structure of given type was seen first time.
+	pub fn proxy_seen(&self, id: UnitTypeId) -> Option<f32> {
+		self.proxies.get(&id).copied()
+	}
+	/// Returns game time in seconds when given number of opponent's townhalls were seen first time.
+	pub fn townhalls_seen_at(&self, count: usize) -> Option<f32> {
+		reached_at(&self.townhalls, count)
+	}
+}
+
+// `times[i]` is game time when count `i + 1` was reached first time
+fn record(times: &mut Vec<f32>, count: usize, time: f32) {
+	while times.len() < count {
+		times.push(time);
+	}
+}
+fn reached_at(times: &[f32], count: usize) -> Option<f32> {
+	match count {
+		0 => Some(0.0),
+		_ => times.get(count - 1).copied(),
+	}
+}
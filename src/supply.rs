@@ -0,0 +1,330 @@
+//! Supply planner, which forecasts supply demand of production and tells when to start supply providers.
+//!
+//! [`supply_timeline`](crate::bot::Bot::supply_timeline) collects supply providers in progress
+//! and supply which production is expected to request in the next seconds:
+//! - Every slot of production structure (two with reactor) queues a new unit when current one finishes.
+//! - Warp gates warp a new unit when ready, then once per build time of the unit.
+//! - Existing larva are used immediately, every hatchery spawns a new larva each 11 seconds.
+//!
+//! Units are taken from ratios set in [`production`](crate::bot::Bot::production) if any,
+//! otherwise producers are expected to repeat their current orders, while idle townhalls and larva make workers.
+//! Larva from injects isn't forecasted.
+//!
+//! [`supply_forecast`](crate::bot::Bot::supply_forecast) then finds when new providers
+//! should be started, so they finish right before supply is needed.
+//!
+//! ```
+//! # let bot = rust_sc2::bot::Bot::default();
+//! let forecast = bot.supply_forecast(30.0);
+//! if forecast.start_now() > 0 {
+//!     // Build supply provider
+//! }
+//! ```
+
+use crate::{
+	action::Target,
+	bot::Bot,
+	consts::{ALL_PRODUCERS, FRAMES_PER_SECOND, WARPGATE_ABILITIES},
+	distance::Distance,
+	ids::{AbilityId, UnitTypeId},
+};
+use rustc_hash::FxHashMap;
+use std::cmp::Ordering;
+
+/// Maximum supply cap in the game.
+pub const MAX_SUPPLY: f32 = 200.0;
+/// Seconds between spawns of larva by hatchery.
+const LARVA_PERIOD: f32 = 11.0;
+
+/// Supply changes expected in the next seconds.
+#[derive(Debug, Clone, Default)]
+pub struct SupplyTimeline {
+	/// Currently used supply.
+	pub supply_used: f32,
+	/// Current supply cap.
+	pub supply_cap: f32,
+	/// Supply providers in progress: seconds from now when they finish and supply they provide.
+	pub pending: Vec<(f32, f32)>,
+	/// Supply requested by production: seconds from now and amount of supply.
+	pub demand: Vec<(f32, f32)>,
+}
+impl SupplyTimeline {
+	/// Total supply requested by production.
+	pub fn total_demand(&self) -> f32 {
+		self.demand.iter().map(|(_, s)| s).sum()
+	}
+	/// Total supply provided by providers in progress.
+	pub fn total_pending(&self) -> f32 {
+		self.pending.iter().map(|(_, s)| s).sum()
+	}
+	/// Returns seconds from now when production will be supply blocked if no new providers are started.
+	pub fn blocked_in(&self) -> Option<f32> {
+		let mut used = self.supply_used;
+		for (time, supply) in sorted(&self.demand) {
+			if used + supply > MAX_SUPPLY {
+				return None;
+			}
+			if used + supply > self.cap_at(time, &[]) {
+				return Some(time);
+			}
+			used += supply;
+		}
+		None
+	}
+	/// Finds when providers of given supply and build time (in seconds) should be started,
+	/// so that production isn't blocked.
+	pub fn forecast(&self, provider: UnitTypeId, provided: f32, build_time: f32) -> SupplyForecast {
+		let mut added = Vec::<(f32, f32)>::new();
+		let mut starts = Vec::new();
+		let mut used = self.supply_used;
+
+		if provided > 0.0 {
+			for (time, supply) in sorted(&self.demand) {
+				if used + supply > MAX_SUPPLY {
+					break;
+				}
+				if used + supply > self.cap_at(time, &added) {
+					// Provider is added only if planned ones won't cover this demand even later
+					let eventual =
+						self.supply_cap + self.total_pending() + added.iter().map(|(_, s)| s).sum::<f32>();
+					if used + supply > eventual.min(MAX_SUPPLY) {
+						let start = (time - build_time).max(0.0);
+						starts.push(start);
+						added.push((start + build_time, provided));
+					}
+				}
+				used += supply;
+			}
+		}
+
+		let cap = (self.supply_cap + self.total_pending() + provided * starts.len() as f32).min(MAX_SUPPLY);
+		SupplyForecast {
+			provider,
+			demand: self.total_demand(),
+			pending: self.total_pending(),
+			blocked_in: self.blocked_in(),
+			starts,
+			surplus: (cap - used).max(0.0),
+		}
+	}
+
+	fn cap_at(&self, time: f32, added: &[(f32, f32)]) -> f32 {
+		let provided = self
+			.pending
+			.iter()
+			.chain(added)
+			.filter(|(t, _)| *t <= time)
+			.map(|(_, s)| s)
+			.sum::<f32>();
+		(self.supply_cap + provided).min(MAX_SUPPLY)
+	}
+}
+
+/// Result of supply planning made by [`supply_forecast`](crate::bot::Bot::supply_forecast).
+#[derive(Debug, Clone)]
+pub struct SupplyForecast {
+	/// Supply provider of bot's race.
+	pub provider: UnitTypeId,
+	/// Supply which production is expected to request.
+	pub demand: f32,
+	/// Supply provided by providers in progress.
+	pub pending: f32,
+	/// Seconds from now when production will be supply blocked if no new providers are started.
+	pub blocked_in: Option<f32>,
+	/// Seconds from now when new providers should be started, in ascending order.
+	/// `0` means that provider should be started right now (or it's already late).
+	pub starts: Vec<f32>,
+	/// Supply which will be left unused at the end of forecast with planned providers.
+	pub surplus: f32,
+}
+impl SupplyForecast {
+	/// Seconds from now when the next provider should be started.
+	pub fn start_in(&self) -> Option<f32> {
+		self.starts.first().copied()
+	}
+	/// Number of providers which should be started right now.
+	pub fn start_now(&self) -> usize {
+		self.starts.iter().take_while(|t| **t <= 0.0).count()
+	}
+}
+
+fn sorted(events: &[(f32, f32)]) -> Vec<(f32, f32)> {
+	let mut events = events.to_vec();
+	events.sort_by(|(t1, _), (t2, _)| t1.partial_cmp(t2).unwrap_or(Ordering::Equal));
+	events
+}
+
+pub(crate) fn supply_timeline(bot: &Bot, horizon: f32) -> SupplyTimeline {
+	let units = &bot.game_data.units;
+	let seconds = |unit: UnitTypeId| {
+		units
+			.get(&unit)
+			.map_or(0.0, |data| data.build_time / FRAMES_PER_SECOND)
+	};
+	let trained_by = units
+		.values()
+		.filter_map(|data| Some((data.ability?, data.id)))
+		.collect::<FxHashMap<AbilityId, UnitTypeId>>();
+	let unit_of = |ability: AbilityId| {
+		trained_by.get(&ability).copied().or_else(|| {
+			WARPGATE_ABILITIES
+				.iter()
+				.find(|(_, a)| **a == ability)
+				.map(|(unit, _)| *unit)
+		})
+	};
+
+	let mut timeline = SupplyTimeline {
+		supply_used: bot.supply_used as f32,
+		supply_cap: bot.supply_cap as f32,
+		pending: vec![],
+		demand: vec![],
+	};
+
+	// Providers in progress
+	let my = &bot.units.my;
+	for u in my.all.iter().filter(|u| !u.is_ready()) {
+		if let Some(data) = units.get(&u.type_id()).filter(|data| data.food_provided > 0.0) {
+			let left = (1.0 - u.build_progress()) * seconds(u.type_id());
+			timeline.pending.push((left, data.food_provided));
+		}
+	}
+	for u in my.all.iter() {
+		for order in u.orders() {
+			let unit = match unit_of(order.ability) {
+				Some(unit) => unit,
+				None => continue,
+			};
+			let provided = units.get(&unit).map_or(0.0, |data| data.food_provided);
+			if provided <= 0.0 || unit == u.type_id() {
+				continue;
+			}
+			// Structure which is already placed is counted above
+			if let Target::Pos(pos) = order.target {
+				if my
+					.structures
+					.iter()
+					.any(|s| s.type_id() == unit && !s.is_ready() && s.position().distance_squared(pos) < 1.0)
+				{
+					continue;
+				}
+			}
+			timeline
+				.pending
+				.push(((1.0 - order.progress) * seconds(unit), provided));
+		}
+	}
+
+	// Units which producer is expected to make and their supply and build time
+	let goals = bot.production.ratios().collect::<Vec<_>>();
+	let production_of = |producer: UnitTypeId, current: Option<UnitTypeId>| -> Option<(f32, f32)> {
+		let mut total = (0.0, 0.0, 0.0);
+		for (unit, weight) in goals.iter().filter(|(_, w)| *w > 0.0) {
+			if ALL_PRODUCERS.get(unit).is_some_and(|p| p.contains(&producer)) {
+				if let Some(data) = units.get(unit) {
+					total.0 += data.food_required * weight;
+					total.1 += seconds(*unit) * weight;
+					total.2 += weight;
+				}
+			}
+		}
+		let (supply, time) = if total.2 > 0.0 {
+			(total.0 / total.2, total.1 / total.2)
+		} else {
+			let worker = bot.race_values.worker;
+			let unit = current.or_else(|| {
+				ALL_PRODUCERS
+					.get(&worker)
+					.is_some_and(|p| p.contains(&producer))
+					.then_some(worker)
+			})?;
+			(units.get(&unit)?.food_required, seconds(unit))
+		};
+		(supply > 0.0 && time > 0.0).then_some((supply, time))
+	};
+	let mut add_slot = |start: f32, (supply, time): (f32, f32)| {
+		let mut t = start;
+		while t < horizon {
+			timeline.demand.push((t, supply));
+			t += time;
+		}
+	};
+
+	// Production structures and warp gates
+	for s in my.structures.iter().filter(|s| s.is_ready()) {
+		let producer = s.type_id();
+		if producer == UnitTypeId::WarpGate {
+			let current = s.orders().first().and_then(|o| unit_of(o.ability));
+			if let Some(production) = production_of(producer, current) {
+				let ready = WARPGATE_ABILITIES.values().any(|a| s.has_ability(*a));
+				add_slot(if ready { 0.0 } else { production.1 / 2.0 }, production);
+			}
+			continue;
+		}
+		if !is_producer(producer) {
+			continue;
+		}
+		let slots = if s.has_reactor() { 2 } else { 1 };
+		let mut free_at = vec![0.0; slots];
+		let mut current = None;
+		for (i, order) in s.orders().iter().enumerate() {
+			let unit = unit_of(order.ability);
+			let time = unit.map_or(0.0, &seconds);
+			let slot = i % slots;
+			free_at[slot] += if i < slots {
+				(1.0 - order.progress) * time
+			} else {
+				time
+			};
+			current = current.or(unit.filter(|u| units.get(u).is_some_and(|d| d.food_required > 0.0)));
+			if unit.is_none() {
+				// Research or morph, structure is busy for unknown time
+				free_at[slot] = horizon;
+			}
+		}
+		if let Some(production) = production_of(producer, current) {
+			for start in free_at {
+				add_slot(start, production);
+			}
+		}
+	}
+
+	// Larva
+	if let Some(production) = production_of(UnitTypeId::Larva, None) {
+		for _ in my.larvas.iter() {
+			timeline.demand.push((0.0, production.0));
+		}
+		let hatcheries = my
+			.townhalls
+			.iter()
+			.filter(|t| {
+				t.is_ready()
+					&& matches!(
+						t.type_id(),
+						UnitTypeId::Hatchery | UnitTypeId::Lair | UnitTypeId::Hive
+					)
+			})
+			.count();
+		for _ in 0..hatcheries {
+			let mut t = LARVA_PERIOD;
+			while t < horizon {
+				timeline.demand.push((t, production.0));
+				t += LARVA_PERIOD;
+			}
+		}
+	}
+	timeline
+}
+
+pub(crate) fn supply_forecast(bot: &Bot, horizon: f32) -> SupplyForecast {
+	let provider = bot.race_values.supply;
+	let (provided, build_time) = bot.game_data.units.get(&provider).map_or((0.0, 0.0), |data| {
+		(data.food_provided, data.build_time / FRAMES_PER_SECOND)
+	});
+	supply_timeline(bot, horizon).forecast(provider, provided, build_time)
+}
+
+// Checks if structure trains units using supply
+fn is_producer(unit: UnitTypeId) -> bool {
+	ALL_PRODUCERS.values().any(|producers| producers.contains(&unit)) && !matches!(unit, UnitTypeId::Larva)
+}
@@ -0,0 +1,404 @@
+//! Tech tree planner, which finds missing requirements for units and upgrades.
+//!
+//! Plans are constructed with [`tech_plan`](crate::bot::Bot::tech_plan) method of bot.
+
+use crate::{
+	bot::Bot,
+	consts::{
+		ALL_PRODUCERS, ALL_RESEARCHERS, FRAMES_PER_SECOND, PRODUCERS, RESEARCHERS, TECH_ALIAS,
+		TECH_REQUIREMENTS, UPGRADE_PREVIOUS_LEVEL, UPGRADE_TECH_REQUIREMENTS,
+	},
+	game_data::Cost,
+	ids::{UnitTypeId, UpgradeId},
+	player::Race,
+};
+use rustc_hash::FxHashMap;
+use std::iter::once;
+
+/// Item of the tech tree: unit (or structure) type or upgrade.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TechItem {
+	/// Unit, structure or addon.
+	Unit(UnitTypeId),
+	/// Research.
+	Upgrade(UpgradeId),
+}
+impl From<UnitTypeId> for TechItem {
+	fn from(id: UnitTypeId) -> Self {
+		Self::Unit(id)
+	}
+}
+impl From<UpgradeId> for TechItem {
+	fn from(id: UpgradeId) -> Self {
+		Self::Upgrade(id)
+	}
+}
+
+/// Kind of [`TechStep`].
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TechStepKind {
+	/// Structure built by worker or morphed from other structure.
+	Structure,
+	/// Addon of terran production structure.
+	Addon,
+	/// Research of upgrade.
+	Research,
+	/// Unit trained in production structure or morphed from other unit.
+	Unit,
+}
+
+/// Single step of [`TechPlan`].
+#[derive(Debug, Clone)]
+pub struct TechStep {
+	/// What should be built or researched.
+	pub item: TechItem,
+	/// Kind of this step.
+	pub kind: TechStepKind,
+	/// Cost of the item (time is in game loops, as in [`GameData`](crate::game_data::GameData)).
+	pub cost: Cost,
+	/// Estimated amount of seconds from now when this step can be started.
+	/// Equals to `f32::INFINITY` if it can't be afforded with current income.
+	pub start: f32,
+	/// Estimated amount of seconds from now when this step will be finished.
+	/// Equals to `f32::INFINITY` if it can't be afforded with current income.
+	pub finish: f32,
+}
+
+/// Ordered chain of missing structures, addons and researches required to get the goal.
+/// Steps are sorted in order they should be started, goal itself is the last step.
+///
+/// Timings are estimated from current resources and income,
+/// assuming that nothing else is spent and that supply is not blocked.
+#[derive(Debug, Clone)]
+pub struct TechPlan {
+	/// Item for which this plan was made.
+	pub goal: TechItem,
+	/// Missing steps required to get the goal.
+	pub steps: Vec<TechStep>,
+	/// Estimated amount of seconds from now when the goal will be finished.
+	pub finish: f32,
+}
+impl TechPlan {
+	pub(crate) fn new(bot: &Bot, goal: TechItem) -> Self {
+		let mut planner = Planner {
+			bot,
+			race: bot.race,
+			ready: Default::default(),
+			deps: vec![],
+		};
+		planner.require(goal, true);
+
+		let deps = planner.deps;
+		let mut ready = planner.ready;
+
+		// Recalculating timings with resources spent in order of steps
+		let minerals_rate = bot.state.observation.score.collection_rate_minerals / 60.0;
+		let vespene_rate = bot.state.observation.score.collection_rate_vespene / 60.0;
+		let affordable_at = |minerals: u32, vespene: u32| {
+			fn time(need: u32, have: u32, rate: f32) -> f32 {
+				if need <= have {
+					0.0
+				} else if rate > 0.0 {
+					(need - have) as f32 / rate
+				} else {
+					f32::INFINITY
+				}
+			}
+			time(minerals, bot.minerals, minerals_rate).max(time(vespene, bot.vespene, vespene_rate))
+		};
+
+		let mut spent = (0, 0);
+		let mut steps = Vec::with_capacity(deps.len());
+		for (item, kind, requirements) in deps {
+			let cost = match item {
+				TechItem::Unit(id) => bot.get_unit_cost(id),
+				TechItem::Upgrade(id) => bot.get_upgrade_cost(id),
+			};
+			spent.0 += cost.minerals;
+			spent.1 += cost.vespene;
+
+			let start = requirements
+				.iter()
+				.map(|r| ready.get(r).copied().unwrap_or(0.0))
+				.fold(affordable_at(spent.0, spent.1), f32::max);
+			let finish = start + cost.time / FRAMES_PER_SECOND;
+			ready.insert(item, finish);
+
+			steps.push(TechStep {
+				item,
+				kind,
+				cost,
+				start,
+				finish,
+			});
+		}
+
+		Self {
+			goal,
+			finish: ready.get(&goal).copied().unwrap_or(0.0),
+			steps,
+		}
+	}
+	/// Returns estimated amount of seconds from now when the goal will be finished,
+	/// or `None` if it can't be reached with current income.
+	pub fn eta(&self) -> Option<f32> {
+		if self.finish.is_finite() {
+			Some(self.finish)
+		} else {
+			None
+		}
+	}
+	/// Returns summed cost of all steps (time is summed build time in game loops).
+	pub fn total_cost(&self) -> Cost {
+		self.steps.iter().fold(Cost::default(), |mut total, step| {
+			total.minerals += step.cost.minerals;
+			total.vespene += step.cost.vespene;
+			total.supply += step.cost.supply;
+			total.time += step.cost.time;
+			total
+		})
+	}
+	/// Returns `true` if goal is already available or in progress and no steps required.
+	pub fn is_available(&self) -> bool {
+		self.steps.is_empty()
+	}
+}
+
+struct Planner<'a> {
+	bot: &'a Bot,
+	race: Race,
+	/// Seconds from now until items are ready (for items already owned or in progress).
+	ready: FxHashMap<TechItem, f32>,
+	/// Missing items with their requirements in order they should be made.
+	deps: Vec<(TechItem, TechStepKind, Vec<TechItem>)>,
+}
+impl Planner<'_> {
+	fn require(&mut self, item: TechItem, is_goal: bool) {
+		if self.ready.contains_key(&item) || self.deps.iter().any(|(i, _, _)| *i == item) {
+			return;
+		}
+		match item {
+			TechItem::Unit(id) => self.require_unit(id, is_goal),
+			TechItem::Upgrade(id) => self.require_upgrade(id),
+		}
+	}
+	fn require_unit(&mut self, id: UnitTypeId, is_goal: bool) {
+		let kind = self.unit_kind(id);
+		// Units are always made for goal, but existing ones are enough as requirements.
+		if !(is_goal && kind.is_unit()) {
+			if let Some(time) = self.existing_time(id) {
+				self.ready.insert(TechItem::Unit(id), time);
+				return;
+			}
+		}
+
+		let mut requirements = vec![];
+		if kind.is_addon() {
+			if let Some(parent) = addon_parent(id) {
+				requirements.push(parent);
+			}
+		} else if let Some(producer) = self.producer(id) {
+			requirements.push(producer);
+		}
+		if let Some(tech) = self.tech_requirement(id) {
+			requirements.push(tech);
+		}
+		if self.requires_techlab(id) {
+			if let Some(addon) = PRODUCERS.get(&id).copied().and_then(techlab_of) {
+				requirements.push(addon);
+			}
+		}
+		if self.race.is_protoss()
+			&& kind.is_structure()
+			&& !matches!(
+				id,
+				UnitTypeId::Pylon | UnitTypeId::Nexus | UnitTypeId::Assimilator | UnitTypeId::AssimilatorRich
+			) {
+			requirements.push(UnitTypeId::Pylon);
+		}
+		if self.needs_gas(id) {
+			requirements.push(self.bot.race_values.gas);
+		}
+
+		let requirements = requirements.into_iter().map(TechItem::Unit).collect::<Vec<_>>();
+		for r in &requirements {
+			self.require(*r, false);
+		}
+		self.deps.push((TechItem::Unit(id), kind, requirements));
+	}
+	fn require_upgrade(&mut self, id: UpgradeId) {
+		let bot = self.bot;
+		if bot.has_upgrade(id) {
+			self.ready.insert(TechItem::Upgrade(id), 0.0);
+			return;
+		}
+		if bot.is_ordered_upgrade(id) {
+			let time_left = bot
+				.game_data
+				.upgrades
+				.get(&id)
+				.map_or(0.0, |data| data.research_time / FRAMES_PER_SECOND)
+				* (1.0 - bot.upgrade_progress(id));
+			self.ready.insert(TechItem::Upgrade(id), time_left);
+			return;
+		}
+
+		let mut requirements = vec![];
+		let researchers = ALL_RESEARCHERS.get(&id);
+		let researcher = researchers
+			.and_then(|all| {
+				all.iter()
+					.copied()
+					.filter_map(|r| self.existing_time(r).map(|time| (r, time)))
+					.min_by(|(_, t1), (_, t2)| t1.partial_cmp(t2).unwrap_or(std::cmp::Ordering::Equal))
+					.map(|(r, _)| r)
+			})
+			.or_else(|| researchers.and_then(|all| all.first().copied()))
+			.or_else(|| RESEARCHERS.get(&id).copied());
+		if let Some(researcher) = researcher {
+			requirements.push(TechItem::Unit(researcher));
+		}
+		if let Some(tech) = UPGRADE_TECH_REQUIREMENTS.get(&id) {
+			requirements.push(TechItem::Unit(*tech));
+		}
+		if let Some(previous) = UPGRADE_PREVIOUS_LEVEL.get(&id) {
+			requirements.push(TechItem::Upgrade(*previous));
+		}
+		if self.needs_gas_upgrade(id) {
+			requirements.push(TechItem::Unit(bot.race_values.gas));
+		}
+
+		for r in &requirements {
+			self.require(*r, false);
+		}
+		self.deps
+			.push((TechItem::Upgrade(id), TechStepKind::Research, requirements));
+	}
+
+	fn unit_kind(&self, id: UnitTypeId) -> TechStepKind {
+		if addon_parent(id).is_some() {
+			TechStepKind::Addon
+		} else if let Some(data) = self.bot.game_data.units.get(&id) {
+			if data.attributes.iter().any(|a| a.is_structure()) {
+				TechStepKind::Structure
+			} else {
+				TechStepKind::Unit
+			}
+		} else if PRODUCERS.get(&id).is_some_and(|p| is_worker(*p)) {
+			TechStepKind::Structure
+		} else {
+			TechStepKind::Unit
+		}
+	}
+	/// Returns producer which should be made, or `None` if suitable one already exists
+	/// or can't be made (workers and larva are considered always available).
+	fn producer(&mut self, id: UnitTypeId) -> Option<UnitTypeId> {
+		let producer = PRODUCERS.get(&id).copied()?;
+		if is_worker(producer) || producer == UnitTypeId::Larva {
+			return None;
+		}
+		let all = ALL_PRODUCERS.get(&id).map_or(&[][..], |all| all.as_slice());
+		if let Some(time) = all
+			.iter()
+			.filter_map(|p| self.existing_time(*p))
+			.min_by(|t1, t2| t1.partial_cmp(t2).unwrap_or(std::cmp::Ordering::Equal))
+		{
+			self.ready.insert(TechItem::Unit(producer), time);
+		}
+		Some(producer)
+	}
+	fn tech_requirement(&self, id: UnitTypeId) -> Option<UnitTypeId> {
+		self.bot
+			.game_data
+			.units
+			.get(&id)
+			.and_then(|data| data.tech_requirement)
+			.or_else(|| TECH_REQUIREMENTS.get(&id).copied())
+	}
+	fn requires_techlab(&self, id: UnitTypeId) -> bool {
+		match self.bot.game_data.units.get(&id) {
+			Some(data) => data.require_attached,
+			None => matches!(
+				id,
+				UnitTypeId::Marauder
+					| UnitTypeId::Ghost
+					| UnitTypeId::SiegeTank
+					| UnitTypeId::Thor
+					| UnitTypeId::Banshee
+					| UnitTypeId::Raven
+					| UnitTypeId::Battlecruiser
+			),
+		}
+	}
+	fn needs_gas(&self, id: UnitTypeId) -> bool {
+		self.bot.get_unit_cost(id).vespene > 0 && !self.has_gas()
+	}
+	fn needs_gas_upgrade(&self, id: UpgradeId) -> bool {
+		self.bot.get_upgrade_cost(id).vespene > 0 && !self.has_gas()
+	}
+	fn has_gas(&self) -> bool {
+		let bot = self.bot;
+		// Gas building of unknown race can't be planned
+		bot.vespene > 0
+			|| !bot.units.my.gas_buildings.is_empty()
+			|| bot.race_values.gas == UnitTypeId::NotAUnit
+	}
+	/// Returns seconds from now until unit of given type (or its tech alias) is ready,
+	/// or `None` if there's no such unit, structure in progress or order.
+	fn existing_time(&self, id: UnitTypeId) -> Option<f32> {
+		let bot = self.bot;
+		let build_time = |id| {
+			bot.game_data
+				.units
+				.get(&id)
+				.map_or(0.0, |data| data.build_time / FRAMES_PER_SECOND)
+		};
+		let ids = once(id)
+			.chain(TECH_ALIAS.get(&id).into_iter().flatten().copied())
+			.collect::<Vec<_>>();
+
+		let mut best: Option<f32> = None;
+		for u in bot.units.my.all.iter().filter(|u| ids.contains(&u.type_id())) {
+			let time = if u.is_ready() {
+				0.0
+			} else {
+				build_time(u.type_id()) * (1.0 - u.build_progress())
+			};
+			best = Some(best.map_or(time, |b| b.min(time)));
+		}
+		if best.is_none() {
+			let ordered = bot
+				.game_data
+				.units
+				.get(&id)
+				.and_then(|data| data.ability)
+				.and_then(|ability| bot.orders.get(&ability))
+				.is_some_and(|count| *count > 0);
+			if ordered {
+				best = Some(build_time(id));
+			}
+		}
+		best
+	}
+}
+
+fn is_worker(id: UnitTypeId) -> bool {
+	matches!(id, UnitTypeId::SCV | UnitTypeId::Probe | UnitTypeId::Drone)
+}
+fn addon_parent(id: UnitTypeId) -> Option<UnitTypeId> {
+	match id {
+		UnitTypeId::BarracksTechLab | UnitTypeId::BarracksReactor => Some(UnitTypeId::Barracks),
+		UnitTypeId::FactoryTechLab | UnitTypeId::FactoryReactor => Some(UnitTypeId::Factory),
+		UnitTypeId::StarportTechLab | UnitTypeId::StarportReactor => Some(UnitTypeId::Starport),
+		_ => None,
+	}
+}
+fn techlab_of(id: UnitTypeId) -> Option<UnitTypeId> {
+	match id {
+		UnitTypeId::Barracks => Some(UnitTypeId::BarracksTechLab),
+		UnitTypeId::Factory => Some(UnitTypeId::FactoryTechLab),
+		UnitTypeId::Starport => Some(UnitTypeId::StarportTechLab),
+		_ => None,
+	}
+}
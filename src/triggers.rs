@@ -0,0 +1,281 @@
+//! Optional trigger layer over [`Player`](crate::Player) callbacks.
+//!
+//! Triggers are closures registered in [`triggers`](crate::bot::Bot::triggers) field of bot,
+//! which are called by the runner when some [`Event`] happens, [`Alert`] appears or
+//! given amount of game time passes. This allows to compose bot logic from independent modules.
+//!
+//! Triggers are dispatched each step after [`on_event`] and before [`on_step`]
+//! (on the first step they're dispatched after [`on_start`]).
+//! When `rayon` feature is enabled handlers must be `Send + Sync`.
+//!
+//! ```
+//! use rust_sc2::prelude::*;
+//! use rust_sc2::triggers::EventKind;
+//!
+//! let mut bot = rust_sc2::bot::Bot::default();
+//! bot.triggers.on_event(EventKind::UnitCreated, |bot, event| {
+//!     if let Event::UnitCreated(tag) = event {
+//!         bot.chat(&format!("Unit {} created", tag));
+//!     }
+//!     Ok(())
+//! });
+//! bot.triggers.every(5.0, |bot| {
+//!     bot.chat_ally("5 seconds passed");
+//!     Ok(())
+//! });
+//! bot.triggers.at(3.0 * 60.0, |bot| {
+//!     bot.chat_ally("3:00");
+//!     Ok(())
+//! });
+//! ```
+//!
+//! [`on_event`]: crate::Player::on_event
+//! [`on_step`]: crate::Player::on_step
+//! [`on_start`]: crate::Player::on_start
+
+use crate::{
+	bot::{Bot, MaybeSendSync},
+	consts::FRAMES_PER_SECOND,
+	game_state::Alert,
+	Event, SC2Result,
+};
+use std::fmt;
+
+/// Kind of [`Event`] without attached data, used as a key for event triggers.
+#[allow(missing_docs)]
+#[variant_checkers]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+	UnitDestroyed,
+	UnitCreated,
+	ConstructionStarted,
+	ConstructionComplete,
+	RandomRaceDetected,
+	UnitDamaged,
+	UnitTypeChanged,
+	EnemyUnitSeen,
+	UpgradeComplete,
+	UnitIdle,
+	EnemyExpanded,
+}
+impl Event {
+	/// Returns kind of the event.
+	pub fn kind(&self) -> EventKind {
+		match self {
+			Event::UnitDestroyed(..) => EventKind::UnitDestroyed,
+			Event::UnitCreated(..) => EventKind::UnitCreated,
+			Event::ConstructionStarted(..) => EventKind::ConstructionStarted,
+			Event::ConstructionComplete(..) => EventKind::ConstructionComplete,
+			Event::RandomRaceDetected(..) => EventKind::RandomRaceDetected,
+			Event::UnitDamaged(..) => EventKind::UnitDamaged,
+			Event::UnitTypeChanged(..) => EventKind::UnitTypeChanged,
+			Event::EnemyUnitSeen(..) => EventKind::EnemyUnitSeen,
+			Event::UpgradeComplete(..) => EventKind::UpgradeComplete,
+			Event::UnitIdle(..) => EventKind::UnitIdle,
+			Event::EnemyExpanded(..) => EventKind::EnemyExpanded,
+		}
+	}
+}
+
+/// Identifier of registered trigger, can be used to [`remove`](Triggers::remove) it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TriggerId(usize);
+
+#[cfg(feature = "rayon")]
+type EventHandler = Box<dyn FnMut(&mut Bot, Event) -> SC2Result<()> + Send + Sync>;
+#[cfg(not(feature = "rayon"))]
+type EventHandler = Box<dyn FnMut(&mut Bot, Event) -> SC2Result<()>>;
+
+#[cfg(feature = "rayon")]
+type AlertHandler = Box<dyn FnMut(&mut Bot, Alert) -> SC2Result<()> + Send + Sync>;
+#[cfg(not(feature = "rayon"))]
+type AlertHandler = Box<dyn FnMut(&mut Bot, Alert) -> SC2Result<()>>;
+
+#[cfg(feature = "rayon")]
+type TimerHandler = Box<dyn FnMut(&mut Bot) -> SC2Result<()> + Send + Sync>;
+#[cfg(not(feature = "rayon"))]
+type TimerHandler = Box<dyn FnMut(&mut Bot) -> SC2Result<()>>;
+
+enum Trigger {
+	Event(EventKind, EventHandler),
+	Alert(Alert, AlertHandler),
+	Timer {
+		next: u32,
+		interval: Option<u32>,
+		handler: TimerHandler,
+	},
+}
+
+/// Collection of registered triggers.
+/// Can be accessed through [`triggers`](crate::bot::Bot::triggers) field of bot.
+#[derive(Default)]
+pub struct Triggers {
+	triggers: Vec<(TriggerId, Trigger)>,
+	next_id: usize,
+}
+impl Triggers {
+	fn add(&mut self, trigger: Trigger) -> TriggerId {
+		let id = TriggerId(self.next_id);
+		self.next_id += 1;
+		self.triggers.push((id, trigger));
+		id
+	}
+	/// Registers closure called every time event of given kind happens.
+	pub fn on_event<F>(&mut self, kind: EventKind, f: F) -> TriggerId
+	where
+		F: FnMut(&mut Bot, Event) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		self.add(Trigger::Event(kind, Box::new(f)))
+	}
+	/// Registers closure called every time given alert appears in
+	/// [`observation.alerts`](crate::game_state::Observation::alerts).
+	pub fn on_alert<F>(&mut self, alert: Alert, f: F) -> TriggerId
+	where
+		F: FnMut(&mut Bot, Alert) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		self.add(Trigger::Alert(alert, Box::new(f)))
+	}
+	/// Registers closure called every given amount of game seconds
+	/// (i.e. when game time reaches multiples of `seconds`).
+	pub fn every<F>(&mut self, seconds: f32, f: F) -> TriggerId
+	where
+		F: FnMut(&mut Bot) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		let interval = seconds_to_loops(seconds).max(1);
+		self.add(Trigger::Timer {
+			next: interval,
+			interval: Some(interval),
+			handler: Box::new(f),
+		})
+	}
+	/// Registers closure called every given amount of game loops
+	/// (i.e. when game loop reaches multiples of `game_loops`).
+	pub fn every_loops<F>(&mut self, game_loops: u32, f: F) -> TriggerId
+	where
+		F: FnMut(&mut Bot) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		let interval = game_loops.max(1);
+		self.add(Trigger::Timer {
+			next: interval,
+			interval: Some(interval),
+			handler: Box::new(f),
+		})
+	}
+	/// Registers closure called once when game time reaches given amount of seconds.
+	/// If this time already passed, closure will be called on the next dispatch.
+	pub fn at<F>(&mut self, seconds: f32, f: F) -> TriggerId
+	where
+		F: FnMut(&mut Bot) -> SC2Result<()> + MaybeSendSync + 'static,
+	{
+		self.add(Trigger::Timer {
+			next: seconds_to_loops(seconds),
+			interval: None,
+			handler: Box::new(f),
+		})
+	}
+	/// Removes trigger with given id. Returns `true` if trigger was registered.
+	///
+	/// Triggers can't be removed from inside of trigger handlers.
+	pub fn remove(&mut self, id: TriggerId) -> bool {
+		let len = self.triggers.len();
+		self.triggers.retain(|(i, _)| *i != id);
+		self.triggers.len() != len
+	}
+	/// Removes all registered triggers.
+	pub fn clear(&mut self) {
+		self.triggers.clear();
+	}
+	/// Returns number of registered triggers.
+	pub fn len(&self) -> usize {
+		self.triggers.len()
+	}
+	/// Checks if there're no registered triggers.
+	pub fn is_empty(&self) -> bool {
+		self.triggers.is_empty()
+	}
+
+	fn dispatch(
+		&mut self,
+		bot: &mut Bot,
+		events: &[Event],
+		alerts: &[Alert],
+		game_loop: u32,
+	) -> SC2Result<()> {
+		let mut finished = vec![];
+		let mut result = Ok(());
+		for (id, trigger) in &mut self.triggers {
+			result = match trigger {
+				Trigger::Event(kind, handler) => events
+					.iter()
+					.filter(|e| e.kind() == *kind)
+					.try_for_each(|e| handler(bot, *e)),
+				Trigger::Alert(alert, handler) => alerts
+					.iter()
+					.filter(|a| *a == alert)
+					.try_for_each(|a| handler(bot, *a)),
+				Trigger::Timer {
+					next,
+					interval,
+					handler,
+				} => {
+					if game_loop >= *next {
+						// Timer is advanced first, so failed handler isn't called again on every step
+						match interval {
+							Some(interval) => {
+								while *next <= game_loop {
+									*next += *interval;
+								}
+							}
+							None => finished.push(*id),
+						}
+						handler(bot)
+					} else {
+						Ok(())
+					}
+				}
+			};
+			if result.is_err() {
+				break;
+			}
+		}
+		self.triggers.retain(|(id, _)| !finished.contains(id));
+		result
+	}
+}
+impl fmt::Debug for Triggers {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "Triggers({})", self.triggers.len())
+	}
+}
+
+fn seconds_to_loops(seconds: f32) -> u32 {
+	(seconds * FRAMES_PER_SECOND).round() as u32
+}
+
+/// Calls registered triggers, keeping ones added by handlers during dispatch.
+///
+/// Dispatch stops at the first handler error, which is returned.
+pub(crate) fn dispatch_triggers(bot: &mut Bot, events: &[Event]) -> SC2Result<()> {
+	if bot.triggers.is_empty() {
+		return Ok(());
+	}
+	// Handlers may register new triggers while others are taken out of bot
+	let next_id = bot.triggers.next_id;
+	let mut triggers = std::mem::replace(
+		&mut bot.triggers,
+		Triggers {
+			triggers: vec![],
+			next_id,
+		},
+	);
+	let alerts = bot.state.observation.alerts.clone();
+	let game_loop = bot.state.observation.game_loop();
+
+	let result = triggers.dispatch(bot, events, &alerts, game_loop);
+
+	let added = std::mem::take(&mut bot.triggers);
+	triggers.next_id = added.next_id;
+	triggers.triggers.extend(added.triggers);
+	bot.triggers = triggers;
+	result
+}
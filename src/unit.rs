@@ -4,9 +4,9 @@
 use crate::{
 	action::{Commander, Target},
 	bot::{LockBool, LockOwned, LockU32, Locked, Reader, Rl, Rs, Rw},
+	balance::BalanceTable,
 	consts::{
-		RaceValues, ANTI_ARMOR_BUFF, DAMAGE_BONUS_PER_UPGRADE, FRAMES_PER_SECOND, MISSED_WEAPONS,
-		OFF_CREEP_SPEED_UPGRADES, SPEED_BUFFS, SPEED_ON_CREEP, SPEED_UPGRADES, WARPGATE_ABILITIES,
+		RaceValues, ANTI_ARMOR_BUFF, FRAMES_PER_SECOND, MISSED_WEAPONS, UNIT_RADIUS, WARPGATE_ABILITIES,
 	},
 	distance::Distance,
 	game_data::{Attribute, Cost, GameData, TargetType, UnitTypeData, Weapon},
@@ -15,8 +15,9 @@ use crate::{
 	ids::{AbilityId, BuffId, UnitTypeId, UpgradeId},
 	pixel_map::{PixelMap, VisibilityMap},
 	player::Race,
+	stats::{attack_modifiers, creep_speed, is_flying_type, movement_speed, UnitStats, UpgradeLevels},
 	units::Container,
-	utils::CacheMap,
+	utils::{intercept, CacheMap},
 	FromProto,
 };
 use lazy_init::Lazy as LazyInit;
@@ -28,10 +29,13 @@ use sc2_proto::raw::{
 	UnitOrder_oneof_target as ProtoTarget,
 };
 use std::cmp::Ordering;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use std::f32::consts::PI;
-use crate::consts::ON_CREEP_SPEED_UPGRADES;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct WeaponStats {
 	pub damage: u32,
 	pub speed: f32,
@@ -48,12 +52,15 @@ impl WeaponStats {
 pub(crate) struct DataForUnit {
 	pub commander: Rw<Commander>,
 	pub game_data: Rs<GameData>,
+	pub balance: Rs<BalanceTable>,
 	pub techlab_tags: Rw<FxHashSet<u64>>,
 	pub reactor_tags: Rw<FxHashSet<u64>>,
 	pub race_values: Rs<RaceValues>,
 	pub max_cooldowns: Rw<FxHashMap<UnitTypeId, f32>>,
 	pub last_units_hits: Rw<FxHashMap<u64, u32>>,
 	pub last_units_seen: Rw<FxHashMap<u64, u32>>,
+	pub last_units_positions: Rw<FxHashMap<u64, Point2>>,
+	pub last_positions_loop: Rs<LockU32>,
 	pub abilities_units: Rw<FxHashMap<u64, FxHashSet<AbilityId>>>,
 	pub upgrades: Rw<FxHashSet<UpgradeId>>,
 	pub enemy_upgrades: Rw<FxHashSet<UpgradeId>>,
@@ -162,6 +169,11 @@ impl Unit {
 	pub fn tag(&self) -> u64 {
 		self.base.tag
 	}
+	/// Unit was constructed without observation with [`synthetic_unit`](crate::bot::Bot::synthetic_unit).
+	#[inline]
+	pub fn is_synthetic(&self) -> bool {
+		self.base.tag >= SYNTHETIC_TAG
+	}
 	/// The type of unit.
 	#[inline]
 	pub fn type_id(&self) -> UnitTypeId {
@@ -738,67 +750,36 @@ impl Unit {
 	}
 	pub fn on_creep_speed(&self) -> f32 {
 		*self.base.on_creep_speed.get_or_create(|| {
-			let unit_type = self.type_id();
-			let mut base_speed = self.base_real_speed();
-			let upgrades = self.upgrades();
-			// Hydralisks speed upgrade bonus is lower on creep
-			if let Some((upgrade_id, increase)) = ON_CREEP_SPEED_UPGRADES.get(&unit_type) {
-				if upgrades.contains(upgrade_id) {
-					base_speed *= increase;
-				}
-			}
-			if let Some(increase) = SPEED_ON_CREEP.get(&unit_type) {
-				return base_speed * increase;
-			}
-			base_speed
+			creep_speed(
+				&self.data.balance,
+				self.type_id(),
+				self.base_real_speed(),
+				&self.upgrades(),
+				true,
+			)
 		})
 	}
 	pub fn off_creep_speed(&self) -> f32 {
 		*self.base.off_creep_speed.get_or_create(|| {
-			let unit_type = self.type_id();
-
-			// Off creep upgrades
-			let upgrades = self.upgrades();
-			let base_speed = self.base_real_speed();
-			if let Some((upgrade_id, increase)) = OFF_CREEP_SPEED_UPGRADES.get(&unit_type) {
-				if upgrades.contains(upgrade_id) {
-					return base_speed * increase;
-				}
-			}
-			base_speed
+			creep_speed(
+				&self.data.balance,
+				self.type_id(),
+				self.base_real_speed(),
+				&self.upgrades(),
+				false,
+			)
 		})
 	}
 	/// Returns actual speed of the unit calculated including buffs and upgrades.
 	pub fn base_real_speed(&self) -> f32 {
 		*self.base.real_speed.get_or_create(|| {
-			let mut speed = self.speed();
-			let unit_type = self.type_id();
-
-			// ---- Buffs ----
-			// Ultralisk has passive ability "Frenzied" which makes it immune to speed altering buffs
-			if unit_type != UnitTypeId::Ultralisk {
-				for buff in self.buffs() {
-					match buff {
-						BuffId::MedivacSpeedBoost => return speed * 1.7,
-						BuffId::VoidRaySwarmDamageBoost => return speed * 0.75,
-						_ => {
-							if let Some(increase) = SPEED_BUFFS.get(buff) {
-								speed *= increase;
-							}
-						}
-					}
-				}
-			}
-
-			// ---- Upgrades ----
-			let upgrades = self.upgrades();
-			if let Some((upgrade_id, increase)) = SPEED_UPGRADES.get(&unit_type) {
-				if upgrades.contains(upgrade_id) {
-					speed *= increase;
-				}
-			}
-
-			speed
+			movement_speed(
+				&self.data.balance,
+				self.type_id(),
+				self.speed(),
+				self.buffs(),
+				&self.upgrades(),
+			)
 		})
 	}
 	/// Returns actual speed of the unit calculated including buffs and upgrades.
@@ -809,6 +790,30 @@ impl Unit {
 			self.off_creep_speed()
 		}
 	}
+	/// Returns effective stats of the unit calculated including upgrades and buffs.
+	/// Observed maximum health and shield are used when they're known.
+	pub fn stats(&self) -> Option<UnitStats> {
+		let levels = UpgradeLevels {
+			attack: self.attack_upgrade_level(),
+			armor: self.armor_upgrade_level(),
+			shield: self.shield_upgrade_level(),
+		};
+		let mut stats = UnitStats::new(
+			self.type_id(),
+			&self.data.game_data,
+			&self.data.balance,
+			&self.upgrades(),
+			levels,
+			self.buffs(),
+		)?;
+		if self.health_max() > 0 {
+			stats.health_max = self.health_max();
+		}
+		if self.shield_max() > 0 {
+			stats.shield_max = self.shield_max();
+		}
+		Some(stats)
+	}
 	/// Distance unit can travel per one step.
 	pub fn distance_per_step(&self) -> f32 {
 		self.real_speed() / FRAMES_PER_SECOND * self.data.game_step.get_locked() as f32
@@ -817,6 +822,79 @@ impl Unit {
 	pub fn distance_to_weapon_ready(&self) -> f32 {
 		self.real_speed() / FRAMES_PER_SECOND * self.weapon_cooldown()
 	}
+	/// Velocity of unit in game units per second, observed from its movement since previous step.
+	/// Returns `None` if unit wasn't visible on previous step.
+	pub fn velocity(&self) -> Option<Point2> {
+		let last = *self.data.last_units_positions.read_lock().get(&self.tag())?;
+		let frames = self
+			.data
+			.game_loop
+			.get_locked()
+			.saturating_sub(self.data.last_positions_loop.get_locked());
+		if frames == 0 {
+			return None;
+		}
+		Some((self.position() - last) * (FRAMES_PER_SECOND / frames as f32))
+	}
+	/// Expected velocity of unit in game units per second.
+	///
+	/// Unit ordered to go to some point is expected to move towards it with its [`real_speed`],
+	/// otherwise it's expected to keep [`velocity`] observed on the last step.
+	///
+	/// [`real_speed`]: Self::real_speed
+	/// [`velocity`]: Self::velocity
+	pub fn predicted_velocity(&self) -> Point2 {
+		match self.target_pos() {
+			Some(target) if !self.is_closer(f32::EPSILON, target) => {
+				(target - self.position()).normalize() * self.real_speed()
+			}
+			_ => self.velocity().unwrap_or_default(),
+		}
+	}
+	/// Predicts position of unit after given number of game frames.
+	///
+	/// Unit ordered to go to some point is expected to stop there,
+	/// otherwise it's expected to keep [`velocity`](Self::velocity) observed on the last step.
+	pub fn predict_position(&self, frames: u32) -> Point2 {
+		let seconds = frames as f32 / FRAMES_PER_SECOND;
+		let position = self.position();
+		match self.target_pos() {
+			Some(target) => {
+				let distance = self.real_speed() * seconds;
+				if self.is_closer(distance, target) {
+					target
+				} else {
+					position.towards(target, distance)
+				}
+			}
+			None => position + self.velocity().unwrap_or_default() * seconds,
+		}
+	}
+	/// Returns point where unit can catch given target to attack it,
+	/// i.e. predicted position of the target at the moment it gets in range of unit's weapon.
+	///
+	/// Returns `None` if unit can't attack the target or target is too fast to be caught.
+	pub fn intercept_point(&self, target: &Unit) -> Option<Point2> {
+		let range = self.real_range_vs(target);
+		if range < f32::EPSILON {
+			return None;
+		}
+		self.intercept_with_range(target, range + self.radius() + target.radius())
+	}
+	/// Returns point where unit can get to given distance from the target,
+	/// e.g. to catch it and cast a spell on it.
+	///
+	/// Returns `None` if target is too fast to be caught.
+	pub fn intercept_with_range(&self, target: &Unit, range: f32) -> Option<Point2> {
+		intercept(
+			self.position(),
+			self.real_speed(),
+			target.position(),
+			target.predicted_velocity(),
+			range,
+		)
+		.map(|(pos, _)| pos)
+	}
 	/// Attributes of unit, dependent on it's type.
 	pub fn attributes(&self) -> &[Attribute] {
 		self.type_data().map_or(&[], |data| data.attributes.as_slice())
@@ -1074,61 +1152,23 @@ impl Unit {
 				.map_or(0.0, |w| w.range)
 		}
 	}
+	// Additional range of unit's weapons given by upgrades
+	fn range_modifier(&self) -> f32 {
+		attack_modifiers(self.type_id(), self.buffs(), &self.upgrades()).1
+	}
 	/// Returns actual ground range of unit's weapon including upgrades.
 	pub fn real_ground_range(&self) -> f32 {
 		self.weapons()
 			.iter()
 			.find(|w| !w.target.is_air())
-			.map_or(0.0, |w| {
-				let upgrades = self.upgrades();
-				match self.type_id() {
-					UnitTypeId::Hydralisk => {
-						if upgrades.contains(&UpgradeId::EvolveGroovedSpines) {
-							return w.range + 1.0;
-						}
-					}
-					UnitTypeId::Phoenix => {
-						if upgrades.contains(&UpgradeId::PhoenixRangeUpgrade) {
-							return w.range + 2.0;
-						}
-					}
-					UnitTypeId::PlanetaryFortress | UnitTypeId::MissileTurret | UnitTypeId::AutoTurret => {
-						if upgrades.contains(&UpgradeId::HiSecAutoTracking) {
-							return w.range + 1.0;
-						}
-					}
-					_ => {}
-				}
-				w.range
-			})
+			.map_or(0.0, |w| w.range + self.range_modifier())
 	}
 	/// Returns actual air range of unit's weapon including upgrades.
 	pub fn real_air_range(&self) -> f32 {
 		self.weapons()
 			.iter()
 			.find(|w| !w.target.is_ground())
-			.map_or(0.0, |w| {
-				let upgrades = self.upgrades();
-				match self.type_id() {
-					UnitTypeId::Hydralisk => {
-						if upgrades.contains(&UpgradeId::EvolveGroovedSpines) {
-							return w.range + 1.0;
-						}
-					}
-					UnitTypeId::Phoenix => {
-						if upgrades.contains(&UpgradeId::PhoenixRangeUpgrade) {
-							return w.range + 2.0;
-						}
-					}
-					UnitTypeId::PlanetaryFortress | UnitTypeId::MissileTurret | UnitTypeId::AutoTurret => {
-						if upgrades.contains(&UpgradeId::HiSecAutoTracking) {
-							return w.range + 1.0;
-						}
-					}
-					_ => {}
-				}
-				w.range
-			})
+			.map_or(0.0, |w| w.range + self.range_modifier())
 	}
 	/// Returns actual range of unit's weapon vs given target if unit can attack it, otherwise returs `0`.
 	/// Takes upgrades into account.
@@ -1138,48 +1178,10 @@ impl Unit {
 			return 0.0;
 		}
 
-		let extract_range = |w: &Weapon| {
-			let upgrades = self.upgrades();
-			match self.type_id() {
-				UnitTypeId::Hydralisk => {
-					if upgrades.contains(&UpgradeId::EvolveGroovedSpines) {
-						return w.range + 1.0;
-					}
-				}
-				UnitTypeId::Phoenix => {
-					if upgrades.contains(&UpgradeId::PhoenixRangeUpgrade) {
-						return w.range + 2f32;
-					}
-				}
-				UnitTypeId::PlanetaryFortress | UnitTypeId::MissileTurret | UnitTypeId::AutoTurret => {
-					if upgrades.contains(&UpgradeId::HiSecAutoTracking) {
-						return w.range + 1f32;
-					}
-				}
-				UnitTypeId::Colossus => {
-					if upgrades.contains(&UpgradeId::ExtendedThermalLance) {
-						return w.range + 2f32;
-					}
-				}
-				UnitTypeId::Ghost => {
-					// TODO: Is it possible to get energy cost from Ability data?
-					let ability = self
-						.data
-						.game_data
-						.abilities
-						.get(&AbilityId::EffectGhostSnipe)
-						.unwrap();
-					return if self.has_buff(BuffId::ChannelSnipeCombat) {
-						ability.cast_range.unwrap_or_default() + 4f32
-					} else if self.energy() >= 50 {
-						ability.cast_range.unwrap_or_default()
-					} else {
-						w.range
-					};
-				}
-				_ => {}
-			}
-			w.range
+		let range_modifier = self.range_modifier();
+		let extract_range = |w: &Weapon| match self.type_id() {
+			UnitTypeId::Ghost => self.snipe_range().unwrap_or(w.range),
+			_ => w.range + range_modifier,
 		};
 
 		if target.type_id() == UnitTypeId::Colossus {
@@ -1202,6 +1204,26 @@ impl Unit {
 				.map_or(0.0, extract_range)
 		}
 	}
+	// Range of Ghost's snipe if it can be used now
+	pub(crate) fn snipe_range(&self) -> Option<f32> {
+		if self.type_id() != UnitTypeId::Ghost {
+			return None;
+		}
+		// TODO: Is it possible to get energy cost from Ability data?
+		let cast_range = self
+			.data
+			.game_data
+			.abilities
+			.get(&AbilityId::EffectGhostSnipe)
+			.map_or(0.0, |ability| ability.cast_range.unwrap_or_default());
+		if self.has_buff(BuffId::ChannelSnipeCombat) {
+			Some(cast_range + 4.0)
+		} else if self.energy() >= 50 {
+			Some(cast_range)
+		} else {
+			None
+		}
+	}
 	/// Returns ground dps of unit's weapon without considering upgrades.
 	/// Use [`real_ground_weapon`](Self::real_ground_weapon) to get dps including upgrades.
 	pub fn ground_dps(&self) -> f32 {
@@ -1403,54 +1425,9 @@ impl Unit {
 			};
 		}
 
-		let mut speed_modifier = 1.0;
-		let mut range_modifier = 0.0;
-
-		for buff in self.buffs() {
-			match buff {
-				BuffId::Stimpack | BuffId::StimpackMarauder => speed_modifier /= 1.5,
-				BuffId::TimeWarpProduction => speed_modifier *= 2.0,
-				_ => {}
-			}
-		}
-
-		if !upgrades.is_empty() {
-			match self.type_id() {
-				UnitTypeId::Zergling => {
-					if upgrades.contains(&UpgradeId::Zerglingattackspeed) {
-						speed_modifier /= 1.4;
-					}
-				}
-				UnitTypeId::Adept => {
-					if upgrades.contains(&UpgradeId::AdeptPiercingAttack) {
-						speed_modifier /= 1.45;
-					}
-				}
-				UnitTypeId::Hydralisk => {
-					if upgrades.contains(&UpgradeId::EvolveGroovedSpines) {
-						range_modifier += 1.0;
-					}
-				}
-				UnitTypeId::Phoenix => {
-					if upgrades.contains(&UpgradeId::PhoenixRangeUpgrade) {
-						range_modifier += 2.0;
-					}
-				}
-				UnitTypeId::LurkerMPBurrowed => {
-					if upgrades.contains(&UpgradeId::LurkerRange) {
-						range_modifier += 2.0;
-					}
-				}
-				UnitTypeId::PlanetaryFortress | UnitTypeId::MissileTurret | UnitTypeId::AutoTurret => {
-					if upgrades.contains(&UpgradeId::HiSecAutoTracking) {
-						range_modifier += 1.0;
-					}
-				}
-				_ => {}
-			}
-		}
+		let (speed_modifier, range_modifier) = attack_modifiers(self.type_id(), self.buffs(), &upgrades);
 
-		let damage_bonus_per_upgrade = DAMAGE_BONUS_PER_UPGRADE.get(&self.type_id());
+		let damage_bonus_per_upgrade = self.data.balance.damage_bonus_per_upgrade.get(&self.type_id());
 		let extract_weapon_stats = |w: &Weapon| {
 			let damage_bonus_per_upgrade = damage_bonus_per_upgrade.and_then(|bonus| bonus.get(&w.target));
 
@@ -2028,7 +2005,117 @@ impl From<Unit> for Point2 {
 	}
 }
 
+/// Tags of synthetic units start from this value, so they never match tags of real units.
+const SYNTHETIC_TAG: u64 = 1 << 63;
+static NEXT_SYNTHETIC_TAG: AtomicU64 = AtomicU64::new(SYNTHETIC_TAG);
+
 impl Unit {
+	/// Constructs hypothetical unit of given type with full health, using upgrades for its side from `data`.
+	/// Returns `None` if type or its hits are unknown.
+	pub(crate) fn synthetic(
+		data: SharedUnitData,
+		alliance: Alliance,
+		owner: u32,
+		type_id: UnitTypeId,
+		position: Point2,
+	) -> Option<Self> {
+		let type_data = data.game_data.units.get(&type_id)?;
+		let stats = {
+			let upgrades = if alliance.is_mine() {
+				data.upgrades.read_lock()
+			} else {
+				data.enemy_upgrades.read_lock()
+			};
+			let levels = UpgradeLevels::of(type_id, type_data.race, &type_data.attributes, &upgrades);
+			UnitStats::new(
+				type_id,
+				&data.game_data,
+				&data.balance,
+				&upgrades,
+				levels,
+				&Default::default(),
+			)?
+		};
+		// Game data doesn't contain hits, unit with zero health would be considered dead
+		if stats.health_max == 0 {
+			return None;
+		}
+		let radius = UNIT_RADIUS
+			.get(&type_id)
+			.copied()
+			.or_else(|| {
+				let ability = type_data.ability?;
+				data.game_data.abilities.get(&ability)?.footprint_radius
+			})
+			.unwrap_or(0.5);
+		let is_burrowed = matches!(
+			type_id,
+			UnitTypeId::WidowMineBurrowed | UnitTypeId::LurkerMPBurrowed | UnitTypeId::UltraliskBurrowed
+		);
+
+		Some(Self {
+			data,
+			base: Rs::new(UnitBase {
+				display_type: Rl::new(DisplayType::Visible),
+				alliance,
+				tag: NEXT_SYNTHETIC_TAG.fetch_add(1, AtomicOrdering::Relaxed),
+				type_id: Rl::new(type_id),
+				owner,
+				position,
+				position3d: position.to3(0.0),
+				facing: 0.0,
+				radius,
+				build_progress: 1.0,
+				is_cloaked: LockBool::new(is_burrowed),
+				is_revealed: LockBool::new(false),
+				buffs: Default::default(),
+				detect_range: match type_id {
+					UnitTypeId::Observer => 11.0,
+					UnitTypeId::ObserverSiegeMode => 13.75,
+					_ => 0.0,
+				},
+				radar_range: 0.0,
+				is_selected: false,
+				is_on_screen: false,
+				is_blip: false,
+				is_powered: true,
+				is_active: false,
+				attack_upgrade_level: stats.levels.attack,
+				armor_upgrade_level: stats.levels.armor,
+				shield_upgrade_level: stats.levels.shield,
+				health: stats.health_max,
+				health_max: stats.health_max,
+				shield: stats.shield_max,
+				shield_max: stats.shield_max,
+				energy: 0,
+				energy_max: 0,
+				mineral_contents: 0,
+				vespene_contents: 0,
+				is_flying: is_flying_type(type_id),
+				is_burrowed: LockBool::new(is_burrowed),
+				is_hallucination: LockBool::new(false),
+				orders: vec![],
+				addon_tag: None,
+				passengers: vec![],
+				cargo_space_taken: 0,
+				cargo_space_max: stats.cargo_capacity,
+				assigned_harvesters: 0,
+				ideal_harvesters: 0,
+				weapon_cooldown: 0.0,
+				engaged_target_tag: None,
+				buff_duration_remain: 0,
+				buff_duration_max: 0,
+				rally_targets: vec![],
+
+				// cache
+				real_speed: Default::default(),
+				on_creep_speed: Default::default(),
+				off_creep_speed: Default::default(),
+				real_weapon_vs: Default::default(),
+			}),
+		})
+	}
+
 	pub(crate) fn from_proto(data: SharedUnitData, visibility: &VisibilityMap, u: &ProtoUnit) -> Self {
 		let pos = u.get_pos();
 		let position = Point2::from_proto(pos);
@@ -2178,6 +2265,7 @@ impl Unit {
 /// Can be accessed through [`display_type`](Unit::display_type) field.
 #[variant_checkers]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DisplayType {
 	/// Fully visible.
 	Visible,
@@ -2202,6 +2290,7 @@ impl FromProto<ProtoDisplayType> for DisplayType {
 
 /// Order given to unit. All current orders of unit stored in [`orders`](Unit::orders) field.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UnitOrder {
 	/// Ability unit is using.
 	pub ability: AbilityId,
@@ -2213,6 +2302,7 @@ pub struct UnitOrder {
 
 /// Unit inside transport or bunker. All passengers stored in [`passengers`](Unit::passengers) field.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PassengerUnit {
 	pub tag: u64,
 	pub health: f32,
@@ -2227,6 +2317,7 @@ pub struct PassengerUnit {
 /// Rally point of production building.
 /// All rally points stored in [`rally_targets`](Unit::rally_targets) field.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RallyTarget {
 	/// Rally point. Position building rallied on.
 	pub point: Point2,
@@ -1,12 +1,19 @@
 //! Data structures for storing units, fast filtering and finding ones that needed.
 #![warn(missing_docs)]
 
-use crate::{geometry::Point2, ids::UnitTypeId, unit::Unit};
+use crate::{
+	army::{self, ArmyCluster},
+	bot::Rs,
+	geometry::Point2,
+	ids::UnitTypeId,
+	unit::Unit,
+};
 use indexmap::{
 	map::{Iter, IterMut, Keys, Values, ValuesMut},
 	IndexMap, IndexSet,
 };
 use iter::IntoUnits;
+use spatial::SpatialIndex;
 use rustc_hash::FxHasher;
 use std::{
 	hash::BuildHasherDefault,
@@ -17,9 +24,13 @@ use std::{
 pub mod iter;
 #[cfg(feature = "rayon")]
 pub mod rayon;
+pub mod spatial;
 
 type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
 
+/// Minimal size of collection to use spatial index for queries.
+const MIN_INDEXED: usize = 16;
+
 /// Structured collection of all possible units. Can be accessed through [`units`] bot's field.
 ///
 /// [`units`]: crate::bot::Bot::units
@@ -48,6 +59,23 @@ pub struct AllUnits {
 	pub inhibitor_zones: Units,
 }
 impl AllUnits {
+	// Builds spatial index over all units and shares it between all collections of current step
+	pub(crate) fn build_index(&mut self) {
+		let index = Rs::new(SpatialIndex::new(self.all.iter()));
+		for units in [
+			&mut self.all,
+			&mut self.mineral_fields,
+			&mut self.vespene_geysers,
+			&mut self.resources,
+			&mut self.destructables,
+			&mut self.watchtowers,
+			&mut self.inhibitor_zones,
+		] {
+			units.set_index(&index);
+		}
+		self.my.set_index(&index);
+		self.enemy.set_index(&index);
+	}
 	pub(crate) fn clear(&mut self) {
 		self.all.clear();
 		self.my.clear();
@@ -82,6 +110,20 @@ pub struct PlayerUnits {
 	pub placeholders: Units,
 }
 impl PlayerUnits {
+	fn set_index(&mut self, index: &Rs<SpatialIndex>) {
+		for units in [
+			&mut self.all,
+			&mut self.units,
+			&mut self.structures,
+			&mut self.townhalls,
+			&mut self.workers,
+			&mut self.gas_buildings,
+			&mut self.larvas,
+			&mut self.placeholders,
+		] {
+			units.set_index(index);
+		}
+	}
 	pub(crate) fn clear(&mut self) {
 		self.all.clear();
 		self.units.clear();
@@ -98,23 +140,23 @@ impl PlayerUnits {
 ///
 // [`Unit`]: crate::unit::Unit
 #[derive(Default, Clone)]
-pub struct Units(FxIndexMap<u64, Unit>);
+pub struct Units(FxIndexMap<u64, Unit>, Option<Rs<SpatialIndex>>);
 impl Units {
 	// HashMap methods
 
 	/// Constrructs new empty units collection.
 	#[inline]
 	pub fn new() -> Self {
-		Self(Default::default())
+		Self(Default::default(), None)
 	}
 
 	/// Constructs new units collection with given capacity.
 	#[inline]
 	pub fn with_capacity(n: usize) -> Self {
-		Self(IndexMap::with_capacity_and_hasher(
-			n,
-			BuildHasherDefault::<FxHasher>::default(),
-		))
+		Self(
+			IndexMap::with_capacity_and_hasher(n, BuildHasherDefault::<FxHasher>::default()),
+			None,
+		)
 	}
 
 	/// Returns current capacity of the collection.
@@ -147,6 +189,7 @@ impl Units {
 	/// replaces it and returns previous unit.
 	#[inline]
 	pub fn push(&mut self, u: Unit) -> Option<Unit> {
+		self.1 = None;
 		self.0.insert(u.tag(), u)
 	}
 
@@ -175,6 +218,7 @@ impl Units {
 	/// Returns mutable iterator over the units of the collection.
 	#[inline]
 	pub fn iter_mut(&mut self) -> ValuesMut<'_, u64, Unit> {
+		self.1 = None;
 		self.0.values_mut()
 	}
 
@@ -187,6 +231,7 @@ impl Units {
 	/// Returns mutable iterator over (tag, unit) pairs of the collection.
 	#[inline]
 	pub fn pairs_mut(&mut self) -> IterMut<'_, u64, Unit> {
+		self.1 = None;
 		self.0.iter_mut()
 	}
 
@@ -229,6 +274,7 @@ impl Units {
 	/// Returns a mutable reference to unit with given tag or `None` if there's no unit with such tag.
 	#[inline]
 	pub fn get_mut(&mut self, tag: u64) -> Option<&mut Unit> {
+		self.1 = None;
 		self.0.get_mut(&tag)
 	}
 
@@ -382,7 +428,15 @@ impl Units {
 	///
 	/// [`in_range_of`]: UnitsIterator::in_range_of
 	pub fn in_range_of(&self, unit: &Unit, gap: f32) -> Self {
-		self.filter(|u| unit.in_range(u, gap))
+		match self.index() {
+			Some(index) => self.filter_within(
+				index,
+				unit.position(),
+				spatial::reach(unit) + gap + index.max_radius(),
+				|u| unit.in_range(u, gap),
+			),
+			None => self.filter(|u| unit.in_range(u, gap)),
+		}
 	}
 	/// Leaves only units that are close enough to attack given unit and makes new collection of them.
 	///
@@ -392,7 +446,15 @@ impl Units {
 	///
 	/// [`in_range`]: UnitsIterator::in_range
 	pub fn in_range(&self, unit: &Unit, gap: f32) -> Self {
-		self.filter(|u| u.in_range(unit, gap))
+		match self.index() {
+			Some(index) => self.filter_within(
+				index,
+				unit.position(),
+				unit.radius() + gap + index.max_reach(),
+				|u| u.in_range(unit, gap),
+			),
+			None => self.filter(|u| u.in_range(unit, gap)),
+		}
 	}
 	/// Leaves only units in attack range of given unit and makes new collection of them.
 	/// Unlike [`in_range_of`] this takes range upgrades into account.
@@ -404,7 +466,15 @@ impl Units {
 	/// [`in_range_of`]: Self::in_range_of
 	/// [`in_real_range_of`]: UnitsIterator::in_real_range_of
 	pub fn in_real_range_of(&self, unit: &Unit, gap: f32) -> Self {
-		self.filter(|u| unit.in_real_range(u, gap))
+		match self.index() {
+			Some(index) => self.filter_within(
+				index,
+				unit.position(),
+				spatial::reach(unit) + gap + index.max_radius(),
+				|u| unit.in_real_range(u, gap),
+			),
+			None => self.filter(|u| unit.in_real_range(u, gap)),
+		}
 	}
 	/// Leaves only units that are close enough to attack given unit and makes new collection of them.
 	/// Unlike [`in_range`] this takes range upgrades into account.
@@ -416,7 +486,15 @@ impl Units {
 	/// [`in_range`]: Self::in_range
 	/// [`in_real_range`]: UnitsIterator::in_real_range
 	pub fn in_real_range(&self, unit: &Unit, gap: f32) -> Self {
-		self.filter(|u| u.in_real_range(unit, gap))
+		match self.index() {
+			Some(index) => self.filter_within(
+				index,
+				unit.position(),
+				unit.radius() + gap + index.max_reach(),
+				|u| u.in_real_range(unit, gap),
+			),
+			None => self.filter(|u| u.in_real_range(unit, gap)),
+		}
 	}
 	/// Leaves only units visible on current step and makes new collection of them.
 	///
@@ -429,6 +507,58 @@ impl Units {
 		self.filter(|u| u.is_visible())
 	}
 
+	/// Builds spatial index over units of the collection to speed up range and closest queries.
+	/// Collections made by filtering this one share the same index.
+	/// Index is dropped when units are added to the collection or mutably accessed.
+	///
+	/// See [`spatial`] module for details.
+	pub fn build_index(&mut self) {
+		self.1 = Some(Rs::new(SpatialIndex::new(self.iter())));
+	}
+	/// Checks if the collection has spatial index.
+	pub fn has_index(&self) -> bool {
+		self.1.is_some()
+	}
+	/// Groups units of the collection into clusters with DBSCAN.
+	///
+	/// Units are neighbors if they're not further than `eps` from each other,
+	/// cluster starts from unit which has at least `min_points` neighbors (including itself).
+	/// Units which don't belong to any cluster are ignored.
+	///
+	/// See [`army`](crate::army) module for details.
+	pub fn clusters(&self, eps: f32, min_points: usize) -> Vec<ArmyCluster> {
+		army::clusters(self, eps, min_points)
+	}
+	pub(crate) fn set_index(&mut self, index: &Rs<SpatialIndex>) {
+		self.1 = Some(Rs::clone(index));
+	}
+	// Spatial index if collection is big enough to benefit from it
+	fn index(&self) -> Option<&SpatialIndex> {
+		self.1.as_deref().filter(|_| self.len() >= MIN_INDEXED)
+	}
+	// Same as `filter`, but checks only units found with index not further than `distance` from `center`
+	fn filter_within<F>(&self, index: &SpatialIndex, center: Point2, distance: f32, f: F) -> Self
+	where
+		F: Fn(&&Unit) -> bool,
+	{
+		let mut found = index
+			.within(center, distance)
+			.filter_map(|tag| self.0.get_full(&tag))
+			.filter(|(_, _, u)| f(u))
+			.map(|(i, ..)| i)
+			.collect::<Vec<_>>();
+		// Keep original order of units
+		found.sort_unstable();
+		Self(
+			found
+				.into_iter()
+				.filter_map(|i| self.0.get_index(i))
+				.map(|(tag, u)| (*tag, u.clone()))
+				.collect(),
+			self.1.clone(),
+		)
+	}
+
 	/// Sorts the collection by given function.
 	pub fn sort<T, F>(&mut self, f: F)
 	where
@@ -453,13 +583,13 @@ impl Units {
 impl FromIterator<Unit> for Units {
 	#[inline]
 	fn from_iter<I: IntoIterator<Item = Unit>>(iter: I) -> Self {
-		Self(iter.into_iter().map(|u| (u.tag(), u)).collect())
+		Self(iter.into_iter().map(|u| (u.tag(), u)).collect(), None)
 	}
 }
 impl FromIterator<(u64, Unit)> for Units {
 	#[inline]
 	fn from_iter<I: IntoIterator<Item = (u64, Unit)>>(iter: I) -> Self {
-		Self(iter.into_iter().collect())
+		Self(iter.into_iter().collect(), None)
 	}
 }
 
@@ -487,6 +617,7 @@ impl<'a> IntoIterator for &'a mut Units {
 
 	#[inline]
 	fn into_iter(self) -> Self::IntoIter {
+		self.1 = None;
 		self.0.values_mut()
 	}
 }
@@ -494,12 +625,14 @@ impl<'a> IntoIterator for &'a mut Units {
 impl Extend<Unit> for Units {
 	#[inline]
 	fn extend<T: IntoIterator<Item = Unit>>(&mut self, iter: T) {
+		self.1 = None;
 		self.0.extend(iter.into_iter().map(|u| (u.tag(), u)));
 	}
 }
 impl Extend<(u64, Unit)> for Units {
 	#[inline]
 	fn extend<T: IntoIterator<Item = (u64, Unit)>>(&mut self, iter: T) {
+		self.1 = None;
 		self.0.extend(iter);
 	}
 }
@@ -515,6 +648,7 @@ impl Index<u64> for Units {
 impl IndexMut<u64> for Units {
 	#[inline]
 	fn index_mut(&mut self, tag: u64) -> &mut Self::Output {
+		self.1 = None;
 		&mut self.0[&tag]
 	}
 }
@@ -530,6 +664,7 @@ impl Index<usize> for Units {
 impl IndexMut<usize> for Units {
 	#[inline]
 	fn index_mut(&mut self, i: usize) -> &mut Self::Output {
+		self.1 = None;
 		&mut self.0[i]
 	}
 }
@@ -578,7 +713,10 @@ impl Units {
 	where
 		F: Fn(&&Unit) -> bool,
 	{
-		Self(self.iter().filter(f).map(|u| (u.tag(), u.clone())).collect())
+		Self(
+			self.iter().filter(f).map(|u| (u.tag(), u.clone())).collect(),
+			self.1.clone(),
+		)
 	}
 	/// Leaves only units of given types and makes a new collection of them.
 	///
@@ -610,7 +748,10 @@ impl Units {
 	///
 	/// [`closer`]: UnitsIterator::closer
 	pub fn closer<P: Into<Point2> + Copy>(&self, distance: f32, target: P) -> Self {
-		self.filter(|u| u.is_closer(distance, target))
+		match self.index() {
+			Some(index) => self.filter_within(index, target.into(), distance, |u| u.is_closer(distance, target)),
+			None => self.filter(|u| u.is_closer(distance, target)),
+		}
 	}
 	/// Leaves only units further than given distance to target and makes new collection of them.
 	///
@@ -625,7 +766,12 @@ impl Units {
 
 	/// Returns closest from the collection unit to given target.
 	pub fn closest<P: Into<Point2> + Copy>(&self, target: P) -> Option<&Unit> {
-		self.min(|u| u.distance_squared(target))
+		match self.index() {
+			Some(index) => index
+				.nearest(target.into(), |tag| self.0.get_index_of(&tag))
+				.map(|(i, _)| &self.0[i]),
+			None => self.min(|u| u.distance_squared(target)),
+		}
 	}
 	/// Returns furthest from the collection unit to given target.
 	pub fn furthest<P: Into<Point2> + Copy>(&self, target: P) -> Option<&Unit> {
@@ -634,8 +780,7 @@ impl Units {
 
 	/// Returns distance from closest unit in the collection to given target.
 	pub fn closest_distance<P: Into<Point2> + Copy>(&self, target: P) -> Option<f32> {
-		self.min_value(|u| u.distance_squared(target))
-			.map(|dist| dist.sqrt())
+		self.closest_distance_squared(target).map(|dist| dist.sqrt())
 	}
 	/// Returns distance from furthest unit in the collection to given target.
 	pub fn furthest_distance<P: Into<Point2> + Copy>(&self, target: P) -> Option<f32> {
@@ -645,7 +790,12 @@ impl Units {
 
 	/// Returns squared distance from closest unit in the collection to given target.
 	pub fn closest_distance_squared<P: Into<Point2> + Copy>(&self, target: P) -> Option<f32> {
-		self.min_value(|u| u.distance_squared(target))
+		match self.index() {
+			Some(index) => index
+				.nearest(target.into(), |tag| self.0.get_index_of(&tag))
+				.map(|(_, dist)| dist),
+			None => self.min_value(|u| u.distance_squared(target)),
+		}
 	}
 	/// Returns squared distance from furthest unit in the collection to given target.
 	pub fn furthest_distance_squared<P: Into<Point2> + Copy>(&self, target: P) -> Option<f32> {
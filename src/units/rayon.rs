@@ -25,6 +25,7 @@ impl Units {
 	/// Returns mutable parallel iterator over the units of the collection.
 	#[inline]
 	pub fn par_iter_mut(&mut self) -> ParValuesMut<u64, Unit> {
+		self.1 = None;
 		self.0.par_values_mut()
 	}
 
@@ -37,6 +38,7 @@ impl Units {
 	/// Returns mutable parallel iterator over (tag, unit) pairs of the collection.
 	#[inline]
 	pub fn par_pairs_mut(&mut self) -> ParIterMut<u64, Unit> {
+		self.1 = None;
 		self.0.par_iter_mut()
 	}
 
@@ -57,7 +59,10 @@ impl Units {
 	where
 		F: Fn(&&Unit) -> bool + Sync + Send,
 	{
-		Self(self.par_iter().filter(f).map(|u| (u.tag(), u.clone())).collect())
+		Self(
+			self.par_iter().filter(f).map(|u| (u.tag(), u.clone())).collect(),
+			self.1.clone(),
+		)
 	}
 
 	/// Leaves only units of given types and makes a new collection of them.
@@ -220,6 +225,7 @@ impl<'a> IntoParallelIterator for &'a mut Units {
 
 	#[inline]
 	fn into_par_iter(self) -> Self::Iter {
+		self.1 = None;
 		self.0.par_values_mut()
 	}
 }
@@ -227,12 +233,14 @@ impl<'a> IntoParallelIterator for &'a mut Units {
 impl ParallelExtend<Unit> for Units {
 	#[inline]
 	fn par_extend<T: IntoParallelIterator<Item = Unit>>(&mut self, par_iter: T) {
+		self.1 = None;
 		self.0.par_extend(par_iter.into_par_iter().map(|u| (u.tag(), u)));
 	}
 }
 impl ParallelExtend<(u64, Unit)> for Units {
 	#[inline]
 	fn par_extend<T: IntoParallelIterator<Item = (u64, Unit)>>(&mut self, par_iter: T) {
+		self.1 = None;
 		self.0.par_extend(par_iter);
 	}
 }
@@ -240,13 +248,13 @@ impl ParallelExtend<(u64, Unit)> for Units {
 impl FromParallelIterator<Unit> for Units {
 	#[inline]
 	fn from_par_iter<I: IntoParallelIterator<Item = Unit>>(par_iter: I) -> Self {
-		Self(par_iter.into_par_iter().map(|u| (u.tag(), u)).collect())
+		Self(par_iter.into_par_iter().map(|u| (u.tag(), u)).collect(), None)
 	}
 }
 impl FromParallelIterator<(u64, Unit)> for Units {
 	#[inline]
 	fn from_par_iter<I: IntoParallelIterator<Item = (u64, Unit)>>(par_iter: I) -> Self {
-		Self(par_iter.into_par_iter().collect())
+		Self(par_iter.into_par_iter().collect(), None)
 	}
 }
 
@@ -0,0 +1,224 @@
+//! Uniform grid over unit positions used to speed up range and closest queries.
+//!
+//! Index is built over [`AllUnits`](super::AllUnits) once per step when
+//! [`use_spatial_index`](crate::bot::Bot::use_spatial_index) is enabled,
+//! or over any collection with [`Units::build_index`](super::Units::build_index).
+//! Collections made by filtering indexed collection share the same index,
+//! so following methods of [`Units`](super::Units) don't scan all units anymore:
+//! - [`closer`](super::Units::closer)
+//! - [`in_range_of`](super::Units::in_range_of), [`in_range`](super::Units::in_range)
+//! - [`in_real_range_of`](super::Units::in_real_range_of), [`in_real_range`](super::Units::in_real_range)
+//! - [`closest`](super::Units::closest), [`closest_distance`](super::Units::closest_distance)
+//!   and [`closest_distance_squared`](super::Units::closest_distance_squared)
+//!
+//! Results are the same as without index, including order of units and choice between equally close units.
+//! Lazy iterator adaptors (e.g. [`DistanceIterator`](crate::distance::DistanceIterator)) still check all items.
+
+use crate::{distance::Distance, geometry::Point2, unit::Unit};
+
+/// Size of grid cell in game units.
+const CELL_SIZE: f32 = 4.0;
+/// Maximum bonus to weapon range given by upgrades.
+const RANGE_UPGRADE_BONUS: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+	tag: u64,
+	pos: Point2,
+}
+
+/// Grid of unit positions. See [module-level documentation](self) for more info.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+	origin: Point2,
+	width: usize,
+	height: usize,
+	// Entries sorted by cell, `starts[i]..starts[i + 1]` are entries of `i`-th cell
+	starts: Vec<usize>,
+	entries: Vec<Entry>,
+	max_radius: f32,
+	max_reach: f32,
+}
+impl SpatialIndex {
+	/// Builds index over given units.
+	pub fn new<'a, I: IntoIterator<Item = &'a Unit>>(units: I) -> Self {
+		Self::build(
+			units
+				.into_iter()
+				.map(|u| (u.tag(), u.position(), u.radius(), reach(u))),
+		)
+	}
+	/// Builds index over arbitrary points given as `(tag, position, radius)`.
+	pub fn from_points<I: IntoIterator<Item = (u64, Point2, f32)>>(points: I) -> Self {
+		Self::build(
+			points
+				.into_iter()
+				.map(|(tag, pos, radius)| (tag, pos, radius, radius)),
+		)
+	}
+
+	fn build<I: Iterator<Item = (u64, Point2, f32, f32)>>(points: I) -> Self {
+		let mut index = Self::default();
+		let points = points.collect::<Vec<_>>();
+		if points.is_empty() {
+			return index;
+		}
+
+		let (mut min, mut max) = (points[0].1, points[0].1);
+		for &(_, pos, radius, reach) in &points {
+			min = Point2::new(min.x.min(pos.x), min.y.min(pos.y));
+			max = Point2::new(max.x.max(pos.x), max.y.max(pos.y));
+			index.max_radius = index.max_radius.max(radius);
+			index.max_reach = index.max_reach.max(reach);
+		}
+		index.origin = min;
+		index.width = ((max.x - min.x) / CELL_SIZE) as usize + 1;
+		index.height = ((max.y - min.y) / CELL_SIZE) as usize + 1;
+
+		// Counting sort of entries by cells
+		let cells = points.iter().map(|p| index.cell_of(p.1)).collect::<Vec<_>>();
+		let mut starts = vec![0; index.width * index.height + 1];
+		for &cell in &cells {
+			starts[cell + 1] += 1;
+		}
+		for i in 1..starts.len() {
+			starts[i] += starts[i - 1];
+		}
+		let mut next = starts.clone();
+		let mut entries = vec![Entry { tag: 0, pos: min }; points.len()];
+		for (&(tag, pos, ..), &cell) in points.iter().zip(&cells) {
+			entries[next[cell]] = Entry { tag, pos };
+			next[cell] += 1;
+		}
+		index.starts = starts;
+		index.entries = entries;
+		index
+	}
+
+	/// Returns number of indexed units.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+	/// Checks if there're no units in the index.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+	/// Maximum radius of indexed units.
+	pub fn max_radius(&self) -> f32 {
+		self.max_radius
+	}
+	/// Maximum distance at which indexed units can attack,
+	/// i.e. radius plus maximum weapon range including upgrades (or snipe range for ghosts).
+	pub fn max_reach(&self) -> f32 {
+		self.max_reach
+	}
+
+	fn cell_of(&self, pos: Point2) -> usize {
+		let (x, y) = self.coords(pos);
+		x as usize * self.height + y as usize
+	}
+	// Coordinates of cell, which contains given position (may be outside of the grid)
+	fn coords(&self, pos: Point2) -> (isize, isize) {
+		(
+			((pos.x - self.origin.x) / CELL_SIZE).floor() as isize,
+			((pos.y - self.origin.y) / CELL_SIZE).floor() as isize,
+		)
+	}
+	fn cell(&self, x: isize, y: isize) -> &[Entry] {
+		if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+			return &[];
+		}
+		let i = x as usize * self.height + y as usize;
+		&self.entries[self.starts[i]..self.starts[i + 1]]
+	}
+
+	/// Returns tags of units, which are not further than `distance` from `center`.
+	pub fn within(&self, center: Point2, distance: f32) -> impl Iterator<Item = u64> + '_ {
+		let (x0, y0) = self.coords(center.offset(-distance, -distance));
+		let (x1, y1) = self.coords(center.offset(distance, distance));
+		let (x0, y0) = (x0.max(0), y0.max(0));
+		let (x1, y1) = (x1.min(self.width as isize - 1), y1.min(self.height as isize - 1));
+		let distance_squared = distance * distance;
+		(x0..=x1)
+			.flat_map(move |x| (y0..=y1).flat_map(move |y| self.cell(x, y)))
+			.filter(move |e| e.pos.distance_squared(center) <= distance_squared)
+			.map(|e| e.tag)
+	}
+
+	/// Returns closest to `target` unit as `(rank, squared distance)`.
+	///
+	/// Given function returns rank of unit with given tag or `None` to skip it.
+	/// Unit with lowest rank is chosen between equally close units.
+	pub fn nearest<F>(&self, target: Point2, rank: F) -> Option<(usize, f32)>
+	where
+		F: Fn(u64) -> Option<usize>,
+	{
+		if self.is_empty() {
+			return None;
+		}
+		let (cx, cy) = self.coords(target);
+		let max_ring = [
+			cx,
+			cy,
+			self.width as isize - 1 - cx,
+			self.height as isize - 1 - cy,
+		]
+		.iter()
+		.map(|d| d.unsigned_abs())
+		.max()
+		.unwrap_or(0) as isize;
+		// Rings closer to target than the grid are empty
+		let min_ring = [
+			-cx,
+			-cy,
+			cx - self.width as isize + 1,
+			cy - self.height as isize + 1,
+		]
+		.iter()
+		.fold(0, |a, &b| a.max(b));
+
+		let mut best: Option<(usize, f32)> = None;
+		let mut check = |cell: &[Entry]| {
+			for e in cell {
+				if let Some(r) = rank(e.tag) {
+					let d = e.pos.distance_squared(target);
+					if best.is_none_or(|(best_r, best_d)| d < best_d || (d == best_d && r < best_r)) {
+						best = Some((r, d));
+					}
+				}
+			}
+			best
+		};
+		let (w, h) = (self.width as isize, self.height as isize);
+		let mut found = None;
+		for ring in min_ring..=max_ring {
+			if ring == 0 {
+				found = check(self.cell(cx, cy));
+			} else {
+				for x in (cx - ring).max(0)..=(cx + ring).min(w - 1) {
+					check(self.cell(x, cy - ring));
+					found = check(self.cell(x, cy + ring));
+				}
+				for y in (cy - ring + 1).max(0)..(cy + ring).min(h) {
+					check(self.cell(cx - ring, y));
+					found = check(self.cell(cx + ring, y));
+				}
+			}
+			// Units in next rings are at least `ring * CELL_SIZE` away
+			if let Some((_, d)) = found {
+				let bound = ring as f32 * CELL_SIZE;
+				if d < bound * bound {
+					break;
+				}
+			}
+		}
+		best
+	}
+}
+
+// Distance from center of the unit at which it can attack,
+// must be not less than any range returned by `real_range_vs`
+pub(crate) fn reach(u: &Unit) -> f32 {
+	let range = u.weapons().iter().map(|w| w.range).fold(0.0, f32::max) + RANGE_UPGRADE_BONUS;
+	u.radius() + u.snipe_range().map_or(range, |snipe| range.max(snipe))
+}
@@ -1,8 +1,13 @@
 //! Different utilites useful (or useless) in bot development.
 
+use crate::geometry::Point2;
 use indexmap::IndexSet;
+use num_traits::FromPrimitive;
 use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
-use std::hash::{BuildHasherDefault, Hash};
+use std::{
+	fmt::Debug,
+	hash::{BuildHasherDefault, Hash},
+};
 
 type FxIndexSet<T> = IndexSet<T, BuildHasherDefault<FxHasher>>;
 
@@ -90,6 +95,48 @@ where
 	}
 }
 
+/// Finds where chaser can catch target moving with constant velocity.
+///
+/// Takes:
+/// - `chaser`: position of the chaser.
+/// - `speed`: speed of the chaser.
+/// - `target`: position of the target.
+/// - `velocity`: velocity of the target (in the same time units as `speed`).
+/// - `range`: distance between positions at which target is caught (e.g. weapon range plus radii).
+///
+/// Returns: (Position of the target at the moment it's caught, Time until it's caught)
+/// or `None` if target is too fast to be caught.
+pub fn intercept(
+	chaser: Point2,
+	speed: f32,
+	target: Point2,
+	velocity: Point2,
+	range: f32,
+) -> Option<(Point2, f32)> {
+	let offset = target - chaser;
+	let c = offset.len_squared() - range * range;
+	if c <= 0.0 {
+		return Some((target, 0.0));
+	}
+	// Solving |offset + velocity * t| = speed * t + range for smallest positive t
+	let a = velocity.len_squared() - speed * speed;
+	let b = 2.0 * (offset.dot(velocity) - speed * range);
+	let time = if a.abs() < f32::EPSILON {
+		(b < 0.0).then(|| -c / b)?
+	} else {
+		let discriminant = b * b - 4.0 * a * c;
+		if discriminant < 0.0 {
+			return None;
+		}
+		let sqrt = discriminant.sqrt();
+		[(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+			.into_iter()
+			.filter(|t| *t >= 0.0)
+			.fold(None, |min: Option<f32>, t| Some(min.map_or(t, |min| min.min(t))))?
+	};
+	Some((target + velocity * time, time))
+}
+
 #[cfg(feature = "parking_lot")]
 use parking_lot::{RwLock, RwLockReadGuard};
 #[cfg(not(feature = "parking_lot"))]
@@ -135,3 +182,25 @@ where
 		read(&self.0).get(k).copied()
 	}
 }
+
+/// Maps names of ids (e.g. [`UnitTypeId`](crate::ids::UnitTypeId)) in lower case to ids.
+pub(crate) fn ids_by_name<T: FromPrimitive + Debug>() -> FxHashMap<String, T> {
+	(0..8192)
+		.filter_map(T::from_u32)
+		.map(|id| (format!("{:?}", id).to_lowercase(), id))
+		.collect()
+}
+
+/// Makes string safe to use as file name. Letters, digits, `-` and `_` are kept,
+/// other bytes are written as `%XX`, so different strings give different names.
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+	let mut result = String::with_capacity(name.len());
+	for b in name.bytes() {
+		if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+			result.push(b as char);
+		} else {
+			result.push_str(&format!("%{:02X}", b));
+		}
+	}
+	result
+}
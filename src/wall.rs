@@ -0,0 +1,328 @@
+//! Wall-off solver, which finds placement of buildings closing arbitrary choke.
+//!
+//! Walls are searched with [`find_wall`](crate::bot::Bot::find_wall) method of bot.
+//! Buildings are placed on the side of the choke where given `inside` point is
+//! as a chain from one side of the choke to another, then result is verified
+//! with local [`placement`](crate::bot::Bot::placement) grid and ground pathing connectivity.
+//!
+//! Creep isn't checked, because zerg walls are usually planned before creep reaches the choke.
+
+use crate::{bot::Bot, distance::Distance, geometry::Point2, ids::UnitTypeId, placement, player::Race};
+use sc2pathfinding::Choke;
+use std::{cmp::Ordering, collections::VecDeque};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Extra space around choke checked by solver.
+const MARGIN: f32 = 10.0;
+/// Maximum number of checked combinations of buildings.
+const MAX_CHECKS: usize = 20_000;
+/// Maximum number of positions checked for each building.
+const MAX_CANDIDATES: usize = 60;
+/// Radius of psionic matrix of pylon.
+const PYLON_POWER_RADIUS: f32 = 6.5;
+
+/// Placement of buildings found by [`find_wall`](crate::bot::Bot::find_wall).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Wall {
+	/// Buildings with their positions.
+	pub buildings: Vec<(UnitTypeId, Point2)>,
+	/// Size of the gap left in the wall (in tiles), `0` if wall is closed.
+	pub gap: usize,
+}
+impl Wall {
+	/// Returns buildings of the wall which places are still free on the local grid.
+	pub fn remaining<'a>(&'a self, bot: &'a Bot) -> impl Iterator<Item = (UnitTypeId, Point2)> + 'a {
+		self.buildings.iter().copied().filter(move |&(building, pos)| {
+			placement::building_size(bot, building).is_some_and(|size| bot.placement.is_area_free(pos, size))
+		})
+	}
+}
+
+struct Candidate {
+	pos: Point2,
+	tiles: Vec<usize>,
+	ring: Vec<usize>,
+}
+
+struct Solver {
+	origin: (usize, usize),
+	dim: (usize, usize),
+	walkable: Vec<bool>,
+	seeds: Vec<bool>,
+	sinks: Vec<bool>,
+	blocked: Vec<bool>,
+	gap: usize,
+	buildings: Vec<UnitTypeId>,
+	powered: Vec<bool>,
+	candidates: Vec<Vec<Candidate>>,
+	placed: Vec<usize>,
+	checks: usize,
+}
+impl Solver {
+	fn index(&self, (x, y): (isize, isize)) -> Option<usize> {
+		let (i, j) = (x - self.origin.0 as isize, y - self.origin.1 as isize);
+		if i >= 0 && j >= 0 && (i as usize) < self.dim.0 && (j as usize) < self.dim.1 {
+			Some(i as usize * self.dim.1 + j as usize)
+		} else {
+			None
+		}
+	}
+
+	fn solve(&mut self, i: usize) -> bool {
+		if self.checks >= MAX_CHECKS {
+			return false;
+		}
+		if i == self.buildings.len() {
+			self.checks += 1;
+			return if self.gap == 0 {
+				!self.connected(1)
+			} else {
+				self.connected(self.gap) && !self.connected(self.gap + 1)
+			};
+		}
+
+		let is_pylon = self.buildings[i] == UnitTypeId::Pylon;
+		for c in 0..self.candidates[i].len() {
+			let candidate = &self.candidates[i][c];
+			if candidate.tiles.iter().any(|&t| self.blocked[t]) {
+				continue;
+			}
+			// Pieces of the wall should be attached to terrain or to other buildings
+			if !is_pylon
+				&& !candidate
+					.ring
+					.iter()
+					.any(|&t| t == usize::MAX || !self.walkable[t] || self.blocked[t])
+			{
+				continue;
+			}
+			if is_pylon
+				&& self.placed.iter().enumerate().any(|(j, &p)| {
+					self.powered[j]
+						&& self.candidates[j][p].pos.distance_squared(candidate.pos)
+							> PYLON_POWER_RADIUS.powi(2)
+				}) {
+				continue;
+			}
+
+			for &t in &candidate.tiles {
+				self.blocked[t] = true;
+			}
+			self.placed.push(c);
+			if self.solve(i + 1) {
+				return true;
+			}
+			self.placed.pop();
+			for &t in &self.candidates[i][c].tiles {
+				self.blocked[t] = false;
+			}
+		}
+		false
+	}
+
+	// Checks if unit of given size (in tiles) can walk from inside to outside of the choke
+	fn connected(&self, size: usize) -> bool {
+		let (w, h) = self.dim;
+		let valid = |i: usize| {
+			let (x, y) = (i / h, i % h);
+			x + size <= w
+				&& y + size <= h
+				&& (0..size).all(|dx| {
+					(0..size).all(|dy| {
+						let t = (x + dx) * h + y + dy;
+						self.walkable[t] && !self.blocked[t]
+					})
+				})
+		};
+
+		let mut visited = vec![false; w * h];
+		let mut queue = VecDeque::new();
+		for (i, visited) in visited.iter_mut().enumerate() {
+			if self.seeds[i] && valid(i) {
+				*visited = true;
+				queue.push_back(i);
+			}
+		}
+		while let Some(i) = queue.pop_front() {
+			if self.sinks[i] {
+				return true;
+			}
+			let (x, y) = (i / h, i % h);
+			let neighbors = [
+				(x.wrapping_sub(1), y),
+				(x + 1, y),
+				(x, y.wrapping_sub(1)),
+				(x, y + 1),
+			];
+			for (nx, ny) in neighbors {
+				if nx < w && ny < h {
+					let n = nx * h + ny;
+					if !visited[n] && valid(n) {
+						visited[n] = true;
+						queue.push_back(n);
+					}
+				}
+			}
+		}
+		false
+	}
+}
+
+pub(crate) fn find_wall(
+	bot: &Bot,
+	choke: &Choke,
+	inside: Point2,
+	buildings: &[UnitTypeId],
+	gap: usize,
+) -> Option<Wall> {
+	let (a, b) = choke.main_line();
+	let (a, b) = (Point2::from(a), Point2::from(b));
+	let center = (a + b) / 2.0;
+	let length = a.distance(b);
+	let along = (b - a).normalize();
+	let mut normal = along.rotate90(false);
+	if normal.dot(inside - center) < 0.0 {
+		normal = -normal;
+	}
+
+	let extent = (length / 2.0 + MARGIN).ceil() as usize;
+	let (cx, cy) = <(usize, usize)>::from(center);
+	let (width, height) = bot.game_info.pathing_grid.dim();
+	let origin = (cx.saturating_sub(extent), cy.saturating_sub(extent));
+	let dim = (
+		(cx + extent + 1).min(width).saturating_sub(origin.0),
+		(cy + extent + 1).min(height).saturating_sub(origin.1),
+	);
+	if dim.0 == 0 || dim.1 == 0 {
+		return None;
+	}
+
+	let tile_pos = |i: usize| {
+		Point2::new(
+			(origin.0 + i / dim.1) as f32 + 0.5,
+			(origin.1 + i % dim.1) as f32 + 0.5,
+		)
+	};
+	let mut walkable = vec![false; dim.0 * dim.1];
+	let mut buildable = vec![false; dim.0 * dim.1];
+	let mut seeds = vec![false; dim.0 * dim.1];
+	let mut sinks = vec![false; dim.0 * dim.1];
+	for (i, ((w, b), (seed, sink))) in walkable
+		.iter_mut()
+		.zip(buildable.iter_mut())
+		.zip(seeds.iter_mut().zip(sinks.iter_mut()))
+		.enumerate()
+	{
+		let tile = (origin.0 + i / dim.1, origin.1 + i % dim.1);
+		let placeable = bot.is_placeable(tile);
+		let free = bot.placement.is_free(tile);
+		// Structures and resources make placeable tiles occupied
+		*w = bot.is_pathable(tile) && (!placeable || free);
+		*b = placeable && free;
+
+		let offset = tile_pos(i) - center;
+		if offset.dot(along).abs() <= length / 2.0 + 1.0 {
+			let side = offset.dot(normal);
+			*seed = (3.0..=7.0).contains(&side);
+			*sink = (-7.0..=-3.0).contains(&side);
+		}
+	}
+
+	let race_of = |building: UnitTypeId| {
+		bot.game_data
+			.units
+			.get(&building)
+			.map_or(Race::Random, |data| data.race)
+	};
+	let has_pylon = buildings.contains(&UnitTypeId::Pylon);
+
+	// Bigger buildings are placed first, pylon is placed last to power others
+	let mut order = buildings.to_vec();
+	order.sort_by_key(|&building| {
+		(
+			building == UnitTypeId::Pylon,
+			std::cmp::Reverse(placement::building_size(bot, building).unwrap_or(0)),
+		)
+	});
+
+	let mut solver = Solver {
+		origin,
+		dim,
+		walkable,
+		seeds,
+		sinks,
+		blocked: vec![false; dim.0 * dim.1],
+		gap,
+		powered: order
+			.iter()
+			.map(|&building| {
+				has_pylon
+					&& race_of(building).is_protoss()
+					&& !matches!(building, UnitTypeId::Pylon | UnitTypeId::Nexus)
+			})
+			.collect(),
+		buildings: vec![],
+		candidates: vec![],
+		placed: vec![],
+		checks: 0,
+	};
+
+	for &building in &order {
+		let size = placement::building_size(bot, building)? as isize;
+		let offset = if size % 2 == 1 { 0.5 } else { 0.0 };
+		let mut candidates = vec![];
+		for i in 0..dim.0 * dim.1 {
+			let pos = Point2::new(
+				(origin.0 + i / dim.1) as f32 + offset,
+				(origin.1 + i % dim.1) as f32 + offset,
+			);
+			let side = (pos - center).dot(normal);
+			if !(-1.0..=7.0).contains(&side) || pos.distance(center) > length / 2.0 + 4.0 {
+				continue;
+			}
+
+			let left = (pos.x - size as f32 / 2.0).round() as isize;
+			let bottom = (pos.y - size as f32 / 2.0).round() as isize;
+			let tiles = (left..left + size)
+				.flat_map(|x| (bottom..bottom + size).map(move |y| (x, y)))
+				.map(|tile| solver.index(tile).filter(|&t| buildable[t]))
+				.collect::<Option<Vec<_>>>();
+			let tiles = match tiles {
+				Some(tiles) => tiles,
+				None => continue,
+			};
+
+			let reach = gap as isize + 1;
+			let ring = (left - reach..left + size + reach)
+				.flat_map(|x| (bottom - reach..bottom + size + reach).map(move |y| (x, y)))
+				.filter(|&(x, y)| x < left || x >= left + size || y < bottom || y >= bottom + size)
+				.map(|tile| solver.index(tile).unwrap_or(usize::MAX))
+				.collect();
+			candidates.push(Candidate { pos, tiles, ring });
+		}
+		candidates.sort_by(|a, b| {
+			a.pos
+				.distance_squared(center)
+				.partial_cmp(&b.pos.distance_squared(center))
+				.unwrap_or(Ordering::Equal)
+		});
+		candidates.truncate(MAX_CANDIDATES);
+		solver.buildings.push(building);
+		solver.candidates.push(candidates);
+	}
+
+	if !solver.solve(0) {
+		return None;
+	}
+	Some(Wall {
+		buildings: solver
+			.placed
+			.iter()
+			.enumerate()
+			.map(|(i, &c)| (solver.buildings[i], solver.candidates[i][c].pos))
+			.collect(),
+		gap,
+	})
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::{bot_with_units, observation, raw_unit};
+use rust_sc2::{
+    army::{find_fronts, ArmyCluster},
+    bot::Bot,
+    consts::FRAMES_PER_SECOND,
+    game_data::{Attribute, TargetType, Weapon},
+    prelude::*,
+    units::Units,
+};
+
+fn cluster(id: u32, center: Point2, radius: f32) -> ArmyCluster {
+    ArmyCluster {
+        id,
+        center,
+        radius,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_empty() {
+    assert!(Units::new().clusters(6.0, 1).is_empty());
+
+    let bot = Bot::default();
+    assert!(bot.army.my().is_empty());
+    assert!(bot.army.enemy().is_empty());
+    assert!(bot.army.fronts().is_empty());
+}
+
+#[test]
+fn test_front_between_closest_clusters() {
+    let my = [cluster(1, Point2::new(20.0, 20.0), 3.0)];
+    let enemy = [
+        cluster(2, Point2::new(40.0, 20.0), 5.0),
+        cluster(3, Point2::new(20.0, 80.0), 5.0),
+    ];
+    let fronts = find_fronts(&my, &enemy, 20.0);
+    assert_eq!(fronts.len(), 1);
+
+    let front = fronts[0];
+    assert_eq!((front.my_cluster, front.enemy_cluster), (1, 2));
+    assert!((front.gap - 12.0).abs() < 1e-4);
+    assert!(front.position.distance(Point2::new(29.0, 20.0)) < 1e-4);
+    assert!(front.normal.distance(Point2::new(1.0, 0.0)) < 1e-4);
+
+    // Line is perpendicular to the direction to enemy and as wide as the bigger cluster
+    let (a, b) = front.line();
+    assert!((a.distance(b) - 10.0).abs() < 1e-4);
+    assert!((b - a).dot(front.normal).abs() < 1e-4);
+    assert!(front.behind(4.0).distance(Point2::new(25.0, 20.0)) < 1e-4);
+}
+
+#[test]
+fn test_no_front_with_distant_enemy() {
+    let my = [cluster(1, Point2::new(20.0, 20.0), 3.0)];
+    let enemy = [cluster(2, Point2::new(80.0, 80.0), 5.0)];
+    assert!(find_fronts(&my, &enemy, 20.0).is_empty());
+    assert!(find_fronts(&my, &[], 20.0).is_empty());
+}
+
+#[test]
+fn test_cluster_direction() {
+    let mut c = cluster(1, Point2::new(10.0, 10.0), 2.0);
+    assert!(c.direction().is_none());
+
+    c.velocity = Point2::new(0.0, 3.0);
+    assert!((c.speed() - 3.0).abs() < 1e-4);
+    assert!(c.direction().unwrap().distance(Point2::new(0.0, 1.0)) < 1e-4);
+    assert!(c.predicted_center(2.0).distance(Point2::new(10.0, 16.0)) < 1e-4);
+}
+
+fn marine_bot() -> Bot {
+    let weapon = Weapon {
+        target: TargetType::Any,
+        damage: 6,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 5.0,
+        speed: 0.61,
+    };
+    bot_with_units(
+        Race::Terran,
+        &[Attribute::Biological],
+        &[(UnitTypeId::Marine, weapon)],
+    )
+}
+
+// Marines with consecutive tags starting from `first` standing in a row
+fn marines(first: u64, owner: i32, positions: &[(f32, f32)]) -> Vec<sc2_proto::raw::Unit> {
+    positions
+        .iter()
+        .zip(first..)
+        .map(|(&(x, y), tag)| raw_unit(tag, UnitTypeId::Marine, owner, Point2::new(x, y)))
+        .collect()
+}
+
+#[test]
+fn test_tracking_is_opt_in() {
+    let mut bot = marine_bot();
+    let units = marines(1, 1, &[(10.0, 10.0), (11.0, 10.0)]);
+    bot.observe(&observation(1, units.clone()), &[]);
+    assert!(bot.army.my().is_empty());
+
+    bot.track_army = true;
+    bot.observe(&observation(2, units), &[]);
+    assert_eq!(bot.army.my().len(), 1);
+}
+
+#[test]
+fn test_ids_are_stable() {
+    let mut bot = marine_bot();
+    bot.track_army = true;
+    // Steps are 5 seconds apart
+    let step = (FRAMES_PER_SECOND * 5.0) as u32;
+
+    let mut units = marines(1, 1, &[(10.0, 10.0), (11.0, 10.0), (10.0, 11.0)]);
+    units.extend(marines(4, 1, &[(40.0, 10.0), (41.0, 10.0), (60.0, 10.0)]));
+    units.extend(marines(10, 2, &[(30.0, 40.0), (31.0, 40.0)]));
+    bot.observe(&observation(0, units), &[]);
+
+    // Ids start from 0 as in untracked clusters
+    let mut ids = bot.army.my().iter().map(|c| c.id).collect::<Vec<_>>();
+    ids.extend(bot.army.enemy().iter().map(|c| c.id));
+    ids.sort_unstable();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+    assert_eq!(
+        bot.units
+            .my
+            .units
+            .clusters(6.0, 1)
+            .iter()
+            .map(|c| c.id)
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    let first = bot.army.cluster_of(1).unwrap().id;
+    let second_group = bot.army.cluster_of(4).unwrap().id;
+    let enemy = bot.army.cluster_of(10).unwrap().id;
+    assert_eq!(bot.army.cluster_of(2).unwrap().id, first);
+    assert!(bot.army.get(enemy).unwrap().tags.contains(&11));
+
+    // Groups move, units join and leave
+    let mut units = marines(1, 1, &[(12.0, 10.0), (13.0, 10.0), (12.0, 11.0)]);
+    units.extend(marines(4, 1, &[(40.0, 10.0), (41.0, 10.0)]));
+    units.extend(marines(7, 1, &[(42.0, 10.0), (60.0, 10.0)]));
+    units.extend(marines(10, 2, &[(30.0, 37.0), (31.0, 37.0)]));
+    bot.observe(&observation(step, units), &[]);
+
+    assert_eq!(bot.army.cluster_of(1).unwrap().id, first);
+    assert_eq!(bot.army.cluster_of(7).unwrap().id, second_group);
+    assert_eq!(bot.army.cluster_of(10).unwrap().id, enemy);
+    let velocity = |id| bot.army.get(id).unwrap().velocity;
+    assert!(velocity(first).distance(Point2::new(0.4, 0.0)) < 1e-3);
+    assert!(velocity(enemy).distance(Point2::new(0.0, -0.6)) < 1e-3);
+    // Lost unit doesn't take id of its cluster, new unit gets new id
+    assert!(bot.army.cluster_of(6).is_none());
+    assert_eq!(bot.army.cluster_of(8).unwrap().id, 4);
+
+    // Cluster splits, the bigger part keeps the id
+    let mut units = marines(1, 1, &[(12.0, 10.0), (13.0, 10.0), (12.0, 30.0)]);
+    units.extend(marines(10, 2, &[(30.0, 37.0), (31.0, 37.0)]));
+    bot.observe(&observation(step * 2, units), &[]);
+
+    assert_eq!(bot.army.cluster_of(1).unwrap().id, first);
+    assert_eq!(bot.army.cluster_of(3).unwrap().id, 5);
+    assert!(bot.army.get(second_group).is_none());
+    assert_eq!(bot.army.cluster_of(10).unwrap().id, enemy);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{bot, observation, raw_unit, unit_data};
+use rust_sc2::{
+    balance::{BalanceError, BalanceTable, BalanceTables},
+    game_data::{Attribute, GameData, TargetType, Weapon},
+    prelude::*,
+};
+
+const TABLES: &str = "
+# Changes of built-in table
+speed_buff Stimpack 1.6
+
+[90000]
+damage_bonus stalker any - armored=2
+speed_upgrade Zealot Charge 1.4 # patch note
+cost Marine 50 0 1 400
+hits Marine 55 0
+speed Marine 3.2
+
+[80000]
+inhibitor_zone InhibitorZoneSmall 4.5
+";
+
+#[test]
+fn test_builtin_table() {
+    let tables = BalanceTables::default();
+    assert_eq!(tables.tables().len(), 1);
+    let table = tables.select(81009);
+    assert_eq!(table, &BalanceTable::default());
+    assert_eq!(
+        table.speed_upgrades[&UnitTypeId::Zealot],
+        (UpgradeId::Charge, 1.5)
+    );
+    assert!(table.costs.is_empty());
+    assert!(table.speeds.is_empty());
+}
+
+#[test]
+fn test_parse_tables() {
+    let tables = BalanceTables::parse(TABLES).unwrap();
+    assert_eq!(
+        tables.tables().iter().map(|t| t.base_version).collect::<Vec<_>>(),
+        [0, 80000, 90000]
+    );
+
+    let base = tables.select(75689);
+    assert_eq!(base.base_version, 0);
+    assert_eq!(base.speed_buffs[&BuffId::Stimpack], 1.6);
+
+    let old = tables.select(81009);
+    assert_eq!(old.base_version, 80000);
+    assert_eq!(old.inhibitor_zone_radius[&UnitTypeId::InhibitorZoneSmall], 4.5);
+    assert_eq!(old.speed_buffs[&BuffId::Stimpack], 1.6);
+    assert_eq!(old.speed_upgrades[&UnitTypeId::Zealot].1, 1.5);
+
+    let new = tables.get(90000).unwrap();
+    assert_eq!(new, tables.select(95000));
+    assert_eq!(new.speed_upgrades[&UnitTypeId::Zealot].1, 1.4);
+    assert_eq!(new.costs[&UnitTypeId::Marine].minerals, 50);
+    assert_eq!(new.hits[&UnitTypeId::Marine], (55, 0));
+    assert_eq!(new.speeds[&UnitTypeId::Marine], 3.2);
+    assert_eq!(base.hits[&UnitTypeId::Marine], (45, 0));
+    // Inherited from the table for the closest lower version
+    assert_eq!(new.inhibitor_zone_radius[&UnitTypeId::InhibitorZoneSmall], 4.5);
+    let bonus = &new.damage_bonus_per_upgrade[&UnitTypeId::Stalker][&TargetType::Any];
+    assert_eq!(bonus.0, None);
+    assert_eq!(bonus.1[&Attribute::Armored], 2);
+}
+
+#[test]
+fn test_invalid_tables() {
+    for (text, line) in [
+        ("speed_buff Stimpack", 1),
+        ("\nspeed_buff NotABuff 1.5", 2),
+        ("[90000]\ncost Marine 50 0", 2),
+        ("speed Marine fast", 1),
+        ("[version]", 1),
+        ("damage_bonus Stalker Any - Armored", 1),
+    ] {
+        match BalanceTables::parse(text) {
+            Err(BalanceError::InvalidLine { line: l, .. }) => assert_eq!(l, line, "{}", text),
+            _ => panic!("{} should be invalid", text),
+        }
+    }
+    assert!(matches!(
+        BalanceTables::load("no/such/balance.txt"),
+        Err(BalanceError::Io(_))
+    ));
+}
+
+#[test]
+fn test_validate() {
+    let tables = BalanceTables::parse(TABLES).unwrap();
+    let issues = tables.select(90000).validate(&GameData::default());
+    assert!(issues.contains(&"Unknown unit type Marine".to_string()));
+    assert!(issues.contains(&"Unknown upgrade Charge".to_string()));
+    assert!(issues.contains(&"Unknown buff Stimpack".to_string()));
+}
+
+#[test]
+fn test_validate_speeds() {
+    let tables = BalanceTables::parse(TABLES).unwrap();
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 6,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 5.0,
+        speed: 0.61,
+    };
+    let mut data = GameData::default();
+    let marine = unit_data(UnitTypeId::Marine, Race::Terran, vec![], 3.15, weapon);
+    data.units.insert(UnitTypeId::Marine, marine);
+
+    let issues = tables.select(90000).validate(&data);
+    assert!(issues.contains(&"Speed of Marine is 3.2 in table, but 3.15 in game".to_string()));
+    assert!(!issues.contains(&"Unknown unit type Marine".to_string()));
+
+    let mut table = tables.select(90000).clone();
+    table.speeds.insert(UnitTypeId::Marine, 3.155);
+    assert!(!table
+        .validate(&data)
+        .iter()
+        .any(|issue| issue.starts_with("Speed")));
+}
+
+#[test]
+fn test_inhibitor_zone() {
+    let mut bot = bot();
+    let zone = raw_unit(1, UnitTypeId::InhibitorZoneSmall, 0, Point2::new(20.0, 20.0));
+    bot.observe(&observation(1, vec![zone]), &[]);
+    assert_eq!(bot.units.inhibitor_zones.len(), 1);
+
+    assert!(bot.in_inhibitor_zone(Point2::new(23.0, 20.0)));
+    assert!(!bot.in_inhibitor_zone(Point2::new(25.0, 20.0)));
+}
@@ -0,0 +1,50 @@
+use rust_sc2::{bot::Bot, commands::ChatCommand};
+
+#[test]
+fn test_parse_command() {
+    let cmd = ChatCommand::parse(2, "  !Spawn Marine 10 ", "!").unwrap();
+    assert_eq!(cmd.player_id, 2);
+    assert_eq!(cmd.name, "spawn");
+    assert_eq!(cmd.args, ["Marine", "10"]);
+    assert_eq!(cmd.arg(1), Some("10"));
+    assert_eq!(cmd.arg(2), None);
+
+    assert_eq!(ChatCommand::parse(2, "!fog off", "!").unwrap().arg(0), Some("off"));
+    assert!(ChatCommand::parse(2, "gl hf", "!").is_none());
+    assert!(ChatCommand::parse(2, "!", "!").is_none());
+    assert_eq!(ChatCommand::parse(2, "-pause", "-").unwrap().name, "pause");
+}
+
+#[test]
+fn test_register_commands() {
+    let mut bot = Bot::default();
+    assert!(bot.commands.is_empty());
+    assert!(!bot.commands.is_paused());
+
+    bot.commands.add_defaults();
+    for name in ["help", "show", "hide", "pause", "speed", "kill", "spawn", "fog"] {
+        assert!(bot.commands.contains(name), "{}", name);
+    }
+    let len = bot.commands.len();
+
+    bot.commands.add("Spawn", "custom spawn", |_, _| Ok(()));
+    assert_eq!(bot.commands.len(), len);
+    assert!(bot
+        .commands
+        .list()
+        .any(|(name, help)| name == "spawn" && help == "custom spawn"));
+
+    assert!(bot.commands.remove("pause"));
+    assert!(!bot.commands.remove("pause"));
+    assert_eq!(bot.commands.len(), len - 1);
+
+    bot.commands.clear();
+    assert!(bot.commands.is_empty());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_bot_is_thread_safe() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Bot>();
+}
@@ -0,0 +1,240 @@
+//! Fixtures shared by integration tests.
+#![allow(dead_code)]
+
+use rust_sc2::{
+    action::Target,
+    bot::Bot,
+    game_data::{AbilityData, AbilityTarget, Attribute, GameData, TargetType, UnitTypeData, Weapon},
+    geometry::{Rect, Size},
+    pixel_map::{ByteMap, Pixel, PixelMap},
+    prelude::*,
+};
+use sc2_proto::{
+    common::Point,
+    query::ResponseQueryAvailableAbilities,
+    raw::{Alliance, DisplayType, Unit as RawUnit, UnitOrder},
+    sc2api::ResponseObservation,
+};
+
+/// Side of the square map used by observations.
+pub const MAP_SIZE: i32 = 64;
+
+pub fn unit_data(
+    id: UnitTypeId,
+    race: Race,
+    attributes: Vec<Attribute>,
+    speed: f32,
+    weapon: Weapon,
+) -> UnitTypeData {
+    UnitTypeData {
+        id,
+        name: format!("{:?}", id),
+        available: true,
+        cargo_size: 2,
+        mineral_cost: 100,
+        vespene_cost: 50,
+        food_required: 2.0,
+        food_provided: 0.0,
+        ability: None,
+        race,
+        build_time: 0.0,
+        has_vespene: false,
+        has_minerals: false,
+        sight_range: 9.0,
+        tech_alias: vec![],
+        unit_alias: None,
+        tech_requirement: None,
+        require_attached: false,
+        attributes,
+        movement_speed: speed,
+        armor: 1,
+        weapons: vec![weapon],
+    }
+}
+
+/// Bot of player `1` playing against player `2`.
+pub fn bot() -> Bot {
+    let mut bot = Bot::default();
+    bot.player_id = 1;
+    bot.enemy_player_id = 2;
+    bot
+}
+
+/// Game data of units with given weapons, which all have the same race and attributes
+/// and move with speed `3.15`.
+pub fn units_data(race: Race, attributes: &[Attribute], units: &[(UnitTypeId, Weapon)]) -> GameData {
+    let mut data = GameData::default();
+    for (id, weapon) in units {
+        data.units.insert(
+            *id,
+            unit_data(*id, race, attributes.to_vec(), 3.15, weapon.clone()),
+        );
+    }
+    data
+}
+
+/// [`bot`] with game data of given units (see [`units_data`]).
+pub fn bot_with_units(race: Race, attributes: &[Attribute], units: &[(UnitTypeId, Weapon)]) -> Bot {
+    let mut bot = bot();
+    bot.game_data = units_data(race, attributes, units).into();
+    bot
+}
+
+/// Ready unit with full health, owned by player `1`, `2` or neutral if `owner` is `0`.
+pub fn raw_unit(tag: u64, type_id: UnitTypeId, owner: i32, pos: Point2) -> RawUnit {
+    let mut u = RawUnit::new();
+    u.set_tag(tag);
+    u.set_unit_type(type_id as u32);
+    u.set_owner(owner);
+    u.set_alliance(match owner {
+        0 => Alliance::Neutral,
+        1 => Alliance::value_Self,
+        _ => Alliance::Enemy,
+    });
+    u.set_display_type(DisplayType::Visible);
+    u.set_pos(point(pos));
+    u.set_radius(0.5);
+    u.set_build_progress(1.0);
+    u.set_health(100.0);
+    u.set_health_max(100.0);
+    u
+}
+
+pub fn order(ability: AbilityId, target: Target, progress: f32) -> UnitOrder {
+    let mut order = UnitOrder::new();
+    order.set_ability_id(ability as u32);
+    match target {
+        Target::Pos(pos) => order.set_target_world_space_pos(point(pos)),
+        Target::Tag(tag) => order.set_target_unit_tag(tag),
+        Target::None => {}
+    }
+    order.set_progress(progress);
+    order
+}
+
+/// Observation of given units on fully visible map without creep.
+pub fn observation(game_loop: u32, units: Vec<RawUnit>) -> ResponseObservation {
+    let mut res = ResponseObservation::new();
+    let obs = res.mut_observation();
+    obs.set_game_loop(game_loop);
+    obs.mut_player_common().set_player_id(1);
+
+    let raw = obs.mut_raw_data();
+    raw.set_units(units.into());
+    let visibility = raw.mut_map_state().mut_visibility();
+    visibility.set_bits_per_pixel(8);
+    visibility.mut_size().set_x(MAP_SIZE);
+    visibility.mut_size().set_y(MAP_SIZE);
+    visibility.set_data(vec![2; (MAP_SIZE * MAP_SIZE) as usize]);
+    let creep = raw.mut_map_state().mut_creep();
+    creep.set_bits_per_pixel(1);
+    creep.mut_size().set_x(MAP_SIZE);
+    creep.mut_size().set_y(MAP_SIZE);
+    creep.set_data(vec![0; (MAP_SIZE * MAP_SIZE / 8) as usize]);
+    res
+}
+
+/// Abilities available to given units.
+pub fn abilities(units: &[(u64, &[AbilityId])]) -> Vec<ResponseQueryAvailableAbilities> {
+    units
+        .iter()
+        .map(|(tag, abilities)| {
+            let mut res = ResponseQueryAvailableAbilities::new();
+            res.set_unit_tag(*tag);
+            for ability in *abilities {
+                let mut available = sc2_proto::common::AvailableAbility::new();
+                available.set_ability_id(*ability as i32);
+                res.mut_abilities().push(available);
+            }
+            res
+        })
+        .collect()
+}
+
+fn point(pos: Point2) -> Point {
+    let mut point = Point::new();
+    point.set_x(pos.x);
+    point.set_y(pos.y);
+    point
+}
+
+pub fn structure_data(id: UnitTypeId, race: Race) -> UnitTypeData {
+    UnitTypeData {
+        food_required: 0.0,
+        movement_speed: 0.0,
+        weapons: vec![],
+        ..unit_data(
+            id,
+            race,
+            vec![Attribute::Armored, Attribute::Structure],
+            0.0,
+            Weapon {
+                target: TargetType::Ground,
+                damage: 0,
+                damage_bonus: vec![],
+                attacks: 0,
+                range: 0.0,
+                speed: 1.0,
+            },
+        )
+    }
+}
+
+/// Build ability of structure with given footprint.
+pub fn ability_data(id: AbilityId, footprint_radius: f32) -> AbilityData {
+    AbilityData {
+        id,
+        link_name: format!("{:?}", id),
+        link_index: 0,
+        button_name: None,
+        friendly_name: None,
+        hotkey: None,
+        remaps_to_ability_id: None,
+        available: true,
+        target: AbilityTarget::Point,
+        allow_minimap: false,
+        allow_autocast: false,
+        is_building: true,
+        footprint_radius: Some(footprint_radius),
+        is_instant_placement: false,
+        cast_range: None,
+    }
+}
+
+/// Adds structure built by worker with given ability to game data.
+pub fn add_building(
+    data: &mut GameData,
+    id: UnitTypeId,
+    race: Race,
+    ability: AbilityId,
+    footprint_radius: f32,
+) {
+    let mut unit = structure_data(id, race);
+    unit.ability = Some(ability);
+    data.units.insert(id, unit);
+    data.abilities
+        .insert(ability, ability_data(ability, footprint_radius));
+}
+
+/// Sets visibility of observation: tiles are visible if `visible` returns `true`, fogged otherwise.
+pub fn set_visibility(obs: &mut ResponseObservation, visible: impl Fn(usize, usize) -> bool) {
+    let size = MAP_SIZE as usize;
+    let data = (0..size * size)
+        .map(|i| if visible(i % size, i / size) { 2 } else { 1 })
+        .collect();
+    obs.mut_observation()
+        .mut_raw_data()
+        .mut_map_state()
+        .mut_visibility()
+        .set_data(data);
+}
+
+/// Map of [`MAP_SIZE`] where all tiles are pathable and placeable, with 2 tiles border outside of playable area.
+pub fn open_map(bot: &mut Bot) {
+    let size = MAP_SIZE as usize;
+    bot.game_info.map_size = Size::new(size, size);
+    bot.game_info.pathing_grid = PixelMap::from_elem((size, size), Pixel::Empty);
+    bot.game_info.placement_grid = PixelMap::from_elem((size, size), Pixel::Empty);
+    bot.game_info.terrain_height = ByteMap::from_elem((size, size), 0).into();
+    bot.game_info.playable_area = Rect::new(2, 2, size - 2, size - 2);
+}
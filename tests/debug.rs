@@ -0,0 +1,73 @@
+mod common;
+
+use common::observation;
+use rust_sc2::{bot::Bot, debug::Debugger, geometry::Point3, prelude::*};
+
+#[test]
+fn test_layers() {
+    let mut bot = Bot::default();
+    assert!(bot.debug.is_layer_enabled("unknown"));
+
+    bot.debug.in_layer("paths", |debug| {
+        debug.draw_path(&[Point2::new(1.0, 1.0), Point2::new(5.0, 5.0)], None);
+    });
+    assert_eq!(bot.debug.layers().collect::<Vec<_>>(), vec![("paths", true)]);
+
+    assert!(!bot.debug.toggle_layer("paths"));
+    assert!(!bot.debug.is_layer_enabled("paths"));
+    bot.debug.enable_layer("paths");
+    assert!(bot.debug.is_layer_enabled("paths"));
+    bot.debug.disable_layer("heatmap");
+    assert!(!bot.debug.is_layer_enabled("heatmap"));
+}
+
+#[test]
+fn test_on_terrain_without_map() {
+    let bot = Bot::default();
+    let pos = bot.debug.on_terrain(Point2::new(10.0, 20.0));
+    assert_eq!((pos.x, pos.y), (10.0, 20.0));
+}
+
+fn draw(debug: &mut Debugger) {
+    debug.draw_sphere(Point3::new(10.0, 10.0, 0.0), 1.0, None);
+}
+
+#[test]
+fn test_persistent_expiry() {
+    let mut bot = common::bot();
+    bot.observe(&observation(10, vec![]), &[]);
+    bot.debug.persistent(20, draw);
+    assert_eq!(bot.debug.pending_drawings(), 1);
+
+    // Expiry depends on game loops passed, not on number of steps
+    bot.observe(&observation(29, vec![]), &[]);
+    assert_eq!(bot.debug.pending_drawings(), 1);
+    bot.observe(&observation(30, vec![]), &[]);
+    assert_eq!(bot.debug.pending_drawings(), 0);
+}
+
+#[test]
+fn test_persistent_on_disabled_layer() {
+    let mut bot = common::bot();
+    bot.observe(&observation(10, vec![]), &[]);
+    bot.debug.disable_layer("paths");
+
+    // Regular drawings of disabled layer are dropped, persistent are kept hidden
+    bot.debug.in_layer("paths", |debug| {
+        draw(debug);
+        debug.persistent(20, draw);
+    });
+    assert_eq!(bot.debug.pending_drawings(), 0);
+
+    bot.observe(&observation(20, vec![]), &[]);
+    bot.debug.enable_layer("paths");
+    assert_eq!(bot.debug.pending_drawings(), 1);
+    draw(&mut bot.debug);
+    assert_eq!(bot.debug.pending_drawings(), 2);
+
+    bot.debug.toggle_layer("paths");
+    assert_eq!(bot.debug.pending_drawings(), 1);
+    bot.debug.toggle_layer("paths");
+    bot.debug.clear_persistent();
+    assert_eq!(bot.debug.pending_drawings(), 1);
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use common::{bot, observation, order, raw_unit, structure_data};
+use rust_sc2::{
+    action::Target,
+    bot::{Bot, Expansion},
+    game_data::GameData,
+    prelude::*,
+};
+
+fn events_bot() -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    data.units.insert(
+        UnitTypeId::Hatchery,
+        structure_data(UnitTypeId::Hatchery, Race::Zerg),
+    );
+    bot.game_data = data.into();
+    bot.enemy_start = Point2::new(50.5, 50.5);
+    bot.expansions = vec![Expansion {
+        loc: Point2::new(30.5, 30.5),
+        center: Point2::new(30.5, 36.5),
+        minerals: Default::default(),
+        geysers: Default::default(),
+        alliance: Alliance::Neutral,
+        base: None,
+    }];
+    bot
+}
+
+fn count(events: &[Event], f: impl Fn(&Event) -> bool) -> usize {
+    events.iter().filter(|e| f(e)).count()
+}
+
+#[test]
+fn test_unit_events() {
+    let mut bot = events_bot();
+    let mut marine = raw_unit(1, UnitTypeId::Marine, 1, Point2::new(10.0, 10.0));
+    marine.mut_orders().push(order(
+        AbilityId::Attack,
+        Target::Pos(Point2::new(20.0, 20.0)),
+        0.0,
+    ));
+    let tank = raw_unit(2, UnitTypeId::SiegeTank, 1, Point2::new(12.0, 10.0));
+    let zergling = raw_unit(10, UnitTypeId::Zergling, 2, Point2::new(20.0, 20.0));
+
+    let events = bot.observe(&observation(0, vec![marine.clone(), tank, zergling.clone()]), &[]);
+    assert_eq!(count(&events, |e| matches!(e, Event::UnitCreated(_))), 2);
+    assert_eq!(count(&events, |e| matches!(e, Event::EnemyUnitSeen(10))), 1);
+    assert_eq!(count(&events, |e| matches!(e, Event::UnitIdle(_))), 0);
+
+    marine.clear_orders();
+    marine.set_health(90.0);
+    let sieged = raw_unit(2, UnitTypeId::SiegeTankSieged, 1, Point2::new(12.0, 10.0));
+    let mut obs = observation(22, vec![marine, sieged, zergling]);
+    obs.mut_observation()
+        .mut_raw_data()
+        .mut_player()
+        .mut_upgrade_ids()
+        .push(UpgradeId::Stimpack as u32);
+
+    let events = bot.observe(&obs, &[]);
+    assert_eq!(count(&events, |e| matches!(e, Event::UnitDamaged(1, 10))), 1);
+    assert_eq!(count(&events, |e| matches!(e, Event::UnitIdle(1))), 1);
+    assert_eq!(
+        count(&events, |e| matches!(
+            e,
+            Event::UnitTypeChanged(2, UnitTypeId::SiegeTank, UnitTypeId::SiegeTankSieged)
+        )),
+        1
+    );
+    assert_eq!(
+        count(&events, |e| matches!(
+            e,
+            Event::UpgradeComplete(UpgradeId::Stimpack)
+        )),
+        1
+    );
+    // Already seen enemy isn't reported again
+    assert_eq!(count(&events, |e| matches!(e, Event::EnemyUnitSeen(_))), 0);
+    assert_eq!(count(&events, |e| matches!(e, Event::UnitCreated(_))), 0);
+}
+
+#[test]
+fn test_enemy_expanded() {
+    let mut bot = events_bot();
+    let expanded = |events: &[Event]| count(events, |e| matches!(e, Event::EnemyExpanded(_)));
+    let hatchery = |tag| raw_unit(tag, UnitTypeId::Hatchery, 2, Point2::new(30.5, 30.5));
+
+    let events = bot.observe(&observation(0, vec![hatchery(20)]), &[]);
+    assert_eq!(expanded(&events), 1);
+    assert!(bot.expansions[0].alliance.is_enemy());
+
+    // Base goes out of vision and comes back
+    let events = bot.observe(&observation(22, vec![]), &[]);
+    assert_eq!(expanded(&events), 0);
+    assert!(bot.expansions[0].alliance.is_neutral());
+    let events = bot.observe(&observation(44, vec![hatchery(20)]), &[]);
+    assert_eq!(expanded(&events), 0);
+
+    // Base is destroyed and rebuilt
+    let mut obs = observation(66, vec![]);
+    obs.mut_observation()
+        .mut_raw_data()
+        .mut_event()
+        .mut_dead_units()
+        .push(20);
+    bot.observe(&obs, &[]);
+    let events = bot.observe(&observation(88, vec![hatchery(21)]), &[]);
+    assert_eq!(expanded(&events), 1);
+
+    // Enemy start location isn't reported
+    let mut bot = events_bot();
+    let main = raw_unit(30, UnitTypeId::Hatchery, 2, Point2::new(50.5, 50.5));
+    bot.expansions[0].loc = Point2::new(50.5, 50.5);
+    let events = bot.observe(&observation(0, vec![main]), &[]);
+    assert_eq!(expanded(&events), 0);
+}
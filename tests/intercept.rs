@@ -0,0 +1,149 @@
+mod common;
+
+use common::{bot_with_units, observation, order, raw_unit};
+use rust_sc2::{
+    action::Target,
+    bot::Bot,
+    consts::FRAMES_PER_SECOND,
+    game_data::{Attribute, TargetType, Weapon},
+    prelude::*,
+    utils::intercept,
+};
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+}
+
+#[test]
+fn test_stationary_target() {
+    let (pos, time) = intercept(
+        Point2::new(10.0, 10.0),
+        2.0,
+        Point2::new(20.0, 10.0),
+        Point2::default(),
+        4.0,
+    )
+    .unwrap();
+    assert!(pos.distance(Point2::new(20.0, 10.0)) < 1e-3);
+    assert_close(time, 3.0);
+}
+
+#[test]
+fn test_target_in_range() {
+    let target = Point2::new(12.0, 10.0);
+    let (pos, time) = intercept(Point2::new(10.0, 10.0), 2.0, target, Point2::new(5.0, 0.0), 3.0).unwrap();
+    assert_eq!(pos, target);
+    assert_close(time, 0.0);
+}
+
+#[test]
+fn test_fleeing_target() {
+    let chaser = Point2::new(0.0, 0.0);
+    let velocity = Point2::new(0.0, 2.0);
+    let (pos, time) = intercept(chaser, 4.0, Point2::new(10.0, 0.0), velocity, 1.0).unwrap();
+    // Chaser reaches the range exactly when the target is there
+    assert!(pos.distance(Point2::new(10.0, 0.0) + velocity * time) < 1e-3);
+    assert_close(chaser.distance(pos), 4.0 * time + 1.0);
+
+    // Target running straight away with the same speed can't be caught
+    assert!(intercept(chaser, 2.0, Point2::new(10.0, 0.0), Point2::new(2.0, 0.0), 1.0).is_none());
+    assert!(intercept(chaser, 1.0, Point2::new(10.0, 0.0), Point2::new(3.0, 0.0), 1.0).is_none());
+}
+
+#[test]
+fn test_approaching_target() {
+    let (pos, time) = intercept(
+        Point2::new(0.0, 0.0),
+        1.0,
+        Point2::new(10.0, 0.0),
+        Point2::new(-3.0, 0.0),
+        2.0,
+    )
+    .unwrap();
+    assert_close(time, 2.0);
+    assert!(pos.distance(Point2::new(4.0, 0.0)) < 1e-3);
+}
+
+fn zealot_bot() -> Bot {
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 8,
+        damage_bonus: vec![],
+        attacks: 2,
+        range: 0.1,
+        speed: 0.857,
+    };
+    bot_with_units(
+        Race::Protoss,
+        &[Attribute::Biological],
+        &[(UnitTypeId::Zealot, weapon)],
+    )
+}
+
+fn zealot(tag: u64, pos: Point2) -> sc2_proto::raw::Unit {
+    raw_unit(tag, UnitTypeId::Zealot, 2, pos)
+}
+
+#[test]
+fn test_velocity_between_steps() {
+    let mut bot = zealot_bot();
+    bot.observe(&observation(100, vec![zealot(1, Point2::new(10.0, 10.0))]), &[]);
+    let u = bot.units.enemy.units.first().unwrap();
+    assert_eq!(u.velocity(), None);
+    assert_eq!(u.predicted_velocity(), Point2::default());
+    assert_eq!(u.predict_position(22), Point2::new(10.0, 10.0));
+
+    // 8 frames later unit moved 1 tile to the right, new unit appeared
+    bot.observe(
+        &observation(
+            108,
+            vec![
+                zealot(1, Point2::new(11.0, 10.0)),
+                zealot(2, Point2::new(20.0, 20.0)),
+            ],
+        ),
+        &[],
+    );
+    let u = bot.units.enemy.units.get(1).unwrap();
+    let expected = Point2::new(FRAMES_PER_SECOND / 8.0, 0.0);
+    assert!(u.velocity().unwrap().distance(expected) < 1e-3);
+    assert!(u.predicted_velocity().distance(expected) < 1e-3);
+    assert!(u.predict_position(16).distance(Point2::new(13.0, 10.0)) < 1e-3);
+    assert_eq!(bot.units.enemy.units.get(2).unwrap().velocity(), None);
+
+    // Unit standing still
+    bot.observe(&observation(116, vec![zealot(1, Point2::new(11.0, 10.0))]), &[]);
+    let u = bot.units.enemy.units.first().unwrap();
+    assert_eq!(u.velocity(), Some(Point2::default()));
+    assert_eq!(u.predict_position(100), Point2::new(11.0, 10.0));
+}
+
+#[test]
+fn test_prediction_with_move_order() {
+    let mut bot = zealot_bot();
+    bot.observe(&observation(100, vec![zealot(1, Point2::new(10.0, 10.0))]), &[]);
+
+    // Unit moved up, but its order says it's going to the right now
+    let target = Point2::new(20.0, 11.0);
+    let mut u = zealot(1, Point2::new(10.0, 11.0));
+    u.mut_orders()
+        .push(order(AbilityId::MoveMove, Target::Pos(target), 0.0));
+    bot.observe(&observation(108, vec![u]), &[]);
+
+    let u = bot.units.enemy.units.first().unwrap();
+    let speed = u.real_speed();
+    assert!(speed > 0.0);
+    assert!(
+        u.velocity()
+            .unwrap()
+            .distance(Point2::new(0.0, FRAMES_PER_SECOND / 8.0))
+            < 1e-3
+    );
+    assert!(u.predicted_velocity().distance(Point2::new(speed, 0.0)) < 1e-3);
+
+    // Unit moves towards target and stops there
+    let frames = 22;
+    let expected = Point2::new(10.0 + speed * frames as f32 / FRAMES_PER_SECOND, 11.0);
+    assert!(u.predict_position(frames).distance(expected) < 1e-3);
+    assert_eq!(u.predict_position(10_000), target);
+}
@@ -0,0 +1,78 @@
+use log::LevelFilter;
+use rust_sc2::{
+    logging::{finish_game_log, start_game_log},
+    prelude::*,
+};
+use std::fs;
+
+#[test]
+fn test_parse_ladder_args() {
+    let args = LadderArgs::parse([
+        "--GamePort",
+        "5677",
+        "--StartPort=5690",
+        "--LadderServer",
+        "127.0.0.1",
+        "--OpponentId",
+        "a1b2c3",
+        "--RealTime",
+        "--Unknown",
+    ])
+    .unwrap();
+    assert!(args.is_ladder());
+    assert_eq!(args.game_port, Some(5677));
+    assert_eq!(args.start_port, Some(5690));
+    assert_eq!(args.ladder_server.as_deref(), Some("127.0.0.1"));
+    assert_eq!(args.opponent_id.as_deref(), Some("a1b2c3"));
+    assert!(args.realtime);
+}
+
+#[test]
+fn test_local_args() {
+    let args = LadderArgs::parse(Vec::<String>::new()).unwrap();
+    assert!(!args.is_ladder());
+    assert_eq!(args, LadderArgs::default());
+
+    let args = LadderArgs::parse(["--RealTime=false", "--GamePort", "5677"]).unwrap();
+    assert!(!args.realtime);
+    assert!(!args.is_ladder());
+}
+
+#[test]
+fn test_invalid_args() {
+    assert!(LadderArgs::parse(["--GamePort", "port"]).is_err());
+    assert!(LadderArgs::parse(["--StartPort"]).is_err());
+    assert!(LadderArgs::parse(["--RealTime=maybe"]).is_err());
+}
+
+#[test]
+fn test_game_log() {
+    let dir = std::env::temp_dir().join(format!("rust_sc2_logs_{}", std::process::id()));
+    let first = start_game_log(&dir, "vs_a1/b2", LevelFilter::Info).unwrap();
+    assert!(first
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("vs_a1%2Fb2_"));
+    log::info!("first game");
+    log::debug!("filtered out");
+    finish_game_log();
+
+    let second = start_game_log(&dir, "second", LevelFilter::Debug).unwrap();
+    log::debug!("second game");
+    finish_game_log();
+
+    // Game with the same name started right after doesn't overwrite previous log
+    let third = start_game_log(&dir, "second", LevelFilter::Debug).unwrap();
+    assert_ne!(third, second);
+    log::debug!("third game");
+    finish_game_log();
+
+    let first = fs::read_to_string(first).unwrap();
+    assert!(first.contains("first game"));
+    assert!(!first.contains("filtered out"));
+    assert!(fs::read_to_string(second).unwrap().contains("second game"));
+    assert!(fs::read_to_string(third).unwrap().contains("third game"));
+    fs::remove_dir_all(dir).unwrap();
+}
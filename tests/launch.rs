@@ -0,0 +1,124 @@
+use rust_sc2::launch::{LaunchConfig, LaunchError, VersionRegistry};
+use std::{fs, path::PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_sc2_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_builtin_versions() {
+    let versions = VersionRegistry::default();
+    let info = versions.get("4.10").unwrap();
+    assert_eq!(info.base_version, 75689);
+    assert_eq!(versions.get("4.10.0"), Some(info));
+    assert!(matches!(versions.resolve("1.0"), Err(LaunchError::UnknownVersion(_))));
+}
+
+#[test]
+fn test_parse_versions() {
+    let mut versions = VersionRegistry::default();
+    versions
+        .parse("# Custom\n\n9.9.9 99999 ABCDEF\n4.10 11111 123456\n")
+        .unwrap();
+    assert_eq!(versions.get("9.9.9").unwrap().data_hash, "ABCDEF");
+    assert_eq!(versions.get("4.10").unwrap().base_version, 11111);
+
+    let err = versions.parse("1.0 100 HASH\n1.1 not_a_number HASH").unwrap_err();
+    assert!(matches!(err, LaunchError::InvalidVersionLine { line: 2, .. }));
+    assert!(versions.parse("1.2 100").is_err());
+    assert!(versions.parse("1.2 100 HASH extra").is_err());
+}
+
+#[test]
+fn test_find_map() {
+    let sc2 = temp_dir("find_map");
+    let maps = sc2.join("Maps");
+    fs::create_dir_all(maps.join("Ladder2019Season3")).unwrap();
+    fs::write(maps.join("Simple64.SC2Map"), b"").unwrap();
+    fs::write(maps.join("Ladder2019Season3/AcropolisLE.SC2Map"), b"").unwrap();
+    let custom = sc2.join("custom");
+    fs::create_dir_all(&custom).unwrap();
+    fs::write(custom.join("Simple64.SC2Map"), b"").unwrap();
+
+    let sc2_path = sc2.to_str().unwrap();
+    let config = LaunchConfig::default();
+    let found = config.find_map(sc2_path, "Simple64").unwrap();
+    assert_eq!(PathBuf::from(found), maps.join("Simple64.SC2Map"));
+    let found = config.find_map(sc2_path, "AcropolisLE").unwrap();
+    assert_eq!(PathBuf::from(found), maps.join("Ladder2019Season3/AcropolisLE.SC2Map"));
+    let found = config.find_map(sc2_path, "Ladder2019Season3/AcropolisLE").unwrap();
+    assert_eq!(PathBuf::from(found), maps.join("Ladder2019Season3/AcropolisLE.SC2Map"));
+    assert!(matches!(
+        config.find_map(sc2_path, "Missing"),
+        Err(LaunchError::MapNotFound { .. })
+    ));
+
+    // Custom directories are checked first
+    let config = LaunchConfig {
+        map_dirs: vec![custom.clone()],
+        ..Default::default()
+    };
+    let found = config.find_map(sc2_path, "Simple64").unwrap();
+    assert_eq!(PathBuf::from(found), custom.join("Simple64.SC2Map"));
+
+    let empty = temp_dir("no_maps");
+    assert!(matches!(
+        config.find_map(empty.to_str().unwrap(), "Unknown"),
+        Err(LaunchError::MapNotFound { .. })
+    ));
+    assert!(matches!(
+        LaunchConfig::default().find_map(empty.to_str().unwrap(), "Unknown"),
+        Err(LaunchError::NoMapDirectories(_))
+    ));
+
+    fs::remove_dir_all(sc2).unwrap();
+    fs::remove_dir_all(empty).unwrap();
+}
+
+#[test]
+fn test_missing_installation() {
+    let sc2 = temp_dir("versions");
+    let config = LaunchConfig {
+        sc2_path: Some(sc2.join("missing")),
+        ..Default::default()
+    };
+    assert!(matches!(config.find_sc2(), Err(LaunchError::InstallationNotFound(_))));
+
+    let sc2_path = sc2.to_str().unwrap();
+    assert!(matches!(
+        config.version_info(sc2_path, None),
+        Err(LaunchError::NoVersions(_))
+    ));
+    fs::create_dir_all(sc2.join("Versions/Base75689")).unwrap();
+    fs::create_dir_all(sc2.join("Versions/Base81102")).unwrap();
+    assert_eq!(config.version_info(sc2_path, None).unwrap().base_version, 81102);
+    assert_eq!(
+        config.version_info(sc2_path, Some("4.10")).unwrap().base_version,
+        75689
+    );
+    fs::remove_dir_all(sc2).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_find_map_skips_unreadable_dirs() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let sc2 = temp_dir("unreadable_maps");
+    let maps = sc2.join("Maps");
+    // Sorted before the directory with the map
+    let locked = maps.join("Locked");
+    fs::create_dir_all(&locked).unwrap();
+    fs::create_dir_all(maps.join("Open")).unwrap();
+    fs::write(maps.join("Open/AcropolisLE.SC2Map"), b"").unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+
+    let found = LaunchConfig::default().find_map(sc2.to_str().unwrap(), "AcropolisLE");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+    assert_eq!(PathBuf::from(found.unwrap()), maps.join("Open/AcropolisLE.SC2Map"));
+
+    fs::remove_dir_all(sc2).unwrap();
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use common::{add_building, bot, observation, open_map, raw_unit};
+use rust_sc2::{
+    bot::{Bot, Expansion},
+    game_data::GameData,
+    layout::{BuildingSlot, LayoutOptions, SlotKind},
+    prelude::*,
+};
+
+#[test]
+fn test_layout_requires_expansion() {
+    let mut bot = Bot::default();
+    let layout = bot.plan_layout(Point2::new(30.5, 30.5), Race::Terran, LayoutOptions::default());
+    assert!(layout.is_none());
+}
+
+#[test]
+fn test_slot_kind() {
+    assert!(SlotKind::Production.is_production());
+    assert!(SlotKind::Supply.is_supply());
+    assert!(!SlotKind::Defense.is_supply());
+    let options = LayoutOptions::default();
+    assert!(options.production > 0 && options.supply > 0);
+}
+
+const BASE: Point2 = Point2 { x: 32.5, y: 32.5 };
+
+// Bot on an open map with single expansion, which has mineral line to the right of townhall
+fn expansion_bot() -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    add_building(
+        &mut data,
+        UnitTypeId::Barracks,
+        Race::Terran,
+        AbilityId::TerranBuildBarracks,
+        1.5,
+    );
+    bot.game_data = data.into();
+    open_map(&mut bot);
+    bot.init_pathfinding();
+
+    let minerals = (0..8)
+        .map(|i| {
+            raw_unit(
+                i + 1,
+                UnitTypeId::MineralField,
+                0,
+                Point2::new(40.0, 29.5 + i as f32),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut expansion = Expansion {
+        loc: BASE,
+        center: Point2::new(40.0, 33.0),
+        minerals: Default::default(),
+        geysers: Default::default(),
+        alliance: Alliance::Neutral,
+        base: None,
+    };
+    for u in &minerals {
+        expansion.minerals.insert(u.get_tag());
+    }
+    bot.expansions = vec![expansion];
+    bot.observe(&observation(1, minerals), &[]);
+    bot
+}
+
+fn tiles(slot: &BuildingSlot) -> Vec<(i32, i32)> {
+    let mut areas = vec![(slot.pos, slot.size as i32)];
+    if slot.addon {
+        areas.push((slot.pos.offset(2.5, -0.5), 2));
+    }
+    areas
+        .into_iter()
+        .flat_map(|(pos, size)| {
+            let left = (pos.x - size as f32 / 2.0).round() as i32;
+            let bottom = (pos.y - size as f32 / 2.0).round() as i32;
+            (left..left + size).flat_map(move |x| (bottom..bottom + size).map(move |y| (x, y)))
+        })
+        .collect()
+}
+
+#[test]
+fn test_terran_layout() {
+    let mut bot = expansion_bot();
+    let options = LayoutOptions::default();
+    let layout = bot.plan_layout(BASE, Race::Terran, options).unwrap();
+
+    assert_eq!(layout.base, BASE);
+    assert_eq!(layout.slots_of(SlotKind::Production).count(), options.production);
+    assert_eq!(layout.slots_of(SlotKind::Supply).count(), options.supply);
+    assert_eq!(layout.slots_of(SlotKind::Defense).count(), options.defense);
+    assert!(layout
+        .slots_of(SlotKind::Production)
+        .all(|s| s.addon && s.size == 3));
+
+    // Paths from townhall to minerals are kept free
+    let mining = bot
+        .units
+        .mineral_fields
+        .iter()
+        .flat_map(|u| (0..=10).map(move |i| BASE + (u.position() - BASE) * (i as f32 / 10.0)))
+        .map(|pos| (pos.x as i32, pos.y as i32))
+        .collect::<Vec<_>>();
+
+    for (i, slot) in layout.slots.iter().enumerate() {
+        assert!(slot.is_free(&bot));
+        assert!(slot.pos.distance(BASE) <= options.radius);
+        for (x, y) in tiles(slot) {
+            assert!(!mining.contains(&(x, y)), "{:?} is on mineral line", slot);
+            // Slots don't touch each other
+            for other in &layout.slots[i + 1..] {
+                assert!(
+                    tiles(other)
+                        .iter()
+                        .all(|(ox, oy)| (ox - x).abs() > 1 || (oy - y).abs() > 1),
+                    "{:?} touches {:?}",
+                    slot,
+                    other
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_protoss_layout() {
+    let mut bot = expansion_bot();
+    let layout = bot
+        .plan_layout(BASE, Race::Protoss, LayoutOptions::default())
+        .unwrap();
+
+    let pylons = layout.slots_of(SlotKind::Supply).collect::<Vec<_>>();
+    assert!(!pylons.is_empty());
+    for slot in layout.slots_of(SlotKind::Production) {
+        assert!(!slot.addon);
+        assert!(
+            pylons.iter().any(|p| p.pos.distance(slot.pos) <= 6.5),
+            "{:?} isn't powered",
+            slot
+        );
+    }
+}
+
+#[test]
+fn test_occupied_slot() {
+    let mut bot = expansion_bot();
+    let layout = bot
+        .plan_layout(BASE, Race::Terran, LayoutOptions::default())
+        .unwrap();
+    let first = layout.free_slot(&bot, SlotKind::Production).unwrap().pos;
+
+    let mut units = bot
+        .units
+        .mineral_fields
+        .iter()
+        .map(|u| raw_unit(u.tag(), UnitTypeId::MineralField, 0, u.position()))
+        .collect::<Vec<_>>();
+    units.push(raw_unit(100, UnitTypeId::Barracks, 1, first));
+    bot.observe(&observation(2, units), &[]);
+
+    let next = layout.free_slot(&bot, SlotKind::Production).unwrap();
+    assert_ne!(next.pos, first);
+    assert!(next.is_free(&bot));
+}
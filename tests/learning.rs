@@ -0,0 +1,84 @@
+use rust_sc2::{
+    learning::{GameRecord, LearningStore},
+    prelude::*,
+};
+use std::{fs, path::PathBuf};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust_sc2_{}_{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn record(result: GameResult, strategy: &str) -> GameRecord {
+    GameRecord {
+        result,
+        strategy: strategy.to_string(),
+        map: "Map\tName".to_string(),
+        duration: 300.0,
+        enemy_strategies: vec!["EarlyPool".to_string(), "Proxy, Rax".to_string()],
+    }
+}
+
+#[test]
+fn test_store_roundtrip() {
+    let dir = temp_dir("learning");
+    let mut store = LearningStore::load(&dir, "opp/1").unwrap();
+    assert!(store.games.is_empty());
+    assert_eq!(store.path(), dir.join("opp%2F1.txt"));
+    // Similar ids don't share the file
+    let other = LearningStore::load(&dir, "opp_1").unwrap();
+    assert_eq!(other.path(), dir.join("opp_1.txt"));
+
+    store.record(record(GameResult::Victory, "Rush")).unwrap();
+    store.append(&record(GameResult::Defeat, "Macro")).unwrap();
+    assert_eq!(store.games.len(), 1);
+
+    let loaded = LearningStore::load(&dir, "opp/1").unwrap();
+    assert_eq!(loaded.games.len(), 2);
+    assert_eq!(loaded.games[0].result, GameResult::Victory);
+    assert_eq!(loaded.games[1].strategy, "Macro");
+    assert_eq!(loaded.games[1].map, "Map Name");
+    assert_eq!(loaded.games[1].enemy_strategies, ["EarlyPool", "Proxy  Rax"]);
+    assert_eq!(loaded.enemy_strategies()["EarlyPool"], 2);
+    assert!(LearningStore::load(&dir, "opp_1").unwrap().games.is_empty());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_stats() {
+    let dir = temp_dir("learning_stats");
+    let mut store = LearningStore::load(&dir, "").unwrap();
+    assert_eq!(store.opponent_id, "unknown");
+    store.games = vec![
+        record(GameResult::Victory, "Rush"),
+        record(GameResult::Tie, "Rush"),
+        record(GameResult::Defeat, "Rush"),
+        record(GameResult::Undecided, "Rush"),
+    ];
+    let stats = store.stats("Rush");
+    assert_eq!(stats.games, 3);
+    assert_eq!(stats.wins, 1.5);
+    assert_eq!(stats.win_rate(), 0.5);
+    assert_eq!(store.stats("Macro").win_rate(), 0.0);
+}
+
+#[test]
+fn test_strategy_selection() {
+    let dir = temp_dir("learning_select");
+    let mut store = LearningStore::load(&dir, "opp").unwrap();
+    let strategies = ["Rush", "Macro"];
+    assert_eq!(store.select_ucb1(&[], 2f32.sqrt()), None);
+
+    store.games = vec![record(GameResult::Victory, "Rush")];
+    // Untried strategy goes first
+    assert_eq!(store.select_ucb1(&strategies, 2f32.sqrt()), Some("Macro"));
+    assert_eq!(store.select_epsilon_greedy(&strategies, 0.0), Some("Rush"));
+
+    store.games.push(record(GameResult::Defeat, "Macro"));
+    store.games.push(record(GameResult::Defeat, "Macro"));
+    assert_eq!(store.select_ucb1(&strategies, 2f32.sqrt()), Some("Rush"));
+    assert_eq!(store.select_epsilon_greedy(&strategies, 0.0), Some("Rush"));
+    assert!(store.select_epsilon_greedy(&strategies, 1.0).is_some());
+}
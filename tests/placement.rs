@@ -0,0 +1,131 @@
+mod common;
+
+use common::{add_building, bot, observation, open_map, raw_unit};
+use rust_sc2::{bot::Bot, game_data::GameData, prelude::*};
+
+#[test]
+fn test_placement_without_grid() {
+    let bot = Bot::default();
+    let pos = Point2::new(50.5, 50.5);
+    let local = PlacementOptions {
+        confirm: false,
+        ..Default::default()
+    };
+
+    assert!(!bot.placement.is_free((50, 50)));
+    assert!(!bot.placement.is_area_free(pos, 3));
+    assert!(!bot.can_place_local(UnitTypeId::Barracks, pos));
+    assert_eq!(bot.find_placement(UnitTypeId::Barracks, pos, local), None);
+    // Unknown buildings aren't queried
+    assert!(!bot.can_place(UnitTypeId::Barracks, pos));
+    assert_eq!(bot.can_place_some(vec![(UnitTypeId::Pylon, pos)]), vec![false]);
+    assert_eq!(
+        bot.find_placement(UnitTypeId::Barracks, pos, Default::default()),
+        None
+    );
+}
+
+#[test]
+fn test_default_options_are_confirmed() {
+    let options = PlacementOptions::default();
+    assert!(options.confirm);
+    assert!(!options.addon);
+}
+
+// Terran bot on an open map with given units
+fn terran_bot(units: Vec<sc2_proto::raw::Unit>) -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    for (id, ability, radius) in [
+        (UnitTypeId::Barracks, AbilityId::TerranBuildBarracks, 1.5),
+        (UnitTypeId::SupplyDepot, AbilityId::TerranBuildSupplyDepot, 1.0),
+        (
+            UnitTypeId::CommandCenter,
+            AbilityId::TerranBuildCommandCenter,
+            2.5,
+        ),
+    ] {
+        add_building(&mut data, id, Race::Terran, ability, radius);
+    }
+    bot.game_data = data.into();
+    bot.race = Race::Terran;
+    open_map(&mut bot);
+    bot.observe(&observation(1, units), &[]);
+    bot
+}
+
+#[test]
+fn test_structure_footprint() {
+    let barracks = raw_unit(1, UnitTypeId::Barracks, 1, Point2::new(20.5, 20.5));
+    let bot = terran_bot(vec![barracks]);
+
+    for x in 19..=21 {
+        for y in 19..=21 {
+            assert!(!bot.placement.is_free((x, y)), "({}, {}) is free", x, y);
+        }
+    }
+    assert!(bot.placement.is_free((18, 20)));
+    assert!(bot.placement.is_free((22, 20)));
+
+    assert!(bot.can_place_local(UnitTypeId::SupplyDepot, Point2::new(23.0, 21.0)));
+    assert!(!bot.can_place_local(UnitTypeId::SupplyDepot, Point2::new(22.0, 21.0)));
+    assert!(!bot.can_place_local(UnitTypeId::Barracks, Point2::new(20.5, 22.5)));
+    assert!(bot.can_place_local(UnitTypeId::Barracks, Point2::new(20.5, 23.5)));
+}
+
+#[test]
+fn test_addon_clearance() {
+    let barracks = raw_unit(1, UnitTypeId::Barracks, 1, Point2::new(20.5, 20.5));
+    let bot = terran_bot(vec![barracks]);
+    let near = Point2::new(16.5, 20.5);
+    let options = PlacementOptions {
+        confirm: false,
+        step: 1,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        bot.find_placement(UnitTypeId::Barracks, near, options),
+        Some(near)
+    );
+
+    let options = PlacementOptions {
+        addon: true,
+        ..options
+    };
+    let pos = bot.find_placement(UnitTypeId::Barracks, near, options).unwrap();
+    assert_ne!(pos, near);
+    assert!(bot.can_place_local(UnitTypeId::Barracks, pos));
+    // Addon is placed to the right of the building
+    assert!(bot.placement.is_area_free(pos.offset(2.5, -0.5), 2));
+}
+
+#[test]
+fn test_resource_exclusion() {
+    let minerals = raw_unit(1, UnitTypeId::MineralField, 0, Point2::new(40.0, 20.5));
+    let bot = terran_bot(vec![minerals]);
+
+    assert!(!bot.placement.is_free((39, 20)));
+    assert!(!bot.placement.is_free((40, 20)));
+    assert!(!bot.can_place_local(UnitTypeId::CommandCenter, Point2::new(34.5, 20.5)));
+    assert!(bot.can_place_local(UnitTypeId::CommandCenter, Point2::new(30.5, 20.5)));
+    // Only townhalls keep the distance from resources
+    assert!(bot.can_place_local(UnitTypeId::SupplyDepot, Point2::new(37.0, 21.0)));
+}
+
+#[test]
+fn test_local_check_before_query() {
+    let barracks = raw_unit(1, UnitTypeId::Barracks, 1, Point2::new(20.5, 20.5));
+    let bot = terran_bot(vec![barracks]);
+
+    // Positions blocked on local grid aren't sent to the server (which would panic without api)
+    assert!(!bot.can_place(UnitTypeId::Barracks, Point2::new(20.5, 20.5)));
+    let options = PlacementOptions {
+        max_distance: 4,
+        ..Default::default()
+    };
+    assert_eq!(
+        bot.find_placement(UnitTypeId::Barracks, Point2::new(200.5, 200.5), options),
+        None
+    );
+}
@@ -0,0 +1,264 @@
+mod common;
+
+use common::{abilities, bot, observation, open_map, order, raw_unit, structure_data, unit_data};
+use rust_sc2::{
+    action::Target,
+    bot::Bot,
+    game_data::{GameData, TargetType, Weapon},
+    prelude::*,
+    production::Production,
+};
+use rustc_hash::FxHashMap;
+use sc2_proto::{raw::PowerSource, sc2api::ResponseObservation};
+
+#[test]
+fn test_production_ratios() {
+    let mut production = Production::default();
+    production.set_ratio(UnitTypeId::Zealot, 2.0);
+    production.set_ratio(UnitTypeId::Stalker, 1.0);
+    production.set_ratio(UnitTypeId::Zealot, 3.0);
+    assert_eq!(
+        production.ratios().collect::<Vec<_>>(),
+        [(UnitTypeId::Zealot, 3.0), (UnitTypeId::Stalker, 1.0)]
+    );
+
+    assert!(production.remove(UnitTypeId::Zealot));
+    assert!(!production.remove(UnitTypeId::Zealot));
+    production.clear();
+    assert_eq!(production.ratios().count(), 0);
+}
+
+#[test]
+fn test_production_priorities() {
+    let mut production = Production::default();
+    production.set_ratio(UnitTypeId::Zealot, 2.0);
+    production.set_ratio(UnitTypeId::Stalker, 1.0);
+    production.set_ratio(UnitTypeId::Sentry, 0.0);
+
+    let counts = FxHashMap::from_iter([(UnitTypeId::Zealot, 4), (UnitTypeId::Stalker, 1)]);
+    let count = |u| counts.get(&u).copied().unwrap_or(0);
+    assert_eq!(
+        production.priorities(count),
+        [UnitTypeId::Stalker, UnitTypeId::Zealot]
+    );
+
+    let counts = FxHashMap::from_iter([(UnitTypeId::Zealot, 2), (UnitTypeId::Stalker, 3)]);
+    let count = |u| counts.get(&u).copied().unwrap_or(0);
+    assert_eq!(
+        production.priorities(count),
+        [UnitTypeId::Zealot, UnitTypeId::Stalker]
+    );
+
+    production.set_limit(UnitTypeId::Zealot, 2);
+    assert_eq!(production.priorities(count), [UnitTypeId::Stalker]);
+}
+
+#[test]
+fn test_run_production_without_producers() {
+    let mut bot = Bot::default();
+    bot.minerals = 1000;
+    bot.production.set_ratio(UnitTypeId::Marine, 1.0);
+    bot.production.inject = true;
+    bot.production.chrono = true;
+
+    let report = bot.run_production();
+    assert!(report.trained.is_empty());
+    assert!(report.injected.is_empty() && report.chrono_boosted.is_empty());
+    assert_eq!(bot.minerals, 1000);
+    // Settings are kept after run
+    assert_eq!(bot.production.ratios().count(), 1);
+    assert!(bot.warp_in_positions(Point2::new(10.0, 10.0), 4).is_empty());
+}
+
+// Bot with game data of given units trained with given abilities and structures
+fn production_bot(units: &[(UnitTypeId, AbilityId)], structures: &[UnitTypeId]) -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 5,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 0.1,
+        speed: 1.0,
+    };
+    for (id, ability) in units {
+        let mut unit = unit_data(*id, Race::Random, vec![], 3.15, weapon.clone());
+        unit.ability = Some(*ability);
+        data.units.insert(*id, unit);
+    }
+    for id in structures {
+        data.units.insert(*id, structure_data(*id, Race::Random));
+    }
+    bot.game_data = data.into();
+    open_map(&mut bot);
+    bot
+}
+
+// Steps bot and gives it enough resources for everything
+fn observe(bot: &mut Bot, obs: &ResponseObservation, available: &[(u64, &[AbilityId])]) {
+    bot.observe(obs, &abilities(available));
+    bot.minerals = 1000;
+    bot.vespene = 1000;
+    bot.supply_left = 100;
+}
+
+fn with_energy(mut unit: sc2_proto::raw::Unit, energy: f32) -> sc2_proto::raw::Unit {
+    unit.set_energy(energy);
+    unit
+}
+
+fn with_order(mut unit: sc2_proto::raw::Unit, ability: AbilityId, target: Target) -> sc2_proto::raw::Unit {
+    unit.mut_orders().push(order(ability, target, 0.0));
+    unit
+}
+
+#[test]
+fn test_larva_production() {
+    let mut bot = production_bot(&[(UnitTypeId::Zergling, AbilityId::LarvaTrainZergling)], &[]);
+    // Burrowed zerglings are counted too
+    let mut data = (*bot.game_data).clone();
+    let burrowed = data.units[&UnitTypeId::Zergling].clone();
+    data.units.insert(UnitTypeId::ZerglingBurrowed, burrowed);
+    bot.game_data = data.into();
+    let units = vec![
+        raw_unit(1, UnitTypeId::Larva, 1, Point2::new(20.0, 20.0)),
+        raw_unit(2, UnitTypeId::Larva, 1, Point2::new(21.0, 20.0)),
+        raw_unit(3, UnitTypeId::Larva, 1, Point2::new(22.0, 20.0)),
+    ];
+    let train: &[AbilityId] = &[AbilityId::LarvaTrainZergling];
+    // The third larva has no ability, e.g. it's already morphing
+    observe(&mut bot, &observation(1, units), &[(1, train), (2, train)]);
+    bot.production.set_ratio(UnitTypeId::Zergling, 1.0);
+
+    let report = bot.run_production();
+    let mut tags = report.trained.iter().map(|(_, tag)| *tag).collect::<Vec<_>>();
+    tags.sort_unstable();
+    assert_eq!(tags, [1, 2]);
+    assert!(report
+        .trained
+        .iter()
+        .all(|(unit, _)| *unit == UnitTypeId::Zergling));
+    // Larva morphs into a pair of zerglings
+    assert_eq!(bot.minerals, 600);
+}
+
+#[test]
+fn test_reactor_production() {
+    let mut bot = production_bot(
+        &[(UnitTypeId::Marine, AbilityId::BarracksTrainMarine)],
+        &[UnitTypeId::Barracks, UnitTypeId::BarracksReactor],
+    );
+    let mut with_reactor = raw_unit(1, UnitTypeId::Barracks, 1, Point2::new(20.5, 20.5));
+    with_reactor.set_add_on_tag(3);
+    let busy = with_order(
+        raw_unit(2, UnitTypeId::Barracks, 1, Point2::new(20.5, 30.5)),
+        AbilityId::BarracksTrainMarine,
+        Target::None,
+    );
+    let units = vec![
+        with_reactor,
+        busy,
+        raw_unit(3, UnitTypeId::BarracksReactor, 1, Point2::new(23.0, 20.0)),
+    ];
+    let train: &[AbilityId] = &[AbilityId::BarracksTrainMarine];
+    observe(&mut bot, &observation(1, units), &[(1, train), (2, train)]);
+    bot.production.set_ratio(UnitTypeId::Marine, 1.0);
+
+    // Barracks with reactor trains two units, busy one without reactor nothing
+    let report = bot.run_production();
+    assert_eq!(report.trained, [(UnitTypeId::Marine, 1), (UnitTypeId::Marine, 1)]);
+}
+
+#[test]
+fn test_warpgate_production() {
+    let mut bot = production_bot(
+        &[(UnitTypeId::Zealot, AbilityId::GatewayTrainZealot)],
+        &[UnitTypeId::WarpGate, UnitTypeId::Pylon],
+    );
+    let units = vec![
+        raw_unit(1, UnitTypeId::WarpGate, 1, Point2::new(10.5, 10.5)),
+        raw_unit(2, UnitTypeId::WarpGate, 1, Point2::new(10.5, 14.5)),
+        raw_unit(3, UnitTypeId::WarpGate, 1, Point2::new(10.5, 18.5)),
+        raw_unit(4, UnitTypeId::Pylon, 1, Point2::new(40.0, 40.0)),
+    ];
+    let mut obs = observation(1, units);
+    let mut pylon = PowerSource::new();
+    pylon.mut_pos().set_x(40.0);
+    pylon.mut_pos().set_y(40.0);
+    pylon.set_radius(6.5);
+    pylon.set_tag(4);
+    obs.mut_observation()
+        .mut_raw_data()
+        .mut_player()
+        .mut_power_sources()
+        .push(pylon);
+    // The third gate is on cooldown
+    let warp: &[AbilityId] = &[AbilityId::WarpGateTrainZealot];
+    observe(&mut bot, &obs, &[(1, warp), (2, warp)]);
+    bot.production.set_ratio(UnitTypeId::Zealot, 1.0);
+    bot.production.warp_in_near = Some(Point2::new(30.0, 40.0));
+
+    let report = bot.run_production();
+    let mut tags = report.trained.iter().map(|(_, tag)| *tag).collect::<Vec<_>>();
+    tags.sort_unstable();
+    assert_eq!(tags, [1, 2]);
+    assert!(bot
+        .warp_in_positions(Point2::new(30.0, 40.0), 2)
+        .iter()
+        .all(|pos| pos.distance(Point2::new(40.0, 40.0)) < 6.5 && pos.x < 40.0));
+}
+
+#[test]
+fn test_inject() {
+    let mut bot = production_bot(&[], &[UnitTypeId::Hatchery]);
+    let units = vec![
+        raw_unit(1, UnitTypeId::Hatchery, 1, Point2::new(20.5, 20.5)),
+        raw_unit(2, UnitTypeId::Hatchery, 1, Point2::new(40.5, 20.5)),
+        with_energy(raw_unit(10, UnitTypeId::Queen, 1, Point2::new(22.0, 23.0)), 30.0),
+        // Queen is on the way to inject, but hasn't spent energy yet
+        with_order(
+            with_energy(raw_unit(11, UnitTypeId::Queen, 1, Point2::new(30.0, 23.0)), 30.0),
+            AbilityId::EffectInjectLarva,
+            Target::Tag(2),
+        ),
+        with_energy(raw_unit(12, UnitTypeId::Queen, 1, Point2::new(38.0, 23.0)), 30.0),
+    ];
+    observe(&mut bot, &observation(1, units), &[]);
+    bot.production.inject = true;
+
+    let report = bot.run_production();
+    assert_eq!(report.injected, [1]);
+}
+
+#[test]
+fn test_chrono() {
+    let mut bot = production_bot(&[], &[UnitTypeId::Nexus, UnitTypeId::Gateway, UnitTypeId::Forge]);
+    let units = vec![
+        with_energy(raw_unit(1, UnitTypeId::Nexus, 1, Point2::new(20.5, 20.5)), 100.0),
+        // Order was given on the last step, buff doesn't appear yet
+        with_order(
+            with_energy(raw_unit(2, UnitTypeId::Nexus, 1, Point2::new(40.5, 20.5)), 100.0),
+            AbilityId::EffectChronoBoostEnergyCost,
+            Target::Tag(4),
+        ),
+        with_order(
+            raw_unit(3, UnitTypeId::Gateway, 1, Point2::new(20.5, 30.5)),
+            AbilityId::GatewayTrainZealot,
+            Target::None,
+        ),
+        with_order(
+            raw_unit(4, UnitTypeId::Forge, 1, Point2::new(24.5, 30.5)),
+            AbilityId::ForgeResearchProtossGroundWeaponsLevel1,
+            Target::None,
+        ),
+        raw_unit(5, UnitTypeId::Gateway, 1, Point2::new(28.5, 30.5)),
+    ];
+    observe(&mut bot, &observation(1, units), &[]);
+    bot.production.chrono = true;
+    bot.production.chrono_priority = vec![UnitTypeId::Forge];
+
+    // Forge goes first, but it's already being boosted
+    let report = bot.run_production();
+    assert_eq!(report.chrono_boosted, [3]);
+}
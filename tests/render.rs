@@ -0,0 +1,48 @@
+#![cfg(feature = "render")]
+
+use ndarray::Array2;
+use rust_sc2::{
+    bot::Bot,
+    pixel_map::Pixel,
+    render::{MapRender, RenderLayer},
+};
+
+#[test]
+fn test_render_empty_bot() {
+    let bot = Bot::default();
+    let image = MapRender::default().render(&bot);
+    assert_eq!(image.dimensions(), (0, 0));
+}
+
+#[test]
+fn test_render_placement() {
+    let mut bot = Bot::default();
+    bot.game_info.pathing_grid = Array2::from_elem((8, 6), Pixel::Empty);
+    let mut grid = Array2::from_elem((8, 6), Pixel::Set);
+    grid[(1, 0)] = Pixel::Empty;
+    bot.game_info.placement_grid = grid;
+
+    let render = MapRender {
+        layers: vec![RenderLayer::Placement, RenderLayer::Zones, RenderLayer::Units],
+        scale: 2,
+    };
+    let image = render.render(&bot);
+    assert_eq!(image.dimensions(), (16, 12));
+    // Map is flipped vertically to have north on top
+    assert_ne!(image.get_pixel(2, 11).0, [0, 0, 0]);
+    assert_ne!(image.get_pixel(3, 10).0, [0, 0, 0]);
+    assert_eq!(image.get_pixel(2, 0).0, [0, 0, 0]);
+    assert_eq!(image.get_pixel(0, 11).0, [0, 0, 0]);
+}
+
+#[test]
+fn test_save_png() {
+    let mut bot = Bot::default();
+    bot.game_info.pathing_grid = Array2::from_elem((4, 4), Pixel::Empty);
+
+    let path = std::env::temp_dir().join("rust_sc2_render_test.png");
+    MapRender::default().save(&bot, &path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert_eq!(&bytes[1..4], b"PNG");
+    std::fs::remove_file(path).unwrap();
+}
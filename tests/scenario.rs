@@ -0,0 +1,189 @@
+mod common;
+
+use common::{bot, observation, raw_unit, structure_data, units_data};
+use rust_sc2::{
+    bot::Bot,
+    game_data::{Attribute, TargetType, Weapon},
+    prelude::*,
+    scenario::{army_value, RoundOutcome, RoundResult, RoundStep, RoundTracker, Scenario, ScenarioReport},
+};
+
+fn round(my_value_left: f32, enemy_value_left: f32, outcome: RoundOutcome) -> RoundResult {
+    RoundResult {
+        my_value: 500.0,
+        enemy_value: 1000.0,
+        my_value_left,
+        enemy_value_left,
+        duration: 20.0,
+        outcome,
+    }
+}
+
+#[test]
+fn test_scenario_builder() {
+    let scenario = Scenario::new("stalkers")
+        .my(UnitTypeId::Stalker, 4, Point2::new(-5.0, 0.0))
+        .enemy(UnitTypeId::Roach, 6, Point2::new(5.0, 0.0))
+        .enemy(UnitTypeId::Queen, 1, Point2::new(7.0, 0.0))
+        .upgrade_levels(1)
+        .duration(30.0)
+        .rounds(5);
+    assert_eq!(scenario.name, "stalkers");
+    assert_eq!(scenario.my_army.len(), 1);
+    assert_eq!(scenario.enemy_army.len(), 2);
+    assert_eq!(scenario.enemy_army[0].count, 6);
+    assert_eq!(scenario.upgrade_levels, 1);
+    assert_eq!(scenario.duration, 30.0);
+    assert_eq!(scenario.rounds, 5);
+}
+
+#[test]
+fn test_round_result() {
+    let result = round(250.0, 0.0, RoundOutcome::Win);
+    assert_eq!(result.my_survived(), 0.5);
+    assert_eq!(result.enemy_survived(), 0.0);
+    assert_eq!(result.trade(), 0.5);
+    assert!(result.outcome.is_win());
+}
+
+#[test]
+fn test_report() {
+    let empty = ScenarioReport::default();
+    assert_eq!(empty.win_rate(), 0.0);
+    assert_eq!(empty.mean_trade(), 0.0);
+
+    let report = ScenarioReport {
+        name: "test".to_string(),
+        rounds: vec![
+            round(250.0, 0.0, RoundOutcome::Win),
+            round(0.0, 500.0, RoundOutcome::Loss),
+            round(500.0, 0.0, RoundOutcome::Win),
+            round(100.0, 100.0, RoundOutcome::Timeout),
+        ],
+    };
+    assert_eq!(report.count(RoundOutcome::Win), 2);
+    assert_eq!(report.count(RoundOutcome::Draw), 0);
+    assert_eq!(report.win_rate(), 0.5);
+    assert_eq!(report.mean_value_left(), 212.5);
+    assert_eq!(report.mean_enemy_value_left(), 150.0);
+    assert!((report.mean_trade() - 0.275).abs() < 1e-6);
+    assert_eq!(army_value(&Units::new()), 0.0);
+}
+
+fn scenario_bot() -> Bot {
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 6,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 5.0,
+        speed: 0.61,
+    };
+    let mut data = units_data(
+        Race::Random,
+        &[Attribute::Light, Attribute::Biological],
+        &[UnitTypeId::Marine, UnitTypeId::Zergling, UnitTypeId::SCV].map(|id| (id, weapon.clone())),
+    );
+    for id in [UnitTypeId::CommandCenter, UnitTypeId::Hatchery] {
+        data.units.insert(id, structure_data(id, Race::Random));
+    }
+    let mut bot = bot();
+    bot.game_data = data.into();
+    bot
+}
+
+// Structures and townhall production which stay on the map during the whole game
+fn base(scv: u64, larva: u64) -> Vec<sc2_proto::raw::Unit> {
+    vec![
+        raw_unit(1, UnitTypeId::CommandCenter, 1, Point2::new(10.5, 10.5)),
+        raw_unit(2, UnitTypeId::Hatchery, 2, Point2::new(50.5, 50.5)),
+        raw_unit(scv, UnitTypeId::SCV, 1, Point2::new(12.0, 12.0)),
+        raw_unit(larva, UnitTypeId::Larva, 2, Point2::new(50.0, 49.0)),
+    ]
+}
+
+fn with_base(units: Vec<sc2_proto::raw::Unit>, scv: u64, larva: u64) -> Vec<sc2_proto::raw::Unit> {
+    let mut all = base(scv, larva);
+    all.extend(units);
+    all
+}
+
+#[test]
+fn test_round_phases() {
+    let scenario = Scenario::new("marines vs zergling")
+        .my(UnitTypeId::Marine, 2, Point2::new(-3.0, 0.0))
+        .enemy(UnitTypeId::Zergling, 1, Point2::new(3.0, 0.0));
+    let mut bot = scenario_bot();
+    let mut rounds = RoundTracker::new(0.0);
+    let mut step = |bot: &mut Bot, game_loop, units| {
+        bot.observe(&observation(game_loop, units), &[]);
+        rounds.step(bot, &scenario)
+    };
+    let marine = |tag, x| raw_unit(tag, UnitTypeId::Marine, 1, Point2::new(x, 32.0));
+    let zergling = |tag| raw_unit(tag, UnitTypeId::Zergling, 2, Point2::new(35.0, 32.0));
+
+    // Units left from the game start are being removed
+    assert_eq!(
+        step(&mut bot, 1, with_base(vec![marine(3, 20.0)], 10, 20)),
+        RoundStep::Setup
+    );
+    // Workers and larva don't block the round
+    assert_eq!(step(&mut bot, 2, base(10, 20)), RoundStep::Spawn);
+
+    // Not all units spawned yet, new worker and larva aren't scenario units
+    let spawned = vec![marine(4, 29.0), zergling(5)];
+    assert_eq!(step(&mut bot, 3, with_base(spawned, 11, 21)), RoundStep::Setup);
+    let spawned = vec![marine(4, 29.0), marine(6, 28.0), zergling(5)];
+    assert_eq!(step(&mut bot, 4, with_base(spawned, 11, 21)), RoundStep::Fight);
+    // Zergling produced by hatchery during the round isn't counted
+    let units = vec![marine(4, 29.0), marine(6, 28.0), zergling(7)];
+    match step(&mut bot, 5, with_base(units, 11, 21)) {
+        RoundStep::Finished(result) => {
+            assert_eq!(result.outcome, RoundOutcome::Win);
+            assert_eq!((result.my_value, result.enemy_value), (300.0, 150.0));
+            assert_eq!((result.my_value_left, result.enemy_value_left), (300.0, 0.0));
+        }
+        other => panic!("Round isn't finished: {:?}", other),
+    }
+
+    // Next round starts after units are removed or setup time is over
+    assert_eq!(
+        step(&mut bot, 6, with_base(vec![zergling(7)], 11, 21)),
+        RoundStep::Setup
+    );
+    assert_eq!(
+        step(&mut bot, 200, with_base(vec![zergling(7)], 11, 21)),
+        RoundStep::Spawn
+    );
+}
+
+#[test]
+fn test_round_timeout() {
+    let scenario = Scenario::new("stand off")
+        .my(UnitTypeId::Marine, 1, Point2::new(-3.0, 0.0))
+        .enemy(UnitTypeId::Zergling, 1, Point2::new(3.0, 0.0))
+        .duration(10.0);
+    let mut bot = scenario_bot();
+    let mut rounds = RoundTracker::new(0.0);
+    let mut step = |bot: &mut Bot, game_loop, units| {
+        bot.observe(&observation(game_loop, units), &[]);
+        rounds.step(bot, &scenario)
+    };
+    let armies = || {
+        vec![
+            raw_unit(3, UnitTypeId::Marine, 1, Point2::new(29.0, 32.0)),
+            raw_unit(4, UnitTypeId::Zergling, 2, Point2::new(35.0, 32.0)),
+        ]
+    };
+
+    assert_eq!(step(&mut bot, 1, vec![]), RoundStep::Spawn);
+    assert_eq!(step(&mut bot, 2, armies()), RoundStep::Fight);
+    assert_eq!(step(&mut bot, 100, armies()), RoundStep::Fight);
+    match step(&mut bot, 300, armies()) {
+        RoundStep::Finished(result) => {
+            assert_eq!(result.outcome, RoundOutcome::Timeout);
+            assert_eq!(result.trade(), 0.0);
+        }
+        other => panic!("Round isn't finished: {:?}", other),
+    }
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::{bot, observation, open_map, set_visibility};
+use rust_sc2::{
+    bot::{Bot, Expansion},
+    prelude::*,
+    scouting::ScoutTargetKind,
+};
+
+#[test]
+fn test_enemy_start_is_suggested_first() {
+    let mut bot = Bot::default();
+    bot.enemy_start = Point2::new(120.0, 120.0);
+
+    let targets = bot.scout_targets(5);
+    assert_eq!(targets.len(), 1);
+    assert_eq!(targets[0].last_seen, None);
+    assert_eq!(targets[0].kind, ScoutTargetKind::EnemyStart);
+}
+
+#[test]
+fn test_staleness_of_unseen_places() {
+    let bot = Bot::default();
+    let bases = bot.enemy_bases_staleness();
+    assert_eq!(bases.len(), 1);
+    assert!(bases[0].1 > 0.0);
+    assert!(bot.scouting.staleness(None) > bot.scouting.staleness(Some(0)));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(10.0, 10.0)), None);
+}
+
+#[test]
+fn test_scouting_updates() {
+    let mut bot = bot();
+    open_map(&mut bot);
+    bot.start_location = Point2::new(8.5, 8.5);
+    bot.enemy_start = Point2::new(56.5, 56.5);
+    bot.expansions = [
+        Point2::new(56.5, 56.5),
+        Point2::new(40.5, 40.5),
+        Point2::new(12.5, 40.5),
+    ]
+    .into_iter()
+    .map(|loc| Expansion {
+        loc,
+        center: loc,
+        minerals: Default::default(),
+        geysers: Default::default(),
+        alliance: Alliance::Neutral,
+        base: None,
+    })
+    .collect();
+
+    // Only the corner near own start is visible
+    let mut obs = observation(0, vec![]);
+    set_visibility(&mut obs, |x, y| x < 16 && y < 16);
+    bot.observe(&obs, &[]);
+    assert_eq!(bot.scouting.regions().count(), 64);
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(3.0, 3.0)), Some(0));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(15.0, 15.0)), Some(0));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(20.0, 3.0)), None);
+    assert_eq!(bot.scouting.expansion_last_seen(Point2::new(40.5, 40.5)), None);
+
+    // Region is seen when any of its tiles is visible
+    let mut obs = observation(224, vec![]);
+    set_visibility(&mut obs, |x, y| x < 3 && y < 3 || x == 23 && y == 0);
+    bot.observe(&obs, &[]);
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(1.0, 1.0)), Some(224));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(15.0, 15.0)), Some(0));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(20.0, 3.0)), Some(224));
+    assert_eq!(bot.scouting.region_last_seen(Point2::new(27.0, 3.0)), None);
+
+    // Enemy start is the most valuable, then expansion closer to opponent, then unseen regions
+    let targets = bot.scout_targets(4);
+    let kinds = targets.iter().map(|t| t.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            ScoutTargetKind::EnemyStart,
+            ScoutTargetKind::Expansion,
+            ScoutTargetKind::Expansion,
+            ScoutTargetKind::Region
+        ]
+    );
+    assert_eq!(targets[1].pos, Point2::new(40.5, 40.5));
+    assert_eq!(targets[3].last_seen, None);
+
+    // Seen places become less valuable with time passed since they were seen
+    bot.observe(&observation(672, vec![]), &[]);
+    let mut obs = observation(896, vec![]);
+    set_visibility(&mut obs, |_, _| false);
+    bot.observe(&obs, &[]);
+    assert_eq!(
+        bot.scouting.expansion_last_seen(Point2::new(40.5, 40.5)),
+        Some(672)
+    );
+    assert_eq!(bot.scouting.expansion_staleness(Point2::new(40.5, 40.5)), 10.0);
+    let targets = bot.scout_targets(100);
+    assert!(targets
+        .iter()
+        .all(|t| t.last_seen == Some(672) && t.value <= 3.0 * 10.0));
+}
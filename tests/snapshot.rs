@@ -0,0 +1,113 @@
+mod common;
+
+use rust_sc2::{bot::Bot, prelude::*, snapshot::Snapshot};
+
+#[test]
+fn test_snapshot_of_empty_bot() {
+    let bot = Bot::default();
+    bot.enemy_upgrades().insert(UpgradeId::Stimpack);
+
+    let snapshot = bot.snapshot();
+    assert_eq!(snapshot.game_loop, 0);
+    assert!(snapshot.units.is_empty());
+    assert!(snapshot.enemy_upgrades.contains(&UpgradeId::Stimpack));
+
+    // Snapshot owns its data
+    bot.enemy_upgrades().clear();
+    assert_eq!(snapshot.enemy_upgrades.len(), 1);
+}
+
+#[test]
+fn test_default_snapshot() {
+    let snapshot = Snapshot::default();
+    assert_eq!(snapshot.race, Race::Random);
+    assert_eq!(snapshot.visibility.dim(), (0, 0));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_types() {
+    use rust_sc2::{
+        action::ActionError,
+        bot::Expansion,
+        game_info::GameInfo,
+        game_state::{Effect, GameState},
+        geometry::Point3,
+        ramp::Ramp,
+        score::Score,
+        snapshot::UnitSnapshot,
+        units::Units,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+
+    fn serialize<T: Serialize>() {}
+    fn serde<T: Serialize + DeserializeOwned>() {}
+
+    serialize::<Unit>();
+    serialize::<Units>();
+    serde::<UnitSnapshot>();
+    serde::<Snapshot>();
+    serde::<GameState>();
+    serde::<GameInfo>();
+    serde::<Score>();
+    serde::<Expansion>();
+    serde::<Ramp>();
+    serde::<Point2>();
+    serde::<Point3>();
+    serde::<Effect>();
+    serde::<ActionError>();
+    serde::<sc2pathfinding::Choke>();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_json_round_trip() {
+    use common::{bot, observation, order, raw_unit, set_visibility};
+    use rust_sc2::action::Target;
+
+    let mut bot = bot();
+    bot.race = Race::Terran;
+    bot.enemy_race = Race::Protoss;
+    let mut marine = raw_unit(1, UnitTypeId::Marine, 1, Point2::new(10.5, 12.5));
+    marine
+        .mut_orders()
+        .push(order(AbilityId::Attack, Target::Tag(2), 0.0));
+    let mut zealot = raw_unit(2, UnitTypeId::Zealot, 2, Point2::new(14.5, 12.5));
+    zealot.set_shield(50.0);
+    zealot.set_shield_max(50.0);
+    let minerals = raw_unit(3, UnitTypeId::MineralField, 0, Point2::new(20.0, 30.5));
+
+    let mut obs = observation(100, vec![marine, zealot, minerals]);
+    set_visibility(&mut obs, |x, _| x < 32);
+    bot.observe(&obs, &[]);
+    bot.enemy_upgrades().insert(UpgradeId::Charge);
+
+    let snapshot = bot.snapshot();
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let restored = serde_json::from_str::<Snapshot>(&json).unwrap();
+
+    assert_eq!(restored.game_loop, 100);
+    assert_eq!(restored.race, Race::Terran);
+    assert_eq!(restored.enemy_race, Race::Protoss);
+    assert_eq!(restored.enemy_upgrades, snapshot.enemy_upgrades);
+    assert_eq!(restored.visibility, snapshot.visibility);
+    assert!(restored.visibility[(10, 10)].is_visible());
+    assert!(restored.visibility[(40, 10)].is_fogged());
+
+    assert_eq!(restored.units.len(), 3);
+    for (restored, original) in restored.units.iter().zip(&snapshot.units) {
+        assert_eq!(restored.tag, original.tag);
+        assert_eq!(restored.type_id, original.type_id);
+        assert_eq!(restored.alliance, original.alliance);
+        assert_eq!(restored.position, original.position);
+        assert_eq!(restored.health, original.health);
+        assert_eq!(restored.shield, original.shield);
+        assert_eq!(restored.orders.len(), original.orders.len());
+    }
+    let marine = restored.units.iter().find(|u| u.tag == 1).unwrap();
+    assert_eq!(marine.orders[0].ability, AbilityId::Attack);
+    assert_eq!(marine.orders[0].target, Target::Tag(2));
+
+    // Serialization is stable
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
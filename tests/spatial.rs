@@ -0,0 +1,207 @@
+mod common;
+
+use common::{ability_data, bot, observation, raw_unit, units_data};
+use rand::prelude::*;
+use rust_sc2::{
+    bot::Bot,
+    game_data::{Attribute, TargetType, Weapon},
+    prelude::*,
+    units::spatial::SpatialIndex,
+};
+
+fn random_points(n: usize, seed: u64) -> Vec<(u64, Point2, f32)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..n)
+        .map(|i| {
+            let pos = Point2::new(rng.gen_range(20.0..120.0), rng.gen_range(20.0..120.0));
+            (i as u64, pos, rng.gen_range(0.375..1.5))
+        })
+        .collect()
+}
+
+#[test]
+fn test_within_matches_linear_scan() {
+    let points = random_points(300, 1);
+    let index = SpatialIndex::from_points(points.iter().copied());
+    assert_eq!(index.len(), 300);
+    assert_eq!(index.max_radius(), index.max_reach());
+
+    for &(_, center, _) in points.iter().step_by(7) {
+        for distance in [0.5, 3.0, 10.0, 200.0] {
+            let mut found = index.within(center, distance).collect::<Vec<_>>();
+            found.sort_unstable();
+            let expected = points
+                .iter()
+                .filter(|(_, pos, _)| pos.distance_squared(center) <= distance * distance)
+                .map(|(tag, ..)| *tag)
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+    }
+}
+
+#[test]
+fn test_nearest_matches_linear_scan() {
+    let points = random_points(250, 2);
+    let index = SpatialIndex::from_points(points.iter().copied());
+
+    let targets = [
+        Point2::new(70.0, 70.0),
+        Point2::new(0.0, 0.0),
+        Point2::new(300.0, 50.0),
+        Point2::new(21.0, 119.0),
+    ];
+    for target in targets
+        .into_iter()
+        .chain(points.iter().map(|p| p.1.offset(0.3, -0.2)))
+    {
+        // Only even tags are members of queried collection, rank is position in it
+        let rank = |tag: u64| tag.is_multiple_of(2).then_some(tag as usize / 2);
+        let expected = points
+            .iter()
+            .filter(|(tag, ..)| tag.is_multiple_of(2))
+            .map(|(tag, pos, _)| (*tag as usize / 2, pos.distance_squared(target)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        assert_eq!(index.nearest(target, rank), expected);
+    }
+}
+
+#[test]
+fn test_nearest_prefers_lower_rank() {
+    let pos = Point2::new(50.0, 50.0);
+    let index = SpatialIndex::from_points(vec![(1, pos, 0.5), (2, pos, 0.5), (3, pos, 0.5)]);
+    let rank = |tag: u64| Some(10 - tag as usize);
+    assert_eq!(index.nearest(pos.offset(1.0, 0.0), rank), Some((7, 1.0)));
+}
+
+#[test]
+fn test_empty_index() {
+    let index = SpatialIndex::from_points(vec![]);
+    assert!(index.is_empty());
+    assert_eq!(index.within(Point2::new(1.0, 1.0), 10.0).count(), 0);
+    assert_eq!(index.nearest(Point2::new(1.0, 1.0), |_| Some(0)), None);
+
+    let mut units = Units::new();
+    assert!(!units.has_index());
+    units.build_index();
+    assert!(units.has_index());
+    assert!(units.closest(Point2::new(1.0, 1.0)).is_none());
+    assert!(units.closer(5.0, Point2::new(1.0, 1.0)).is_empty());
+}
+
+// Bot with own ghost, which can snipe, and enemy marines around it
+fn ghost_bot() -> Bot {
+    let weapon = |range| Weapon {
+        target: TargetType::Any,
+        damage: 10,
+        damage_bonus: vec![],
+        attacks: 1,
+        range,
+        speed: 1.0,
+    };
+    let mut data = units_data(
+        Race::Terran,
+        &[Attribute::Biological],
+        &[
+            (UnitTypeId::Ghost, weapon(6.0)),
+            (UnitTypeId::Marine, weapon(5.0)),
+        ],
+    );
+    let mut snipe = ability_data(AbilityId::EffectGhostSnipe, 0.0);
+    snipe.footprint_radius = None;
+    snipe.cast_range = Some(10.0);
+    data.abilities.insert(AbilityId::EffectGhostSnipe, snipe);
+
+    let mut bot = bot();
+    bot.game_data = data.into();
+    let center = Point2::new(32.0, 32.0);
+    let mut ghost = raw_unit(1, UnitTypeId::Ghost, 1, center);
+    ghost.set_energy(75.0);
+    let mut rng = StdRng::seed_from_u64(3);
+    let marines = (0..60).map(|i| {
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(1.0..16.0);
+        raw_unit(
+            i + 2,
+            UnitTypeId::Marine,
+            2,
+            center + Point2::new(angle.cos(), angle.sin()) * distance,
+        )
+    });
+    bot.observe(
+        &observation(1, std::iter::once(ghost).chain(marines).collect()),
+        &[],
+    );
+    bot
+}
+
+fn tags(units: &Units) -> Vec<u64> {
+    units.iter().map(|u| u.tag()).collect()
+}
+
+#[test]
+fn test_queries_match_without_index() {
+    let bot = ghost_bot();
+    let ghost = bot.units.my.units.first().unwrap();
+    let plain = bot.units.enemy.units.clone();
+    let mut indexed = plain.clone();
+    indexed.build_index();
+    assert!(!plain.has_index() && indexed.has_index());
+
+    // Snipe reaches further than weapon with upgrades
+    assert!(ghost.real_range_vs(plain.first().unwrap()) > 6.0 + 2.0);
+    for gap in [0.0, 1.0] {
+        let expected = tags(&plain.in_real_range_of(ghost, gap));
+        assert!(plain
+            .iter()
+            .any(|u| u.distance(ghost) > 9.0 && expected.contains(&u.tag())));
+        assert_eq!(tags(&indexed.in_real_range_of(ghost, gap)), expected);
+        assert_eq!(
+            tags(&indexed.in_range_of(ghost, gap)),
+            tags(&plain.in_range_of(ghost, gap))
+        );
+        assert_eq!(
+            tags(&indexed.in_real_range(ghost, gap)),
+            tags(&plain.in_real_range(ghost, gap))
+        );
+        assert_eq!(
+            tags(&indexed.in_range(ghost, gap)),
+            tags(&plain.in_range(ghost, gap))
+        );
+    }
+    for distance in [2.0, 7.5, 12.0, 30.0] {
+        assert_eq!(
+            tags(&indexed.closer(distance, ghost)),
+            tags(&plain.closer(distance, ghost))
+        );
+    }
+    assert_eq!(
+        indexed.closest(ghost).map(|u| u.tag()),
+        plain.closest(ghost).map(|u| u.tag())
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_mutation_drops_index() {
+    use rayon::prelude::*;
+
+    let bot = ghost_bot();
+    let mut units = bot.units.enemy.units.clone();
+    let check = |units: &mut Units, mutate: &dyn Fn(&mut Units)| {
+        units.build_index();
+        mutate(units);
+        assert!(!units.has_index());
+    };
+
+    check(&mut units, &|units| units.par_iter_mut().for_each(|_| {}));
+    check(&mut units, &|units| units.par_pairs_mut().for_each(|_| {}));
+    check(&mut units, &|units| {
+        (&mut *units).into_par_iter().for_each(|_| {})
+    });
+    let marines = units.clone();
+    check(&mut units, &|units| units.par_extend(marines.par_iter().cloned()));
+    check(&mut units, &|units| {
+        units.par_extend(marines.par_pairs().map(|(tag, u)| (*tag, u.clone())))
+    });
+}
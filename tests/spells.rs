@@ -0,0 +1,244 @@
+mod common;
+
+use common::{bot_with_units, observation, raw_unit};
+use rust_sc2::{
+    action::Target,
+    bot::Bot,
+    game_data::{Attribute, TargetType, Weapon},
+    game_state::{Alliance, Effect},
+    prelude::*,
+    spells::{find_spell_target, AreaSpell, SpellOptions, SpellTarget},
+};
+
+const SPELLS: [AreaSpell; 6] = [
+    AreaSpell::PsiStorm,
+    AreaSpell::FungalGrowth,
+    AreaSpell::Emp,
+    AreaSpell::CorrosiveBile,
+    AreaSpell::Snipe,
+    AreaSpell::Kd8Charge,
+];
+
+#[test]
+fn test_spell_properties() {
+    for spell in SPELLS {
+        assert!(spell.range() > 0.0, "{:?}", spell);
+        assert!(spell.damage() > 0, "{:?}", spell);
+        assert_eq!(spell.radius() == 0.0, spell.is_targeted(), "{:?}", spell);
+    }
+    assert!(AreaSpell::CorrosiveBile.delay() > AreaSpell::PsiStorm.delay());
+    assert!(AreaSpell::PsiStorm.buff().is_some());
+    assert!(AreaSpell::PsiStorm.friendly_fire());
+    assert!(!AreaSpell::FungalGrowth.friendly_fire());
+}
+
+#[test]
+fn test_spell_target() {
+    let position = Point2::new(30.0, 40.0);
+    let target = SpellTarget {
+        position,
+        tag: None,
+        value: 200.0,
+        count: 3,
+    };
+    assert!(matches!(target.target(), Target::Pos(pos) if pos == position));
+
+    let target = SpellTarget {
+        tag: Some(42),
+        ..target
+    };
+    assert!(matches!(target.target(), Target::Tag(42)));
+
+    let options = SpellOptions::default();
+    assert_eq!(options.min_value, 0.0);
+    assert!(options.weights.is_empty());
+}
+
+const CASTER: Point2 = Point2 { x: 20.0, y: 20.0 };
+// Enemies standing close to each other
+const GROUP: [Point2; 3] = [
+    Point2 { x: 26.0, y: 20.0 },
+    Point2 { x: 26.5, y: 20.5 },
+    Point2 { x: 26.0, y: 21.0 },
+];
+const LONELY: Point2 = Point2 { x: 22.0, y: 26.0 };
+
+// Every unit costs 150 resources and has 100 health, so storm deals 120 value to each
+fn spell_bot(units: Vec<sc2_proto::raw::Unit>) -> Bot {
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 6,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 5.0,
+        speed: 1.0,
+    };
+    let mut bot = bot_with_units(
+        Race::Random,
+        &[Attribute::Light, Attribute::Biological],
+        &[
+            UnitTypeId::HighTemplar,
+            UnitTypeId::Zealot,
+            UnitTypeId::Marine,
+            UnitTypeId::Marauder,
+        ]
+        .map(|id| (id, weapon.clone())),
+    );
+
+    let mut all = vec![raw_unit(1, UnitTypeId::HighTemplar, 1, CASTER)];
+    all.extend(units);
+    bot.observe(&observation(1, all), &[]);
+    bot
+}
+
+fn enemies(positions: &[Point2]) -> Vec<sc2_proto::raw::Unit> {
+    positions
+        .iter()
+        .zip(100..)
+        .map(|(pos, tag)| raw_unit(tag, UnitTypeId::Marine, 2, *pos))
+        .collect()
+}
+
+fn with_lonely(mut units: Vec<sc2_proto::raw::Unit>) -> Vec<sc2_proto::raw::Unit> {
+    units.push(raw_unit(200, UnitTypeId::Marine, 2, LONELY));
+    units
+}
+
+fn storm(bot: &Bot, effects: &[Effect], options: &SpellOptions) -> Option<SpellTarget> {
+    let caster = bot.units.my.units.get(1).unwrap();
+    find_spell_target(
+        caster,
+        AreaSpell::PsiStorm,
+        &bot.units.enemy.units,
+        &bot.units.my.units,
+        effects,
+        options,
+    )
+}
+
+#[test]
+fn test_best_area() {
+    let mut units = with_lonely(enemies(&GROUP));
+    // Out of cast range
+    units.push(raw_unit(300, UnitTypeId::Marine, 2, Point2::new(45.0, 20.0)));
+    let bot = spell_bot(units);
+
+    let target = storm(&bot, &[], &SpellOptions::default()).unwrap();
+    assert_eq!(target.count, 3);
+    assert!((target.value - 360.0).abs() < 1e-3);
+    assert!(target.tag.is_none());
+    assert!(GROUP.iter().all(|pos| pos.is_closer(2.0, target.position)));
+    assert!(target.position.is_closer(9.5, CASTER));
+
+    let options = SpellOptions {
+        min_value: 400.0,
+        ..Default::default()
+    };
+    assert!(storm(&bot, &[], &options).is_none());
+    assert!(storm(&spell_bot(vec![]), &[], &SpellOptions::default()).is_none());
+}
+
+#[test]
+fn test_friendly_fire() {
+    let mut units = with_lonely(enemies(&GROUP));
+    units.push(raw_unit(2, UnitTypeId::Zealot, 1, Point2::new(26.2, 20.5)));
+    units.push(raw_unit(3, UnitTypeId::Zealot, 1, Point2::new(26.4, 20.3)));
+    let bot = spell_bot(units);
+
+    // Own zealots outweigh the group
+    let target = storm(&bot, &[], &SpellOptions::default()).unwrap();
+    assert_eq!(target.count, 1);
+    assert_eq!(target.position, LONELY);
+    assert!((target.value - 120.0).abs() < 1e-3);
+
+    let options = SpellOptions {
+        friendly_fire_weight: 0.5,
+        ..Default::default()
+    };
+    let target = storm(&bot, &[], &options).unwrap();
+    assert_eq!(target.count, 3);
+    assert!((target.value - 240.0).abs() < 1e-3);
+
+    // Fungal doesn't damage own units
+    let caster = bot.units.my.units.get(1).unwrap();
+    let target = bot
+        .find_spell_target(caster, AreaSpell::FungalGrowth, &SpellOptions::default())
+        .unwrap();
+    assert_eq!(target.count, 3);
+}
+
+#[test]
+fn test_already_affected_enemies() {
+    let mut stormed = enemies(&GROUP);
+    for u in &mut stormed {
+        u.mut_buff_ids().push(BuffId::PsiStorm as u32);
+    }
+    let bot = spell_bot(with_lonely(stormed));
+    assert_eq!(
+        storm(&bot, &[], &SpellOptions::default()).unwrap().position,
+        LONELY
+    );
+
+    let bot = spell_bot(with_lonely(enemies(&GROUP)));
+    let effect = |alliance| Effect {
+        id: EffectId::PsiStormPersistent,
+        positions: vec![GROUP[1]],
+        alliance,
+        owner: 1,
+        radius: 1.5,
+    };
+    let target = storm(&bot, &[effect(Alliance::Own)], &SpellOptions::default()).unwrap();
+    assert_eq!(target.position, LONELY);
+    // Enemy's storms don't matter
+    let target = storm(&bot, &[effect(Alliance::Enemy)], &SpellOptions::default()).unwrap();
+    assert_eq!(target.count, 3);
+}
+
+#[test]
+fn test_snipe_weights() {
+    let mut units = enemies(&GROUP);
+    units.push(raw_unit(200, UnitTypeId::Marauder, 2, LONELY));
+    let bot = spell_bot(units);
+    let caster = bot.units.my.units.get(1).unwrap();
+
+    let mut options = SpellOptions::default();
+    options.weights.insert(UnitTypeId::Marauder, 2.0);
+    let target = bot.find_spell_target(caster, AreaSpell::Snipe, &options).unwrap();
+    assert_eq!(target.tag, Some(200));
+    assert_eq!(target.count, 1);
+    assert!(matches!(target.target(), Target::Tag(200)));
+    // Snipe deals more damage than marauder has health
+    assert!((target.value - 300.0).abs() < 1e-3);
+
+    options.weights.insert(UnitTypeId::Marauder, 0.0);
+    let target = bot.find_spell_target(caster, AreaSpell::Snipe, &options).unwrap();
+    assert!(target.tag.is_some_and(|tag| tag != 200));
+}
+
+#[test]
+fn test_bile_leads_moving_target() {
+    let mut bot = spell_bot(vec![raw_unit(
+        100,
+        UnitTypeId::Marine,
+        2,
+        Point2::new(16.0, 24.0),
+    )]);
+    let caster = raw_unit(1, UnitTypeId::HighTemplar, 1, CASTER);
+    // Marine runs 1 tile per 8 frames to the right
+    let marine = raw_unit(100, UnitTypeId::Marine, 2, Point2::new(17.0, 24.0));
+    bot.observe(&observation(9, vec![caster, marine]), &[]);
+
+    let caster = bot.units.my.units.get(1).unwrap();
+    let options = SpellOptions::default();
+    let target = bot
+        .find_spell_target(caster, AreaSpell::CorrosiveBile, &options)
+        .unwrap();
+    let frames = AreaSpell::CorrosiveBile.delay() as f32;
+    assert!(target.position.distance(Point2::new(17.0 + frames / 8.0, 24.0)) < 1e-3);
+
+    // Storm hits immediately
+    let target = bot
+        .find_spell_target(caster, AreaSpell::PsiStorm, &options)
+        .unwrap();
+    assert_eq!(target.position, Point2::new(17.0, 24.0));
+}
@@ -0,0 +1,283 @@
+mod common;
+
+use common::{bot_with_units, observation, raw_unit, unit_data, units_data};
+use rust_sc2::{
+    balance::BalanceTable,
+    game_data::{Attribute, GameData, TargetType, Weapon},
+    prelude::*,
+    stats::{UnitStats, UpgradeLevels},
+};
+use rustc_hash::FxHashSet;
+
+fn game_data() -> GameData {
+    let mut data = GameData::default();
+    data.units.insert(
+        UnitTypeId::Hydralisk,
+        unit_data(
+            UnitTypeId::Hydralisk,
+            Race::Zerg,
+            vec![Attribute::Light, Attribute::Biological],
+            3.15,
+            Weapon {
+                target: TargetType::Any,
+                damage: 12,
+                damage_bonus: vec![],
+                attacks: 1,
+                range: 5.0,
+                speed: 0.59,
+            },
+        ),
+    );
+    data.units.insert(
+        UnitTypeId::Stalker,
+        unit_data(
+            UnitTypeId::Stalker,
+            Race::Protoss,
+            vec![Attribute::Armored, Attribute::Mechanical],
+            4.13,
+            Weapon {
+                target: TargetType::Any,
+                damage: 13,
+                damage_bonus: vec![(Attribute::Armored, 5)],
+                attacks: 1,
+                range: 6.0,
+                speed: 1.34,
+            },
+        ),
+    );
+    data
+}
+
+fn upgrades(ids: &[UpgradeId]) -> FxHashSet<UpgradeId> {
+    ids.iter().copied().collect()
+}
+
+#[test]
+fn test_upgrade_levels() {
+    let ranged = upgrades(&[
+        UpgradeId::ZergMissileWeaponsLevel1,
+        UpgradeId::ZergMissileWeaponsLevel2,
+        UpgradeId::ZergGroundArmorsLevel1,
+        UpgradeId::ZergMeleeWeaponsLevel3,
+    ]);
+    let hydra = UpgradeLevels::of(
+        UnitTypeId::Hydralisk,
+        Race::Zerg,
+        &[Attribute::Biological],
+        &ranged,
+    );
+    assert_eq!(
+        hydra,
+        UpgradeLevels {
+            attack: 2,
+            armor: 1,
+            shield: 0
+        }
+    );
+    let ling = UpgradeLevels::of(
+        UnitTypeId::Zergling,
+        Race::Zerg,
+        &[Attribute::Biological],
+        &ranged,
+    );
+    assert_eq!(ling.attack, 3);
+    let drone = UpgradeLevels::of(UnitTypeId::Drone, Race::Zerg, &[Attribute::Biological], &ranged);
+    assert_eq!(drone, UpgradeLevels::default());
+
+    let protoss = upgrades(&[
+        UpgradeId::ProtossAirWeaponsLevel1,
+        UpgradeId::ProtossShieldsLevel1,
+    ]);
+    let stalker = UpgradeLevels::of(
+        UnitTypeId::Stalker,
+        Race::Protoss,
+        &[Attribute::Armored],
+        &protoss,
+    );
+    assert_eq!(
+        stalker,
+        UpgradeLevels {
+            attack: 0,
+            armor: 0,
+            shield: 1
+        }
+    );
+    let void_ray = UpgradeLevels::of(
+        UnitTypeId::VoidRay,
+        Race::Protoss,
+        &[Attribute::Armored],
+        &protoss,
+    );
+    assert_eq!(void_ray.attack, 1);
+
+    let terran = upgrades(&[
+        UpgradeId::TerranShipWeaponsLevel1,
+        UpgradeId::TerranShipWeaponsLevel2,
+        UpgradeId::TerranVehicleWeaponsLevel1,
+        UpgradeId::TerranVehicleAndShipArmorsLevel1,
+    ]);
+    // Landed vikings still use ship weapons
+    for viking in [UnitTypeId::VikingFighter, UnitTypeId::VikingAssault] {
+        let levels = UpgradeLevels::of(viking, Race::Terran, &[Attribute::Armored], &terran);
+        assert_eq!(
+            levels,
+            UpgradeLevels {
+                attack: 2,
+                armor: 1,
+                shield: 0
+            },
+            "{:?}",
+            viking
+        );
+    }
+    let tank = UpgradeLevels::of(
+        UnitTypeId::SiegeTank,
+        Race::Terran,
+        &[Attribute::Armored],
+        &terran,
+    );
+    assert_eq!(tank.attack, 1);
+}
+
+#[test]
+fn test_unit_stats() {
+    let data = game_data();
+    let balance = BalanceTable::default();
+    let none = FxHashSet::default();
+    let no_buffs = FxHashSet::default();
+
+    let base = UnitStats::new(
+        UnitTypeId::Hydralisk,
+        &data,
+        &balance,
+        &none,
+        UpgradeLevels::default(),
+        &no_buffs,
+    )
+    .unwrap();
+    assert_eq!(base.health_max, 90);
+    assert_eq!(base.ground_range(), 5.0);
+    assert_eq!(base.air_range(), 5.0);
+    assert_eq!(base.speed, 3.15);
+
+    let ups = upgrades(&[UpgradeId::EvolveGroovedSpines, UpgradeId::EvolveMuscularAugments]);
+    let levels = UpgradeLevels {
+        attack: 2,
+        armor: 1,
+        shield: 0,
+    };
+    let upgraded = UnitStats::new(UnitTypeId::Hydralisk, &data, &balance, &ups, levels, &no_buffs).unwrap();
+    assert_eq!(upgraded.ground_range(), 6.0);
+    assert_eq!(upgraded.weapons[0].damage, 14);
+    assert_eq!(upgraded.armor, 2);
+    assert!((upgraded.speed - 3.15 * 1.31).abs() < 1e-4);
+    assert!((upgraded.speed_on_creep - 3.15 * 1.17 * 1.3).abs() < 1e-4);
+    assert!(upgraded.ground_dps() > base.ground_dps());
+
+    assert!(UnitStats::new(UnitTypeId::Marine, &data, &balance, &none, levels, &no_buffs).is_none());
+}
+
+#[test]
+fn test_unit_stats_bonuses() {
+    let data = game_data();
+    let balance = BalanceTable::default();
+    let none = FxHashSet::default();
+    let no_buffs = FxHashSet::default();
+    let levels = UpgradeLevels {
+        attack: 1,
+        armor: 0,
+        shield: 2,
+    };
+    let stalker = UnitStats::new(UnitTypeId::Stalker, &data, &balance, &none, levels, &no_buffs).unwrap();
+    assert_eq!(stalker.hits_max(), 160);
+    assert_eq!(stalker.shield_armor, 2);
+    assert_eq!(stalker.weapons[0].damage, 14);
+    assert_eq!(stalker.weapons[0].damage_bonus, vec![(Attribute::Armored, 6)]);
+    assert_eq!(stalker.cargo_size, 2);
+    assert_eq!(stalker.cargo_capacity, 0);
+}
+
+#[test]
+fn test_cargo_capacity() {
+    let mut data = game_data();
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 0,
+        damage_bonus: vec![],
+        attacks: 0,
+        range: 0.0,
+        speed: 0.0,
+    };
+    for id in [UnitTypeId::OverlordTransport, UnitTypeId::OverseerSiegeMode] {
+        data.units.insert(
+            id,
+            unit_data(id, Race::Zerg, vec![Attribute::Armored], 0.9, weapon.clone()),
+        );
+    }
+    let balance = BalanceTable::default();
+    let none = FxHashSet::default();
+    let no_buffs = FxHashSet::default();
+    let stats = |id| UnitStats::new(id, &data, &balance, &none, Default::default(), &no_buffs).unwrap();
+
+    assert_eq!(stats(UnitTypeId::OverlordTransport).cargo_capacity, 8);
+    // Overseers can't transport units
+    assert_eq!(stats(UnitTypeId::OverseerSiegeMode).cargo_capacity, 0);
+}
+
+#[test]
+fn test_real_ranges_match_stats() {
+    let weapon = |target, range| Weapon {
+        target,
+        damage: 10,
+        damage_bonus: vec![],
+        attacks: 1,
+        range,
+        speed: 1.0,
+    };
+    let units = [
+        (UnitTypeId::Colossus, weapon(TargetType::Ground, 7.0)),
+        (UnitTypeId::Hydralisk, weapon(TargetType::Any, 5.0)),
+    ];
+    let ups = upgrades(&[UpgradeId::ExtendedThermalLance, UpgradeId::EvolveGroovedSpines]);
+
+    let mut bot = bot_with_units(Race::Random, &[Attribute::Armored], &units);
+    let mut obs = observation(
+        1,
+        vec![
+            raw_unit(1, UnitTypeId::Colossus, 1, Point2::new(20.0, 20.0)),
+            raw_unit(2, UnitTypeId::Hydralisk, 1, Point2::new(22.0, 20.0)),
+        ],
+    );
+    let player = obs.mut_observation().mut_raw_data().mut_player();
+    player.set_upgrade_ids(ups.iter().map(|u| *u as u32).collect());
+    bot.observe(&obs, &[]);
+
+    let data = units_data(Race::Random, &[Attribute::Armored], &units);
+    let balance = BalanceTable::default();
+    let stats = |id| {
+        UnitStats::new(
+            id,
+            &data,
+            &balance,
+            &ups,
+            UpgradeLevels::default(),
+            &FxHashSet::default(),
+        )
+        .unwrap()
+    };
+    let colossus = bot.units.my.units.get(1).unwrap();
+    let hydralisk = bot.units.my.units.get(2).unwrap();
+
+    assert_eq!(colossus.real_ground_range(), 9.0);
+    assert_eq!(
+        colossus.real_ground_range(),
+        stats(UnitTypeId::Colossus).ground_range()
+    );
+    assert_eq!(colossus.real_range_vs(hydralisk), 9.0);
+    assert_eq!(hydralisk.real_air_range(), 6.0);
+    assert_eq!(
+        hydralisk.real_air_range(),
+        stats(UnitTypeId::Hydralisk).air_range()
+    );
+    assert_eq!(hydralisk.real_range_vs(colossus), 6.0);
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use common::{bot, observation, raw_unit, structure_data};
+use rust_sc2::{
+    bot::Bot,
+    consts::FRAMES_PER_SECOND,
+    game_data::GameData,
+    prelude::*,
+    strategy::{EnemyStrategy, StrategyCondition, StrategyRule},
+};
+
+#[test]
+fn test_default_rules() {
+    let rules = StrategyRule::defaults();
+    for strategy in [
+        EnemyStrategy::ProxyBarracks,
+        EnemyStrategy::ProxyGateway,
+        EnemyStrategy::CannonRush,
+        EnemyStrategy::TwelvePool,
+        EnemyStrategy::FastExpand,
+        EnemyStrategy::OneBaseTech,
+    ] {
+        assert!(
+            rules.iter().any(|r| r.strategy == strategy),
+            "{:?} has no rule",
+            strategy
+        );
+    }
+    assert!(rules.iter().all(|r| r.conditions.iter().all(|(_, w)| *w > 0.0)));
+}
+
+#[test]
+fn test_nothing_observed() {
+    let mut bot = Bot::default();
+    bot.strategy.rules.push(StrategyRule {
+        strategy: EnemyStrategy::Custom("Early Reapers".to_string()),
+        race: Some(Race::Terran),
+        conditions: vec![(StrategyCondition::SeenBefore(UnitTypeId::Reaper, 120.0), 1.0)],
+    });
+
+    assert!(bot.enemy_strategy().is_none());
+    assert!(bot.strategy.estimates().is_empty());
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::TwelvePool), 0.0);
+    assert_eq!(bot.strategy.first_seen(UnitTypeId::SpawningPool), None);
+    assert_eq!(bot.strategy.townhalls_seen_at(0), Some(0.0));
+}
+
+fn zerg_bot() -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    for id in [UnitTypeId::Hatchery, UnitTypeId::SpawningPool] {
+        data.units.insert(id, structure_data(id, Race::Zerg));
+    }
+    // 46 seconds
+    data.units.get_mut(&UnitTypeId::SpawningPool).unwrap().build_time = 1030.4;
+    bot.game_data = data.into();
+    bot.enemy_race = Race::Zerg;
+    bot.start_location = Point2::new(8.5, 8.5);
+    bot.enemy_start = Point2::new(56.5, 56.5);
+    bot
+}
+
+// Game loop at given game time in seconds
+fn at(seconds: f32) -> u32 {
+    (seconds * FRAMES_PER_SECOND) as u32
+}
+
+#[test]
+fn test_absence_without_scouting() {
+    let mut bot = zerg_bot();
+    bot.observe(&observation(at(30.0), vec![]), &[]);
+    bot.observe(&observation(at(200.0), vec![]), &[]);
+
+    assert!(bot.strategy.estimates().is_empty());
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::FastExpand), 0.0);
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::TwelvePool), 0.0);
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::OneBaseTech), 0.0);
+}
+
+#[test]
+fn test_twelve_pool() {
+    let mut bot = zerg_bot();
+    let main = raw_unit(1, UnitTypeId::Hatchery, 2, Point2::new(56.5, 56.5));
+    let mut pool = raw_unit(2, UnitTypeId::SpawningPool, 2, Point2::new(52.5, 56.5));
+    pool.set_build_progress(0.5);
+    let zergling = raw_unit(3, UnitTypeId::Zergling, 2, Point2::new(40.0, 40.0));
+
+    bot.observe(&observation(at(40.0), vec![main.clone(), pool]), &[]);
+    let seen = at(40.0) as f32 / FRAMES_PER_SECOND;
+    assert_eq!(bot.strategy.first_seen(UnitTypeId::SpawningPool), Some(seen));
+    // Pool was started half of its build time ago
+    let started = bot.strategy.started_at(UnitTypeId::SpawningPool).unwrap();
+    assert!((started - (seen - 23.0)).abs() < 1e-3);
+    // Fewer townhalls can't be known yet
+    assert!((bot.strategy.confidence(&EnemyStrategy::TwelvePool) - 0.5).abs() < 1e-4);
+
+    bot.observe(&observation(at(70.0), vec![main, zergling]), &[]);
+    let best = bot.enemy_strategy().unwrap();
+    assert_eq!(best.strategy, EnemyStrategy::TwelvePool);
+    assert!((best.confidence - 1.0).abs() < 1e-4);
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::FastExpand), 0.0);
+}
+
+#[test]
+fn test_late_scouted_pool() {
+    let mut bot = zerg_bot();
+    let main = raw_unit(1, UnitTypeId::Hatchery, 2, Point2::new(56.5, 56.5));
+    let pool = raw_unit(2, UnitTypeId::SpawningPool, 2, Point2::new(52.5, 56.5));
+    let mut standard_pool = pool.clone();
+    standard_pool.set_build_progress(0.2);
+
+    // Finished pool seen at 1:10 could be started at 0:24 the latest
+    bot.observe(&observation(at(70.0), vec![main.clone(), pool]), &[]);
+    assert!((bot.strategy.started_at(UnitTypeId::SpawningPool).unwrap() - 24.0).abs() < 0.1);
+    assert!((bot.strategy.confidence(&EnemyStrategy::TwelvePool) - 0.75).abs() < 1e-4);
+
+    // Standard pool is too late for twelve pool
+    let mut bot = zerg_bot();
+    bot.observe(&observation(at(70.0), vec![main, standard_pool]), &[]);
+    assert!((bot.strategy.started_at(UnitTypeId::SpawningPool).unwrap() - 60.8).abs() < 0.1);
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::TwelvePool), 0.0);
+}
+
+#[test]
+fn test_fast_expand() {
+    let mut bot = zerg_bot();
+    let main = raw_unit(1, UnitTypeId::Hatchery, 2, Point2::new(56.5, 56.5));
+    let natural = raw_unit(2, UnitTypeId::Hatchery, 2, Point2::new(40.5, 56.5));
+
+    bot.observe(&observation(at(50.0), vec![main, natural]), &[]);
+    assert_eq!(
+        bot.strategy.townhalls_seen_at(2),
+        Some(at(50.0) as f32 / FRAMES_PER_SECOND)
+    );
+
+    let best = bot.enemy_strategy().unwrap();
+    assert_eq!(best.strategy, EnemyStrategy::FastExpand);
+    assert!((best.confidence - 1.0).abs() < 1e-4);
+    assert_eq!(bot.strategy.confidence(&EnemyStrategy::TwelvePool), 0.0);
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use common::{bot, observation, order, raw_unit, structure_data, unit_data};
+use rust_sc2::{
+    action::Target,
+    bot::Bot,
+    consts::FRAMES_PER_SECOND,
+    game_data::{GameData, TargetType, UnitTypeData, Weapon},
+    prelude::*,
+    supply::SupplyTimeline,
+};
+
+fn timeline(used: f32, cap: f32, pending: &[(f32, f32)], demand: &[(f32, f32)]) -> SupplyTimeline {
+    SupplyTimeline {
+        supply_used: used,
+        supply_cap: cap,
+        pending: pending.to_vec(),
+        demand: demand.to_vec(),
+    }
+}
+
+#[test]
+fn test_supply_block() {
+    let timeline = timeline(14.0, 15.0, &[], &[(12.0, 1.0), (0.0, 1.0), (24.0, 1.0)]);
+    assert_eq!(timeline.total_demand(), 3.0);
+    assert_eq!(timeline.blocked_in(), Some(12.0));
+
+    let forecast = timeline.forecast(UnitTypeId::SupplyDepot, 8.0, 21.0);
+    assert_eq!(forecast.blocked_in, Some(12.0));
+    assert_eq!(forecast.starts, [0.0]);
+    assert_eq!(forecast.start_now(), 1);
+    assert_eq!(forecast.surplus, 6.0);
+}
+
+#[test]
+fn test_supply_lookahead() {
+    let covered = timeline(14.0, 15.0, &[(5.0, 8.0)], &[(0.0, 1.0), (12.0, 1.0)]);
+    assert_eq!(covered.blocked_in(), None);
+    assert!(covered.forecast(UnitTypeId::Pylon, 8.0, 18.0).starts.is_empty());
+
+    let later = timeline(10.0, 15.0, &[], &[(30.0, 3.0), (40.0, 3.0), (50.0, 2.0)]);
+    let forecast = later.forecast(UnitTypeId::SupplyDepot, 8.0, 21.0);
+    assert_eq!(forecast.blocked_in, Some(40.0));
+    assert_eq!(forecast.start_in(), Some(19.0));
+    assert_eq!(forecast.start_now(), 0);
+    // One provider covers the rest of demand, so only one is planned
+    assert_eq!(forecast.starts.len(), 1);
+
+    let big = timeline(20.0, 23.0, &[], &[(0.0, 6.0), (0.0, 6.0), (10.0, 6.0)]);
+    let forecast = big.forecast(UnitTypeId::Overlord, 8.0, 18.0);
+    assert_eq!(forecast.starts, [0.0, 0.0]);
+}
+
+#[test]
+fn test_supply_max() {
+    let timeline = timeline(198.0, 200.0, &[], &[(0.0, 2.0), (1.0, 2.0)]);
+    assert_eq!(timeline.blocked_in(), None);
+    assert!(timeline.forecast(UnitTypeId::Pylon, 8.0, 18.0).starts.is_empty());
+
+    let mut bot = Bot::default();
+    bot.production.set_ratio(UnitTypeId::Marine, 1.0);
+    let forecast = bot.supply_forecast(30.0);
+    assert_eq!(forecast.demand, 0.0);
+    assert!(forecast.starts.is_empty());
+}
+
+// Bot with game data of units made with given abilities: type, ability, supply and build time in seconds.
+// Units with negative supply are providers.
+fn supply_bot(units: &[(UnitTypeId, AbilityId, f32, f32)]) -> Bot {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 5,
+        damage_bonus: vec![],
+        attacks: 1,
+        range: 0.1,
+        speed: 1.0,
+    };
+    for (id, ability, supply, time) in units {
+        let mut unit = if *supply < 0.0 {
+            UnitTypeData {
+                food_provided: -supply,
+                ..structure_data(*id, Race::Random)
+            }
+        } else {
+            UnitTypeData {
+                food_required: *supply,
+                ..unit_data(*id, Race::Random, vec![], 3.15, weapon.clone())
+            }
+        };
+        unit.ability = Some(*ability);
+        unit.build_time = time * FRAMES_PER_SECOND;
+        data.units.insert(*id, unit);
+    }
+    for id in [UnitTypeId::Barracks, UnitTypeId::BarracksReactor] {
+        data.units.insert(id, structure_data(id, Race::Terran));
+    }
+    bot.game_data = data.into();
+    bot
+}
+
+fn with_orders(mut unit: sc2_proto::raw::Unit, orders: &[(AbilityId, Target, f32)]) -> sc2_proto::raw::Unit {
+    for (ability, target, progress) in orders {
+        unit.mut_orders().push(order(*ability, *target, *progress));
+    }
+    unit
+}
+
+fn assert_events(actual: &[(f32, f32)], expected: &[(f32, f32)]) {
+    let mut actual = actual.to_vec();
+    actual.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(
+            (a.0 - e.0).abs() < 1e-3 && a.1 == e.1,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_reactor_slots() {
+    let mut bot = supply_bot(&[(UnitTypeId::Marine, AbilityId::BarracksTrainMarine, 1.0, 18.0)]);
+    let train = AbilityId::BarracksTrainMarine;
+    let mut barracks = with_orders(
+        raw_unit(1, UnitTypeId::Barracks, 1, Point2::new(20.5, 20.5)),
+        &[
+            (train, Target::None, 0.5),
+            (train, Target::None, 0.0),
+            (train, Target::None, 0.0),
+        ],
+    );
+    barracks.set_add_on_tag(2);
+    let reactor = raw_unit(2, UnitTypeId::BarracksReactor, 1, Point2::new(23.0, 20.0));
+    bot.observe(&observation(1, vec![barracks, reactor]), &[]);
+
+    // Both slots queue new marines: the first after current and queued ones, the second after current one
+    let timeline = bot.supply_timeline(40.0);
+    assert!(timeline.pending.is_empty());
+    assert_events(&timeline.demand, &[(18.0, 1.0), (27.0, 1.0), (36.0, 1.0)]);
+}
+
+#[test]
+fn test_overlord_eggs() {
+    let mut bot = supply_bot(&[
+        (UnitTypeId::Overlord, AbilityId::LarvaTrainOverlord, -8.0, 18.0),
+        (UnitTypeId::Zergling, AbilityId::LarvaTrainZergling, 0.5, 17.0),
+    ]);
+    let egg = |tag, ability, progress| {
+        with_orders(
+            raw_unit(tag, UnitTypeId::Egg, 1, Point2::new(20.0, 20.0)),
+            &[(ability, Target::None, progress)],
+        )
+    };
+    let units = vec![
+        egg(1, AbilityId::LarvaTrainOverlord, 0.5),
+        egg(2, AbilityId::LarvaTrainOverlord, 0.0),
+        egg(3, AbilityId::LarvaTrainZergling, 0.5),
+    ];
+    bot.observe(&observation(1, units), &[]);
+
+    let timeline = bot.supply_timeline(30.0);
+    assert_events(&timeline.pending, &[(9.0, 8.0), (18.0, 8.0)]);
+    assert_eq!(timeline.total_pending(), 16.0);
+}
+
+#[test]
+fn test_placed_depots() {
+    let mut bot = supply_bot(&[(
+        UnitTypeId::SupplyDepot,
+        AbilityId::TerranBuildSupplyDepot,
+        -8.0,
+        21.0,
+    )]);
+    let build = |tag, pos| {
+        with_orders(
+            raw_unit(tag, UnitTypeId::SCV, 1, Point2::new(20.0, 20.0)),
+            &[(AbilityId::TerranBuildSupplyDepot, Target::Pos(pos), 0.0)],
+        )
+    };
+    let mut depot = raw_unit(3, UnitTypeId::SupplyDepot, 1, Point2::new(30.0, 30.0));
+    depot.set_build_progress(0.25);
+    let units = vec![
+        // Depot is already placed and counted as structure in progress
+        build(1, Point2::new(30.0, 30.0)),
+        // Worker is on the way
+        build(2, Point2::new(40.0, 40.0)),
+        depot,
+    ];
+    bot.observe(&observation(1, units), &[]);
+
+    let timeline = bot.supply_timeline(30.0);
+    assert_events(&timeline.pending, &[(15.75, 8.0), (21.0, 8.0)]);
+    assert!(timeline.demand.is_empty());
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use common::unit_data;
+use rust_sc2::{
+    bot::Bot,
+    game_data::{Attribute, GameData, TargetType, Weapon},
+    prelude::*,
+};
+use rustc_hash::FxHashSet;
+
+fn bot() -> Bot {
+    let mut data = GameData::default();
+    data.units.insert(
+        UnitTypeId::Stalker,
+        unit_data(
+            UnitTypeId::Stalker,
+            Race::Protoss,
+            vec![Attribute::Armored, Attribute::Mechanical],
+            4.13,
+            Weapon {
+                target: TargetType::Any,
+                damage: 13,
+                damage_bonus: vec![(Attribute::Armored, 5)],
+                attacks: 1,
+                range: 6.0,
+                speed: 1.34,
+            },
+        ),
+    );
+    data.units.insert(
+        UnitTypeId::Hydralisk,
+        unit_data(
+            UnitTypeId::Hydralisk,
+            Race::Zerg,
+            vec![Attribute::Light, Attribute::Biological],
+            3.15,
+            Weapon {
+                target: TargetType::Any,
+                damage: 12,
+                damage_bonus: vec![],
+                attacks: 1,
+                range: 5.0,
+                speed: 0.59,
+            },
+        ),
+    );
+    let mut bot = Bot::default();
+    bot.player_id = 1;
+    bot.game_data = data.into();
+    bot
+}
+
+#[test]
+fn test_synthetic_units() {
+    let bot = bot();
+    let stalker = bot
+        .synthetic_unit(UnitTypeId::Stalker, 1, Point2::new(10.0, 10.0))
+        .unwrap();
+    let hydra = bot
+        .synthetic_unit(UnitTypeId::Hydralisk, 2, Point2::new(16.5, 10.0))
+        .unwrap();
+
+    assert!(stalker.is_mine() && hydra.is_enemy());
+    assert!(stalker.is_synthetic() && hydra.is_synthetic());
+    assert_ne!(stalker.tag(), hydra.tag());
+    assert_eq!((stalker.health(), stalker.shield()), (80, 80));
+    assert_eq!(hydra.health_max(), 90);
+    assert_eq!(hydra.radius(), 0.625);
+    assert!(!hydra.is_flying());
+
+    assert_eq!(stalker.real_range_vs(&hydra), 6.0);
+    assert!(stalker.in_real_range(&hydra, 0.0));
+    assert!(!hydra.in_real_range(&stalker, 0.0));
+    assert!(stalker.dps_vs(&hydra) > 0.0);
+    assert!(bot
+        .synthetic_unit(UnitTypeId::Marine, 1, Point2::new(0.0, 0.0))
+        .is_none());
+}
+
+#[test]
+fn test_synthetic_unit_without_hits() {
+    let mut bot = bot();
+    let mut data = (*bot.game_data).clone();
+    let weapon = Weapon {
+        target: TargetType::Ground,
+        damage: 0,
+        damage_bonus: vec![],
+        attacks: 0,
+        range: 0.0,
+        speed: 0.0,
+    };
+    data.units.insert(
+        UnitTypeId::Changeling,
+        unit_data(UnitTypeId::Changeling, Race::Protoss, vec![], 3.15, weapon),
+    );
+    bot.game_data = data.into();
+
+    // Hits of changeling aren't in the balance table, so it would be created dead
+    assert!(bot
+        .synthetic_unit(UnitTypeId::Changeling, 2, Point2::new(10.0, 10.0))
+        .is_none());
+    assert!(bot
+        .synthetic_unit(UnitTypeId::Stalker, 2, Point2::new(10.0, 10.0))
+        .is_some());
+}
+
+#[test]
+fn test_synthetic_upgrades() {
+    let bot = bot();
+    let stalker = bot
+        .synthetic_unit(UnitTypeId::Stalker, 1, Point2::new(10.0, 10.0))
+        .unwrap();
+    let upgrades = [
+        UpgradeId::EvolveGroovedSpines,
+        UpgradeId::ZergMissileWeaponsLevel1,
+    ]
+    .into_iter()
+    .collect::<FxHashSet<_>>();
+    let hydra = bot
+        .synthetic_unit_with_upgrades(UnitTypeId::Hydralisk, 2, Point2::new(16.5, 10.0), &upgrades)
+        .unwrap();
+
+    assert_eq!(hydra.attack_upgrade_level(), 1);
+    assert_eq!(hydra.real_range_vs(&stalker), 6.0);
+    assert!(hydra.in_real_range(&stalker, 0.0));
+    // Upgrades of one unit don't affect other units
+    assert!(!bot.has_upgrade(UpgradeId::EvolveGroovedSpines));
+    let other = bot
+        .synthetic_unit(UnitTypeId::Hydralisk, 2, Point2::new(16.5, 10.0))
+        .unwrap();
+    assert_eq!(other.real_range_vs(&stalker), 5.0);
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::structure_data;
+use rust_sc2::{
+    bot::Bot,
+    consts::{FRAMES_PER_SECOND, RACE_VALUES},
+    game_data::{Attribute, GameData, UnitTypeData},
+    prelude::*,
+    tech_tree::TechItem,
+};
+
+fn bot_of_race(race: Race) -> Bot {
+    let mut bot = Bot::default();
+    bot.race = race;
+    bot.race_values = RACE_VALUES[&race].clone().into();
+    bot
+}
+
+// Protoss data with real costs and build times
+fn protoss_data() -> GameData {
+    fn with_cost(data: UnitTypeData, minerals: u32, vespene: u32, seconds: f32) -> UnitTypeData {
+        UnitTypeData {
+            mineral_cost: minerals,
+            vespene_cost: vespene,
+            build_time: seconds * FRAMES_PER_SECOND,
+            ..data
+        }
+    }
+    let structure = |id, minerals, vespene, seconds| {
+        with_cost(structure_data(id, Race::Protoss), minerals, vespene, seconds)
+    };
+
+    let mut data = GameData::default();
+    for unit in [
+        structure(UnitTypeId::Pylon, 100, 0, 18.0),
+        structure(UnitTypeId::Gateway, 150, 0, 46.0),
+        structure(UnitTypeId::CyberneticsCore, 150, 0, 36.0),
+        structure(UnitTypeId::Stargate, 150, 150, 43.0),
+        structure(UnitTypeId::FleetBeacon, 300, 200, 43.0),
+        with_cost(
+            UnitTypeData {
+                attributes: vec![Attribute::Armored, Attribute::Massive, Attribute::Mechanical],
+                ..structure_data(UnitTypeId::Carrier, Race::Protoss)
+            },
+            350,
+            250,
+            64.0,
+        ),
+    ] {
+        data.units.insert(unit.id, unit);
+    }
+    data
+}
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+}
+
+fn position(plan: &rust_sc2::tech_tree::TechPlan, item: impl Into<TechItem>) -> usize {
+    let item = item.into();
+    plan.steps
+        .iter()
+        .position(|s| s.item == item)
+        .unwrap_or_else(|| panic!("{:?} is missing in plan", item))
+}
+
+#[test]
+fn test_carrier_plan_order() {
+    let bot = bot_of_race(Race::Protoss);
+    let plan = bot.tech_plan(UnitTypeId::Carrier);
+
+    assert_eq!(
+        plan.steps.last().unwrap().item,
+        TechItem::Unit(UnitTypeId::Carrier)
+    );
+    assert!(position(&plan, UnitTypeId::Pylon) < position(&plan, UnitTypeId::Gateway));
+    assert!(position(&plan, UnitTypeId::Gateway) < position(&plan, UnitTypeId::CyberneticsCore));
+    assert!(position(&plan, UnitTypeId::CyberneticsCore) < position(&plan, UnitTypeId::Stargate));
+    assert!(position(&plan, UnitTypeId::Stargate) < position(&plan, UnitTypeId::FleetBeacon));
+    assert!(position(&plan, UnitTypeId::FleetBeacon) < position(&plan, UnitTypeId::Carrier));
+}
+
+#[test]
+fn test_upgrade_plan_includes_researcher_and_previous_level() {
+    let bot = bot_of_race(Race::Protoss);
+    let plan = bot.tech_plan(UpgradeId::ProtossGroundWeaponsLevel2);
+
+    assert!(position(&plan, UnitTypeId::Forge) < plan.steps.len() - 1);
+    assert!(position(&plan, UnitTypeId::TwilightCouncil) < plan.steps.len() - 1);
+    assert!(
+        position(&plan, UpgradeId::ProtossGroundWeaponsLevel1)
+            < position(&plan, UpgradeId::ProtossGroundWeaponsLevel2)
+    );
+}
+
+#[test]
+fn test_addon_in_plan() {
+    let bot = bot_of_race(Race::Terran);
+    let plan = bot.tech_plan(UnitTypeId::Marauder);
+
+    let kinds = plan
+        .steps
+        .iter()
+        .map(|s| (s.item, s.kind.is_addon()))
+        .collect::<Vec<_>>();
+    assert!(kinds.contains(&(TechItem::Unit(UnitTypeId::BarracksTechLab), true)));
+    assert!(position(&plan, UnitTypeId::SupplyDepot) < position(&plan, UnitTypeId::Barracks));
+    assert!(position(&plan, UnitTypeId::Barracks) < position(&plan, UnitTypeId::BarracksTechLab));
+}
+
+#[test]
+fn test_plan_timings() {
+    let mut bot = bot_of_race(Race::Protoss);
+    bot.game_data = protoss_data().into();
+    bot.minerals = 2000;
+    bot.vespene = 1000;
+
+    // Everything is affordable, so steps wait only for their requirements
+    let plan = bot.tech_plan(UnitTypeId::Carrier);
+    let finish = |item| plan.steps[position(&plan, item)].finish;
+    assert_close(finish(UnitTypeId::Pylon), 18.0);
+    assert_close(finish(UnitTypeId::Gateway), 64.0);
+    assert_close(finish(UnitTypeId::CyberneticsCore), 100.0);
+    assert_close(finish(UnitTypeId::Stargate), 143.0);
+    assert_close(finish(UnitTypeId::FleetBeacon), 186.0);
+    assert_close(plan.eta().unwrap(), 250.0);
+
+    let total = plan.total_cost();
+    assert_eq!((total.minerals, total.vespene), (1200, 600));
+    assert_close(total.time, 250.0 * FRAMES_PER_SECOND);
+
+    // Gateway waits for minerals: 250 are needed for both steps, 100 are mined at 5 per second
+    bot.minerals = 100;
+    bot.state.observation.score.collection_rate_minerals = 300.0;
+    let plan = bot.tech_plan(UnitTypeId::Gateway);
+    assert_close(plan.steps[0].start, 0.0);
+    assert_close(plan.steps[1].start, 30.0);
+    assert_close(plan.eta().unwrap(), 76.0);
+
+    // Nothing is mined
+    bot.state.observation.score.collection_rate_minerals = 0.0;
+    assert_eq!(bot.tech_plan(UnitTypeId::Gateway).eta(), None);
+}
+
+#[test]
+fn test_tech_requirements() {
+    assert_eq!(
+        TECH_REQUIREMENTS.get(&UnitTypeId::FleetBeacon),
+        Some(&UnitTypeId::Stargate)
+    );
+    assert_eq!(
+        TECH_REQUIREMENTS.get(&UnitTypeId::Ultralisk),
+        Some(&UnitTypeId::UltraliskCavern)
+    );
+
+    let bot = bot_of_race(Race::Zerg);
+    let plan = bot.tech_plan(UnitTypeId::Ultralisk);
+    assert!(position(&plan, UnitTypeId::Hive) < position(&plan, UnitTypeId::UltraliskCavern));
+    assert!(position(&plan, UnitTypeId::UltraliskCavern) < position(&plan, UnitTypeId::Ultralisk));
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use rust_sc2::{bot::Bot, game_state::Alert, prelude::*, triggers::EventKind};
+use sc2_proto::sc2api::Alert as ProtoAlert;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_event_kind() {
+    assert_eq!(Event::UnitCreated(1).kind(), EventKind::UnitCreated);
+    assert_eq!(Event::UnitDamaged(1, 10).kind(), EventKind::UnitDamaged);
+    assert_eq!(
+        Event::UnitTypeChanged(1, UnitTypeId::SiegeTank, UnitTypeId::SiegeTankSieged).kind(),
+        EventKind::UnitTypeChanged
+    );
+    assert_eq!(
+        Event::UpgradeComplete(UpgradeId::Stimpack).kind(),
+        EventKind::UpgradeComplete
+    );
+    assert!(Event::EnemyExpanded(Point2::new(10.0, 10.0))
+        .kind()
+        .is_enemy_expanded());
+}
+
+#[test]
+fn test_register_and_remove_triggers() {
+    let mut bot = Bot::default();
+    assert!(bot.triggers.is_empty());
+
+    let on_idle = bot.triggers.on_event(EventKind::UnitIdle, |_, _| Ok(()));
+    let on_alert = bot.triggers.on_alert(Alert::NuclearLaunchDetected, |_, _| Ok(()));
+    let timer = bot.triggers.every(5.0, |_| Ok(()));
+    bot.triggers.at(180.0, |_| Ok(()));
+    assert_eq!(bot.triggers.len(), 4);
+    assert_ne!(on_idle, on_alert);
+    assert_ne!(on_alert, timer);
+
+    assert!(bot.triggers.remove(timer));
+    assert!(!bot.triggers.remove(timer));
+    assert_eq!(bot.triggers.len(), 3);
+
+    bot.triggers.clear();
+    assert!(bot.triggers.is_empty());
+}
+
+fn step(bot: &mut Bot, game_loop: u32, events: &[Event]) -> SC2Result<()> {
+    bot.observe(&common::observation(game_loop, vec![]), &[]);
+    bot.dispatch_triggers(events)
+}
+
+fn counter() -> (Arc<Mutex<u32>>, Arc<Mutex<u32>>) {
+    let count = Arc::new(Mutex::new(0));
+    (count.clone(), count)
+}
+
+#[test]
+fn test_dispatch_event_triggers() {
+    let mut bot = common::bot();
+    let idle = Arc::new(Mutex::new(vec![]));
+    let log = idle.clone();
+    bot.triggers.on_event(EventKind::UnitIdle, move |_, event| {
+        if let Event::UnitIdle(tag) = event {
+            log.lock().unwrap().push(tag);
+        }
+        Ok(())
+    });
+
+    let events = [Event::UnitIdle(1), Event::UnitCreated(2), Event::UnitIdle(3)];
+    step(&mut bot, 1, &events).unwrap();
+    assert_eq!(*idle.lock().unwrap(), vec![1, 3]);
+}
+
+#[test]
+fn test_dispatch_alert_triggers() {
+    let mut bot = common::bot();
+    let (nukes, count) = counter();
+    bot.triggers.on_alert(Alert::NuclearLaunchDetected, move |_, _| {
+        *count.lock().unwrap() += 1;
+        Ok(())
+    });
+    let (exhausted, count) = counter();
+    bot.triggers.on_alert(Alert::MineralsExhausted, move |_, _| {
+        *count.lock().unwrap() += 1;
+        Ok(())
+    });
+
+    let mut obs = common::observation(1, vec![]);
+    obs.mut_observation()
+        .mut_alerts()
+        .push(ProtoAlert::NuclearLaunchDetected);
+    bot.observe(&obs, &[]);
+    bot.dispatch_triggers(&[]).unwrap();
+    assert_eq!(*nukes.lock().unwrap(), 1);
+    assert_eq!(*exhausted.lock().unwrap(), 0);
+
+    // Alert is gone on the next observation
+    step(&mut bot, 2, &[]).unwrap();
+    assert_eq!(*nukes.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_dispatch_timers() {
+    let mut bot = common::bot();
+    let (every, count) = counter();
+    bot.triggers.every_loops(10, move |_| {
+        *count.lock().unwrap() += 1;
+        Ok(())
+    });
+    let (at, count) = counter();
+    // 1 second is 22.4 game loops
+    bot.triggers.at(1.0, move |_| {
+        *count.lock().unwrap() += 1;
+        Ok(())
+    });
+
+    let fired = |bot: &mut Bot, game_loop| {
+        step(bot, game_loop, &[]).unwrap();
+        (*every.lock().unwrap(), *at.lock().unwrap(), bot.triggers.len())
+    };
+    assert_eq!(fired(&mut bot, 5), (0, 0, 2));
+    assert_eq!(fired(&mut bot, 10), (1, 0, 2));
+    assert_eq!(fired(&mut bot, 11), (1, 0, 2));
+    // Skipped multiples of interval fire only once
+    assert_eq!(fired(&mut bot, 35), (2, 1, 1));
+    assert_eq!(fired(&mut bot, 39), (2, 1, 1));
+    assert_eq!(fired(&mut bot, 40), (3, 1, 1));
+}
+
+#[test]
+fn test_triggers_added_by_handler() {
+    let mut bot = common::bot();
+    let (fired, count) = counter();
+    bot.triggers.on_event(EventKind::UnitCreated, move |bot, _| {
+        let count = count.clone();
+        bot.triggers.at(0.0, move |_| {
+            *count.lock().unwrap() += 1;
+            Ok(())
+        });
+        Ok(())
+    });
+
+    step(&mut bot, 1, &[Event::UnitCreated(1)]).unwrap();
+    assert_eq!(bot.triggers.len(), 2);
+    assert_eq!(*fired.lock().unwrap(), 0);
+
+    step(&mut bot, 2, &[]).unwrap();
+    assert_eq!(bot.triggers.len(), 1);
+    assert_eq!(*fired.lock().unwrap(), 1);
+}
+
+#[test]
+fn test_failed_timers_are_advanced() {
+    let mut bot = common::bot();
+    let (every, count) = counter();
+    bot.triggers.every_loops(10, move |_| {
+        *count.lock().unwrap() += 1;
+        Err("every failed".into())
+    });
+    let (at, count) = counter();
+    bot.triggers.at(0.0, move |_| {
+        *count.lock().unwrap() += 1;
+        Err("at failed".into())
+    });
+
+    assert!(step(&mut bot, 10, &[]).is_err());
+    assert_eq!(*every.lock().unwrap(), 1);
+    // Dispatch stopped at the first error
+    assert_eq!(*at.lock().unwrap(), 0);
+
+    assert!(step(&mut bot, 15, &[]).is_err());
+    assert_eq!(*every.lock().unwrap(), 1);
+    assert_eq!(*at.lock().unwrap(), 1);
+    assert_eq!(bot.triggers.len(), 1);
+
+    step(&mut bot, 19, &[]).unwrap();
+    assert_eq!(*every.lock().unwrap(), 1);
+    assert_eq!(*at.lock().unwrap(), 1);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_triggers_are_thread_safe() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<rust_sc2::triggers::Triggers>();
+}
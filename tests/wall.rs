@@ -0,0 +1,146 @@
+mod common;
+
+use common::{add_building, bot, observation, open_map, raw_unit};
+use rust_sc2::{bot::Bot, game_data::GameData, pixel_map::Pixel, prelude::*, wall::Wall};
+use sc2pathfinding::Choke;
+use std::collections::VecDeque;
+
+#[test]
+fn test_no_wall_without_map() {
+    let bot = Bot::default();
+    let choke = Choke::new(((10, 10), (10, 16)));
+    let wall = bot.find_wall(
+        &choke,
+        Point2::new(5.0, 13.0),
+        &[
+            UnitTypeId::Gateway,
+            UnitTypeId::CyberneticsCore,
+            UnitTypeId::Pylon,
+        ],
+        1,
+    );
+    assert!(wall.is_none());
+}
+
+const INSIDE: Point2 = Point2 { x: 33.0, y: 20.0 };
+const OUTSIDE: Point2 = Point2 { x: 33.0, y: 45.0 };
+
+// Bot on a map split by cliff at y 30..=32 with 6 tiles wide choke at x 30..=35
+fn choke_bot() -> (Bot, Choke) {
+    let mut bot = bot();
+    let mut data = GameData::default();
+    add_building(
+        &mut data,
+        UnitTypeId::Barracks,
+        Race::Terran,
+        AbilityId::TerranBuildBarracks,
+        1.5,
+    );
+    add_building(
+        &mut data,
+        UnitTypeId::SupplyDepot,
+        Race::Terran,
+        AbilityId::TerranBuildSupplyDepot,
+        1.0,
+    );
+    bot.game_data = data.into();
+    open_map(&mut bot);
+    for x in (0..30).chain(36..64) {
+        for y in 30..=32 {
+            bot.game_info.pathing_grid[(x, y)] = Pixel::Set;
+            bot.game_info.placement_grid[(x, y)] = Pixel::Set;
+        }
+    }
+    bot.observe(&observation(1, vec![]), &[]);
+    (bot, Choke::new(((30, 31), (36, 31))))
+}
+
+// Checks if ground unit can walk from inside to outside of the choke through the wall
+fn passable(bot: &Bot, wall: &Wall) -> bool {
+    let blocked = wall
+        .buildings
+        .iter()
+        .flat_map(|&(building, pos)| {
+            let size = if building == UnitTypeId::Barracks { 3 } else { 2 };
+            let left = (pos.x - size as f32 / 2.0).round() as usize;
+            let bottom = (pos.y - size as f32 / 2.0).round() as usize;
+            (left..left + size).flat_map(move |x| (bottom..bottom + size).map(move |y| (x, y)))
+        })
+        .collect::<Vec<_>>();
+
+    let start = <(usize, usize)>::from(INSIDE);
+    let mut visited = vec![start];
+    let mut queue = VecDeque::from([start]);
+    while let Some((x, y)) = queue.pop_front() {
+        if (x, y) == <(usize, usize)>::from(OUTSIDE) {
+            return true;
+        }
+        for next in [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ] {
+            if bot.is_pathable(next) && !blocked.contains(&next) && !visited.contains(&next) {
+                visited.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn test_closed_wall() {
+    let (mut bot, choke) = choke_bot();
+    let wall = bot
+        .find_wall(&choke, INSIDE, &[UnitTypeId::Barracks, UnitTypeId::Barracks], 0)
+        .unwrap();
+
+    assert_eq!(wall.gap, 0);
+    assert_eq!(wall.buildings.len(), 2);
+    assert!(!passable(&bot, &wall));
+    for &(building, pos) in &wall.buildings {
+        assert_eq!(building, UnitTypeId::Barracks);
+        assert!(bot.can_place_local(building, pos));
+        // Wall is built from the inside
+        assert!(pos.y <= 32.5);
+    }
+    assert_eq!(wall.remaining(&bot).count(), 2);
+
+    let (_, first) = wall.buildings[0];
+    bot.observe(
+        &observation(2, vec![raw_unit(1, UnitTypeId::Barracks, 1, first)]),
+        &[],
+    );
+    assert_eq!(wall.remaining(&bot).collect::<Vec<_>>(), vec![wall.buildings[1]]);
+}
+
+#[test]
+fn test_wall_with_gap() {
+    let (bot, choke) = choke_bot();
+    let wall = bot
+        .find_wall(
+            &choke,
+            INSIDE,
+            &[UnitTypeId::Barracks, UnitTypeId::SupplyDepot],
+            1,
+        )
+        .unwrap();
+
+    assert_eq!(wall.gap, 1);
+    assert!(passable(&bot, &wall));
+    assert!(wall
+        .buildings
+        .iter()
+        .all(|&(building, pos)| bot.can_place_local(building, pos)));
+    // Too small to close the choke
+    assert!(bot
+        .find_wall(
+            &choke,
+            INSIDE,
+            &[UnitTypeId::Barracks, UnitTypeId::SupplyDepot],
+            0
+        )
+        .is_none());
+}